@@ -0,0 +1,43 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use card_core::*;
+
+/// Deal a fixed set of random 7-card hands
+fn hands(count: usize) -> Vec<Vec<Card>> {
+    let mut rng = StdRng::seed_from_u64(7);
+    let deck: Vec<Card> = Deck::new().iter().cloned().collect();
+    (0..count).map(|_| deck.choose_multiple(&mut rng, 7).cloned().collect()).collect()
+}
+
+fn seven_card_evaluation(c: &mut Criterion) {
+    let hands = hands(10_000);
+    let sets: Vec<CardSet> = hands.iter().map(|h| h.iter().collect()).collect();
+    LookupEvaluator::init();
+
+    let mut group = c.benchmark_group("seven cards");
+    group.throughput(Throughput::Elements(hands.len() as u64));
+    group.bench_function("PokerEvaluator", |b| {
+        b.iter(|| hands.iter().map(|h| PokerEvaluator::evaluate(black_box(h)).unwrap().value()).max())
+    });
+    group.bench_function("LookupEvaluator", |b| {
+        b.iter(|| sets.iter().map(|s| LookupEvaluator::strength(black_box(s))).max())
+    });
+    group.finish();
+}
+
+fn board_enumeration(c: &mut Criterion) {
+    let hole: CardSet = hands(1)[0].iter().take(2).collect();
+    LookupEvaluator::init();
+
+    // all 1081 turn and river combinations for known hole cards and flop
+    c.bench_function("enumerate turn and river", |b| {
+        let flop: CardSet = Deck::new().iter().filter(|c| !hole.contains(c)).take(3).collect();
+        let known = hole.union(&flop);
+        b.iter(|| remaining_boards(&known, 2).map(|board| LookupEvaluator::strength(&known.union(&board))).max())
+    });
+}
+
+criterion_group!(benches, seven_card_evaluation, board_enumeration);
+criterion_main!(benches);
@@ -262,7 +262,7 @@ fn generate_and_deplete_deck() {
         loop {
             let total: HandTotal;
 
-            hand.push(deck.draw_nth(0).unwrap());
+            hand.push(deck.draw_bottom().unwrap());
             total = BlackjackEvaluator::hand_value(&hand);
 
             println!("Hand: {:?}", hand);
@@ -374,7 +374,7 @@ fn run_table_games() {
 }
 
 fn main() {
-    // first we just generate a deck and draw from front of the deck until it is depleted.
+    // first we just generate a deck and draw from the bottom of the deck until it is depleted.
     // each drawn card is added to a hand until the hand busts, the cards are then discarded to a discard pile
     // and the process continues until there are no cards left in the deck
     generate_and_deplete_deck();
@@ -0,0 +1,229 @@
+use crate::card_type::CardType;
+use crate::cards::Card;
+use crate::combinations::{rank_combination, unrank_combination, CardSetCombinations};
+
+/// A set of French cards stored as a 64-bit mask, using the ```CardType``` index of each card as its bit position.
+/// Jokers are all represented by the same bit, and cards without a suit that are not jokers cannot be held.
+///
+/// Card sets are cheap to copy and combine, which makes them well suited to representing dead cards, boards and the
+/// remaining cards of a deck in evaluators and simulations. Unlike a ```Deck```, a set has no order and cannot hold
+/// duplicates.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CardSet(u64);
+
+impl CardSet {
+    /// Create an empty set
+    pub fn new() -> Self {
+        CardSet(0)
+    }
+
+    /// Create a set holding the 52 cards of a standard deck
+    pub fn full() -> Self {
+        CardSet((1u64 << 52) - 1)
+    }
+
+    /// Create a set from its bit mask
+    pub fn from_bits(bits: u64) -> Self {
+        CardSet(bits & ((1u64 << Card::NUM_FACES) - 1))
+    }
+
+    /// Get the bit mask of the set
+    pub fn bits(&self) -> u64 {
+        self.0
+    }
+
+    /// Add a card to the set. Returns false if the card was already in the set, or cannot be held by a set
+    pub fn insert(&mut self, card: Card) -> bool {
+        let bit = CardSet::bit(&card);
+        let added = self.0 & bit == 0 && bit != 0;
+        self.0 |= bit;
+        added
+    }
+
+    /// Remove a card from the set. Returns false if the card was not in the set
+    pub fn remove(&mut self, card: &Card) -> bool {
+        let bit = CardSet::bit(card);
+        let removed = self.0 & bit != 0;
+        self.0 &= !bit;
+        removed
+    }
+
+    /// Returns true if the card is in the set
+    pub fn contains(&self, card: &Card) -> bool {
+        self.0 & CardSet::bit(card) != 0
+    }
+
+    /// The bit of a card, or 0 for cards that are not in the deck
+    fn bit(card: &Card) -> u64 {
+        match card.to_index() {
+            index if index < Card::NUM_FACES => 1u64 << index,
+            _ => 0,
+        }
+    }
+
+    /// Get the cards in both sets
+    pub fn intersection(&self, other: &CardSet) -> CardSet {
+        CardSet(self.0 & other.0)
+    }
+
+    /// Get the cards in either set
+    pub fn union(&self, other: &CardSet) -> CardSet {
+        CardSet(self.0 | other.0)
+    }
+
+    /// Get the cards in this set that are not in the other set
+    pub fn difference(&self, other: &CardSet) -> CardSet {
+        CardSet(self.0 & !other.0)
+    }
+
+    /// Returns true if the sets have no cards in common
+    pub fn is_disjoint(&self, other: &CardSet) -> bool {
+        self.0 & other.0 == 0
+    }
+
+    /// Get the number of cards in the set
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Returns true if the set holds no cards
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Iterate over the cards in the set, in index order
+    pub fn iter(&self) -> CardSetIter {
+        CardSetIter(self.0)
+    }
+
+    /// Iterate over all k-card subsets of the set
+    pub fn combinations(&self, k: usize) -> CardSetCombinations {
+        CardSetCombinations::new(self, k)
+    }
+
+    /// Iterate over all k-card subsets of the set in parallel
+    #[cfg(feature = "rayon")]
+    pub fn par_combinations(&self, k: usize) -> impl rayon::iter::ParallelIterator<Item = CardSet> {
+        crate::combinations::par_card_set_combinations(*self, k)
+    }
+
+    /// Get the rank of the set among all sets of the same size, based on the indices of its cards. Sets of k cards
+    /// drawn from a standard deck are ranked in the range ```0..binomial(52, k)```
+    pub fn combination_rank(&self) -> u64 {
+        let indices: Vec<usize> = self.iter().map(|c| c.to_index()).collect();
+        rank_combination(&indices)
+    }
+
+    /// Create the k-card set with the given rank. This is the inverse of ```combination_rank()``` for sets drawn from a
+    /// standard deck. Returns ```None``` if the rank is not in the range ```0..binomial(52, k)```
+    pub fn from_combination_rank(rank: u64, k: usize) -> Option<Self> {
+        let indices = unrank_combination(rank, 52, k)?;
+        Some(CardSet::from_bits(indices.iter().fold(0, |bits, i| bits | (1u64 << i))))
+    }
+}
+
+impl std::fmt::Display for CardSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, card) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", card)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::iter::FromIterator<Card> for CardSet {
+    fn from_iter<I: IntoIterator<Item = Card>>(cards: I) -> Self {
+        let mut set = CardSet::new();
+        for card in cards {
+            set.insert(card);
+        }
+        set
+    }
+}
+
+impl<'a> std::iter::FromIterator<&'a Card> for CardSet {
+    fn from_iter<I: IntoIterator<Item = &'a Card>>(cards: I) -> Self {
+        cards.into_iter().cloned().collect()
+    }
+}
+
+impl IntoIterator for CardSet {
+    type Item = Card;
+    type IntoIter = CardSetIter;
+
+    fn into_iter(self) -> CardSetIter {
+        self.iter()
+    }
+}
+
+/// Iterates over the cards of a ```CardSet```, in index order
+#[derive(Debug, Clone)]
+pub struct CardSetIter(u64);
+
+impl Iterator for CardSetIter {
+    type Item = Card;
+
+    fn next(&mut self) -> Option<Card> {
+        if self.0 == 0 {
+            return None;
+        }
+        let index = self.0.trailing_zeros() as usize;
+        self.0 &= self.0 - 1;
+        Card::from_index(index)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.count_ones() as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for CardSetIter {}
+
+#[cfg(test)]
+mod tests {
+    use super::CardSet;
+    use crate::cards::{Card, Rank, Suit};
+    use crate::deck::Deck;
+
+    #[test]
+    fn set_operations() {
+        let ace = Card::new();
+        let king = Card::from_suit_and_rank(Suit::Spades, Rank::King);
+        let mut set = CardSet::new();
+
+        assert!(set.insert(ace));
+        assert!(!set.insert(ace));
+        set.insert(Card::joker());
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&Card::joker()));
+        assert!(set.remove(&Card::joker()));
+        assert!(!set.remove(&king));
+
+        let unsuited = Card::from_suit_and_rank(Suit::None, Rank::Ace);
+        assert!(!set.insert(unsuited));
+        assert!(!set.contains(&unsuited));
+        assert!(!set.remove(&unsuited));
+        assert_eq!(set.len(), 1);
+
+        let full = CardSet::full();
+        assert_eq!(full.len(), 52);
+        assert_eq!(full.difference(&set).len(), 51);
+        assert_eq!(full.intersection(&set), set);
+        assert!(!full.is_disjoint(&set));
+        assert_eq!(set.union(&[king].iter().collect()).to_string(), "Ac Ks");
+    }
+
+    #[test]
+    fn from_deck() {
+        let set: CardSet = Deck::new().iter().collect();
+
+        assert_eq!(set, CardSet::full());
+        assert!(set.iter().eq(Deck::new().iter().cloned()));
+        assert_eq!(CardSet::from_bits(u64::MAX).len(), 53);
+    }
+}
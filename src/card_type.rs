@@ -0,0 +1,23 @@
+/// Describes a family of playing cards (French, Latin, German, Hanafuda, ...) so that the deck machinery can be shared
+/// between them.
+///
+/// Every distinct card face of a family maps to a unique index in the range ```0..NUM_FACES```. The index is used for
+/// compact representations of cards and collections, while ```standard_deck()``` describes the composition (and default
+/// order) of a single, freshly opened deck.
+pub trait CardType: Copy + PartialEq + std::fmt::Debug {
+    /// The number of distinct card faces in this family, i.e. the size of the index space used by ```to_index()```
+    const NUM_FACES: usize;
+
+    /// Convert a card to its unique index (0..NUM_FACES). Values that are not a face of the family (e.g. a trump number
+    /// out of range) map to an index of ```NUM_FACES``` or more, which ```from_index()``` rejects and a ```DeckSpec```
+    /// reports as foreign
+    fn to_index(&self) -> usize;
+
+    /// Convert an index back into a card. Returns ```None``` if the index is out of range
+    fn from_index(index: usize) -> Option<Self>;
+
+    /// Generate the cards of a single standard deck of this family, in default order
+    fn standard_deck() -> Vec<Self> {
+        (0..Self::NUM_FACES).filter_map(Self::from_index).collect()
+    }
+}
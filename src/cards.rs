@@ -1,5 +1,8 @@
+use crate::card_type::CardType;
+
 /// Represents the suit of a card and is able to express Clubs, Diamonds, Hearts, Spades or None
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Suit {
     Clubs,
     Diamonds,
@@ -42,10 +45,22 @@ impl Suit {
             _ => Suit::None,
         }
     }
+
+    /// Convert the character used to display a suit ('c', 'd', 'h' or 's', in either case) to a ```Suit``` variant
+    pub fn from_char(suit: char) -> Option<Self> {
+        match suit.to_ascii_lowercase() {
+            'c' => Some(Suit::Clubs),
+            'd' => Some(Suit::Diamonds),
+            'h' => Some(Suit::Hearts),
+            's' => Some(Suit::Spades),
+            _ => None,
+        }
+    }
 }
 
 /// Represents the rank of a card and can express values from Ace to King (0..12) as well as Joker (13..)
-#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Rank {
     Ace,
     Two,
@@ -63,6 +78,7 @@ pub enum Rank {
     Joker,
 }
 
+/// Ranks display as "A", "2" to "9", "T", "J", "Q", "K" and "JOKER"
 impl std::fmt::Display for Rank {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -72,7 +88,7 @@ impl std::fmt::Display for Rank {
             Rank::Queen => write!(f, "Q"),
             Rank::King => write!(f, "K"),
             Rank::Joker => write!(f, "JOKER"),
-            _ => write!(f, "{}", self.to_ordinal()),
+            _ => write!(f, "{}", self.to_ordinal() + 1),
         }
     }
 }
@@ -117,10 +133,25 @@ impl Rank {
             _ => Rank::Joker,
         }
     }
+
+    /// Convert the character used to display a rank ('A', '2' to '9', 'T', 'J', 'Q' or 'K', in either case) to a
+    /// ```Rank``` variant
+    pub fn from_char(rank: char) -> Option<Self> {
+        match rank.to_ascii_uppercase() {
+            'A' => Some(Rank::Ace),
+            'T' => Some(Rank::Ten),
+            'J' => Some(Rank::Jack),
+            'Q' => Some(Rank::Queen),
+            'K' => Some(Rank::King),
+            c @ '2'..='9' => Some(Rank::from_ordinal(c as u8 - b'1')),
+            _ => None,
+        }
+    }
 }
 
 /// Represents a playing card. A playing card is made of up a ```Suit``` and ```Rank```.
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Card {
     suit: Suit,
     rank: Rank,
@@ -182,11 +213,99 @@ impl Card {
     pub fn suit(&self) -> &Suit {
         &self.suit
     }
+
+    /// Construct a joker
+    pub fn joker() -> Self {
+        Card {
+            suit: Suit::None,
+            rank: Rank::Joker,
+        }
+    }
+
+    /// Returns true if this card is a joker
+    pub fn is_joker(&self) -> bool {
+        self.rank == Rank::Joker
+    }
+}
+
+/// The error returned when a string does not describe a card
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseCardError {
+    input: String,
+}
+
+impl std::fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid card '{}'", self.input)
+    }
+}
+
+impl std::error::Error for ParseCardError {}
+
+/// Parses cards in the format they are displayed in, i.e. the rank followed by the suit ("Ah", "Td", "2c"). "10" is
+/// accepted for tens, and "JOKER" for a joker.
+impl std::str::FromStr for Card {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseCardError { input: s.to_string() };
+        let trimmed = s.trim();
+        if trimmed.eq_ignore_ascii_case("JOKER") || trimmed.eq_ignore_ascii_case("JOKER-") {
+            return Ok(Card::joker());
+        }
+
+        let mut chars = trimmed.chars();
+        let suit = chars.next_back().and_then(Suit::from_char).ok_or_else(error)?;
+        let rank = match chars.as_str() {
+            "10" => Rank::Ten,
+            r if r.chars().count() == 1 => r.chars().next().and_then(Rank::from_char).ok_or_else(error)?,
+            _ => return Err(error()),
+        };
+        Ok(Card::from_suit_and_rank(suit, rank))
+    }
+}
+
+impl CardType for Card {
+    /// 52 suited cards followed by the joker
+    const NUM_FACES: usize = 53;
+
+    /// Suited cards are indexed by suit and rank (```suit * 13 + rank```), jokers always map to 52. Other cards without
+    /// a suit are not in the deck and map to 53
+    fn to_index(&self) -> usize {
+        if self.is_joker() {
+            return 52;
+        }
+        if self.suit == Suit::None {
+            return Self::NUM_FACES;
+        }
+        self.suit.to_ordinal() as usize * 13 + self.rank.to_ordinal() as usize
+    }
+
+    fn from_index(index: usize) -> Option<Self> {
+        match index {
+            0..=51 => Some(Card::from_ordinals((index / 13) as u8, (index % 13) as u8)),
+            52 => Some(Card::joker()),
+            _ => None,
+        }
+    }
+
+    /// A standard French deck does not include jokers. Those must be added separately.
+    fn standard_deck() -> Vec<Self> {
+        (0..52).filter_map(Self::from_index).collect()
+    }
+}
+
+/// Parse cards separated by whitespace, e.g. "Ah Kd 2c", for tests. Panics if a card is invalid
+#[cfg(test)]
+pub(crate) fn cards(cards: &str) -> Vec<Card> {
+    cards.split_whitespace().map(|c| c.parse().unwrap()).collect()
 }
 
 #[cfg(test)]
 mod tests {
     use super::{Card, Suit, Rank};
+    use crate::deck::Deck;
+    use crate::card_type::CardType;
 
     #[test]
     fn card_default() {
@@ -195,6 +314,13 @@ mod tests {
         assert_eq!(*c.suit(), Suit::Clubs);
     }
 
+    #[test]
+    fn rank_display() {
+        let ranks: Vec<String> = (0..14).map(|r| Rank::from_ordinal(r).to_string()).collect();
+        assert_eq!(ranks.join(" "), "A 2 3 4 5 6 7 8 9 T J Q K JOKER");
+        assert_eq!(Card::from_suit_and_rank(Suit::Hearts, Rank::Two).to_string(), "2h");
+    }
+
     #[test]
     fn card_from_ordinals() {
         assert_eq!(Card::from_ordinals(0, 12), Card::from_suit_and_rank(Suit::Clubs, Rank::King));
@@ -281,4 +407,35 @@ mod tests {
         assert_eq!(Rank::King.to_ordinal(), 12);
         assert_eq!(Rank::Joker.to_ordinal(), 13);
     }
+
+    #[test]
+    fn card_index_round_trip() {
+        for i in 0..Card::NUM_FACES {
+            assert_eq!(Card::from_index(i).unwrap().to_index(), i);
+        }
+        assert_eq!(Card::from_suit_and_rank(Suit::Diamonds, Rank::Two).to_index(), 14);
+        assert_eq!(Card::joker().to_index(), 52);
+        assert_eq!(Card::from_suit_and_rank(Suit::Hearts, Rank::Joker).to_index(), 52);
+        assert_eq!(Card::from_suit_and_rank(Suit::None, Rank::Ace).to_index(), 53);
+        assert_eq!(Card::from_index(53), None);
+        assert_eq!(Card::standard_deck().len(), 52);
+    }
+
+    #[test]
+    fn parse_cards() {
+        assert_eq!("Ah".parse(), Ok(Card::from_suit_and_rank(Suit::Hearts, Rank::Ace)));
+        assert_eq!("10d".parse(), Ok(Card::from_suit_and_rank(Suit::Diamonds, Rank::Ten)));
+        assert_eq!("tc".parse(), Ok(Card::from_suit_and_rank(Suit::Clubs, Rank::Ten)));
+        assert_eq!("JOKER".parse(), Ok(Card::joker()));
+        assert!("1c".parse::<Card>().is_err());
+        assert!("Ax".parse::<Card>().is_err());
+        assert!("".parse::<Card>().is_err());
+        assert_eq!("Kz".parse::<Card>().unwrap_err().to_string(), "invalid card 'Kz'");
+
+        // every card displays in a format that parses back to it
+        for card in Deck::new().iter().chain(std::iter::once(&Card::joker())) {
+            assert_eq!(card.to_string().parse(), Ok(*card));
+        }
+        assert_eq!(Card::from_suit_and_rank(Suit::Clubs, Rank::Two).to_string(), "2c");
+    }
 }
@@ -0,0 +1,285 @@
+use crate::card_set::CardSet;
+
+/// The number of combinations each parallel task enumerates sequentially
+#[cfg(feature = "rayon")]
+const PAR_CHUNK: u64 = 4096;
+
+/// The binomial coefficient ```n choose k```, i.e. the number of k-card combinations of n cards
+pub fn binomial(n: usize, k: usize) -> u64 {
+    if k > n {
+        return 0;
+    }
+    let k = k.min(n - k);
+    let mut result: u128 = 1;
+    for i in 0..k {
+        result = result * (n - i) as u128 / (i + 1) as u128;
+    }
+    result as u64
+}
+
+/// Calculate the rank of a combination in the combinatorial number system, i.e. its position in colexicographic order.
+/// ```indices``` must be strictly increasing. The rank of a k-combination of n items is in the range
+/// ```0..binomial(n, k)```.
+pub fn rank_combination(indices: &[usize]) -> u64 {
+    indices.iter().enumerate().map(|(i, c)| binomial(*c, i + 1)).sum()
+}
+
+/// Calculate the k-combination of n items with the given rank in the combinatorial number system. This is the inverse
+/// of ```rank_combination()```, returning strictly increasing indices. Returns ```None``` if the rank is not in the
+/// range ```0..binomial(n, k)```
+pub fn unrank_combination(mut rank: u64, n: usize, k: usize) -> Option<Vec<usize>> {
+    if rank >= binomial(n, k) {
+        return None;
+    }
+    let mut indices = vec![0; k];
+
+    for i in (0..k).rev() {
+        // find the largest c with binomial(c, i + 1) <= rank
+        let mut c = i;
+        while binomial(c + 1, i + 1) <= rank {
+            c += 1;
+        }
+        rank -= binomial(c, i + 1);
+        indices[i] = c;
+    }
+    Some(indices)
+}
+
+/// Advance a combination of indices (in ```0..n```) to the next one in colexicographic order. Returns false once all
+/// combinations have been visited
+fn next_colex(indices: &mut [usize], n: usize) -> bool {
+    let k = indices.len();
+    for i in 0..k {
+        let limit = if i + 1 < k { indices[i + 1] } else { n };
+        if indices[i] + 1 < limit {
+            indices[i] += 1;
+            for (j, index) in indices.iter_mut().enumerate().take(i) {
+                *index = j;
+            }
+            return true;
+        }
+    }
+    false
+}
+
+/// Iterates over all k-item combinations of a list of items (e.g. the cards of a ```Deck```), in colexicographic order
+/// of their positions, so the nth combination yielded has rank n (see ```rank_combination()```)
+#[derive(Debug, Clone)]
+pub struct Combinations<T: Copy> {
+    items: Vec<T>,
+    indices: Vec<usize>,
+    remaining: u64,
+}
+
+impl<T: Copy> Combinations<T> {
+    /// Create an iterator over the k-item combinations of ```items```
+    pub fn new(items: Vec<T>, k: usize) -> Self {
+        Combinations::starting_at(items, k, 0)
+    }
+
+    /// Create an iterator over the k-item combinations of ```items```, starting at the combination with the given rank.
+    /// This allows the enumeration to be split into ranges that are processed independently
+    pub fn starting_at(items: Vec<T>, k: usize, rank: u64) -> Self {
+        let remaining = binomial(items.len(), k).saturating_sub(rank);
+        let indices = unrank_combination(rank, items.len(), k).unwrap_or_default();
+        Combinations {
+            items,
+            indices,
+            remaining,
+        }
+    }
+}
+
+impl<T: Copy> Iterator for Combinations<T> {
+    type Item = Vec<T>;
+
+    fn next(&mut self) -> Option<Vec<T>> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let combination = self.indices.iter().map(|i| self.items[*i]).collect();
+        self.remaining -= 1;
+        if self.remaining > 0 {
+            next_colex(&mut self.indices, self.items.len());
+        }
+        Some(combination)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining as usize, Some(self.remaining as usize))
+    }
+}
+
+impl<T: Copy> ExactSizeIterator for Combinations<T> {}
+
+/// Iterates over all k-card subsets of a ```CardSet```, in colexicographic order of the cards' indices, without
+/// allocating per combination
+#[derive(Debug, Clone)]
+pub struct CardSetCombinations {
+    bits: Vec<u64>,
+    indices: Vec<usize>,
+    remaining: u64,
+}
+
+impl CardSetCombinations {
+    /// Create an iterator over the k-card subsets of ```set```
+    pub fn new(set: &CardSet, k: usize) -> Self {
+        CardSetCombinations::starting_at(set, k, 0)
+    }
+
+    /// Create an iterator over the k-card subsets of ```set```, starting at the subset with the given rank (relative to
+    /// the cards of ```set```)
+    pub fn starting_at(set: &CardSet, k: usize, rank: u64) -> Self {
+        let bits: Vec<u64> = (0..64).map(|i| 1u64 << i).filter(|bit| set.bits() & bit != 0).collect();
+        let remaining = binomial(bits.len(), k).saturating_sub(rank);
+        let indices = unrank_combination(rank, bits.len(), k).unwrap_or_default();
+        CardSetCombinations {
+            bits,
+            indices,
+            remaining,
+        }
+    }
+}
+
+impl Iterator for CardSetCombinations {
+    type Item = CardSet;
+
+    fn next(&mut self) -> Option<CardSet> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let mask = self.indices.iter().fold(0, |mask, i| mask | self.bits[*i]);
+        self.remaining -= 1;
+        if self.remaining > 0 {
+            next_colex(&mut self.indices, self.bits.len());
+        }
+        Some(CardSet::from_bits(mask))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining as usize, Some(self.remaining as usize))
+    }
+}
+
+impl ExactSizeIterator for CardSetCombinations {}
+
+/// Enumerate every k-card board that can still be dealt from a standard deck, given the dead (known) cards
+pub fn remaining_boards(dead: &CardSet, k: usize) -> CardSetCombinations {
+    CardSet::full().difference(dead).combinations(k)
+}
+
+/// Enumerate the k-item combinations of ```items``` in parallel, by splitting the ranks into chunks that are each
+/// enumerated sequentially
+#[cfg(feature = "rayon")]
+pub(crate) fn par_combinations<T: Copy + Send + Sync>(items: Vec<T>, k: usize) -> impl rayon::iter::ParallelIterator<Item = Vec<T>> {
+    use rayon::prelude::*;
+
+    let total = binomial(items.len(), k);
+    (0..total.div_ceil(PAR_CHUNK)).into_par_iter().flat_map_iter(move |chunk| {
+        Combinations::starting_at(items.clone(), k, chunk * PAR_CHUNK).take(PAR_CHUNK as usize)
+    })
+}
+
+/// Enumerate the k-card subsets of ```set``` in parallel
+#[cfg(feature = "rayon")]
+pub(crate) fn par_card_set_combinations(set: CardSet, k: usize) -> impl rayon::iter::ParallelIterator<Item = CardSet> {
+    use rayon::prelude::*;
+
+    let total = binomial(set.len(), k);
+    (0..total.div_ceil(PAR_CHUNK)).into_par_iter().flat_map_iter(move |chunk| {
+        CardSetCombinations::starting_at(&set, k, chunk * PAR_CHUNK).take(PAR_CHUNK as usize)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{binomial, rank_combination, remaining_boards, unrank_combination, CardSetCombinations, Combinations};
+    use crate::card_set::CardSet;
+    use crate::cards::Card;
+    use crate::deck::Deck;
+
+    #[test]
+    fn binomials() {
+        assert_eq!(binomial(52, 5), 2_598_960);
+        assert_eq!(binomial(52, 7), 133_784_560);
+        assert_eq!(binomial(5, 0), 1);
+        assert_eq!(binomial(3, 4), 0);
+    }
+
+    #[test]
+    fn combinations_are_ranked_in_order() {
+        let combinations: Vec<Vec<usize>> = Combinations::new((0..7).collect(), 3).collect();
+
+        assert_eq!(combinations.len(), 35);
+        assert_eq!(combinations[0], vec![0, 1, 2]);
+        assert_eq!(combinations[1], vec![0, 1, 3]);
+        assert_eq!(combinations[34], vec![4, 5, 6]);
+        for (rank, combination) in combinations.iter().enumerate() {
+            assert_eq!(rank_combination(combination), rank as u64);
+            assert_eq!(unrank_combination(rank as u64, 7, 3).as_ref(), Some(combination));
+        }
+        assert_eq!(unrank_combination(35, 7, 3), None);
+        assert_eq!(unrank_combination(0, 2, 3), None);
+        assert_eq!(unrank_combination(0, 7, 0), Some(Vec::new()));
+    }
+
+    #[test]
+    fn deck_combinations() {
+        let deck = Deck::new();
+        let mut hands = deck.combinations(2);
+
+        assert_eq!(hands.len(), 1326);
+        assert_eq!(hands.next(), Some(vec![Card::from_ordinals(0, 0), Card::from_ordinals(0, 1)]));
+        assert_eq!(Deck::new_empty().combinations(2).count(), 0);
+        assert_eq!(deck.combinations(0).count(), 1);
+    }
+
+    #[test]
+    fn card_set_combinations() {
+        let set: CardSet = Deck::new().iter().take(6).collect();
+        let subsets: Vec<CardSet> = set.combinations(4).collect();
+
+        assert_eq!(subsets.len(), 15);
+        assert!(subsets.iter().all(|s| s.len() == 4 && s.difference(&set).is_empty()));
+        for (rank, subset) in subsets.iter().enumerate() {
+            assert_eq!(subset.combination_rank(), rank as u64);
+            assert_eq!(CardSet::from_combination_rank(rank as u64, 4), Some(*subset));
+        }
+        assert!(CardSet::from_combination_rank(binomial(52, 4) - 1, 4).is_some());
+        assert_eq!(CardSet::from_combination_rank(binomial(52, 4), 4), None);
+        assert_eq!(CardSet::from_combination_rank(u64::MAX, 4), None);
+    }
+
+    #[test]
+    fn boards_with_dead_cards() {
+        let dead: CardSet = Deck::new().iter().take(4).collect();
+        let boards = remaining_boards(&dead, 5);
+
+        assert_eq!(boards.len(), 1_712_304);
+        assert!(remaining_boards(&dead, 2).all(|b| b.is_disjoint(&dead)));
+    }
+
+    #[test]
+    fn starting_at_rank() {
+        let all: Vec<Vec<usize>> = Combinations::new((0..8).collect(), 4).collect();
+        let rest: Vec<Vec<usize>> = Combinations::starting_at((0..8).collect(), 4, 30).collect();
+
+        assert_eq!(rest, all[30..].to_vec());
+        assert_eq!(Combinations::starting_at((0..8).collect(), 4, 70).count(), 0);
+        assert_eq!(CardSetCombinations::starting_at(&CardSet::full(), 2, 1000).count(), 326);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_enumeration() {
+        use rayon::prelude::*;
+
+        let dead: CardSet = Deck::new().iter().take(4).collect();
+        let remaining = CardSet::full().difference(&dead);
+        let sequential: u64 = remaining.combinations(3).map(|s| s.bits() % 1009).sum();
+        let parallel: u64 = remaining.par_combinations(3).map(|s| s.bits() % 1009).sum();
+
+        assert_eq!(sequential, parallel);
+        assert_eq!(Deck::new().par_combinations(2).count(), 1326);
+    }
+}
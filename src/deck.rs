@@ -1,28 +1,58 @@
 use std::collections::VecDeque;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use crate::card_type::CardType;
 use crate::cards::Card;
+use crate::combinations::Combinations;
+use crate::deck_builder::DeckBuilderError;
+use crate::deck_spec::{DeckSpec, ValidationReport};
+use crate::history::{DeckEvent, DeckHistory, HistoryClock};
+use crate::probability::DrawOdds;
+use crate::sha256::sha256;
 
-/// A deck is a collection of 52 cards (Aces through to Kings) of each of the four suits (Clubs, Diamonds, Heart and Spades).
+/// A deck is an ordered collection of cards of any ```CardType```. By default this is the French ```Card```, and a
+/// standard deck is made of 52 cards (Aces through to Kings) of each of the four suits (Clubs, Diamonds, Heart and Spades).
 /// Decks can have cards added to- and removed from them.
 ///
-/// To remove cards from the deck, call either the ```.draw()``` or ```.draw_nth()``` methods. ```draw()``` will remove a card
-/// from the end of the deck while ```draw_nth()``` allows the caller to remove a card from any valid position within the deck.
+/// A deck has a top and a bottom. The top is the end that ```draw()``` removes cards from and ```add()``` places cards on,
+/// and positions (as used by ```draw_nth()```, ```iter()``` and ```from_cards()```) are counted from the bottom. A freshly
+/// made standard deck therefore has the Ace of Clubs at the bottom and the King of Spades on top.
+///
+/// To state intent explicitly, use ```draw_top()```/```draw_bottom()``` and ```put_on_top()```/```put_on_bottom()```, and
+/// pass a ```DeckOrientation``` when converting between decks and lists of cards.
+///
+/// Every mutation of a deck can optionally be recorded in a ```DeckHistory``` (see ```enable_history()```).
 #[derive(Debug, Clone)]
-pub struct Deck {
-    cards: VecDeque<Card>,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Deck<C: CardType = Card> {
+    cards: VecDeque<C>,
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    history: Option<DeckHistory<C>>,
 }
 
-impl Default for Deck {
-    /// Generate a deck of 52 cards, ordered by Suit (Clubs, Diamonds, Hearts, Spades) and rank (Ace to King).
-    /// This method will not add jokers to the deck. Those must be added separately.
-    fn default() -> Self {
-        let mut deck = VecDeque::with_capacity(52);
+/// Describes the order in which a list of cards is given or returned, relative to the top of the deck
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DeckOrientation {
+    /// The first card in the list is the top card of the deck, i.e. the list is in dealing order
+    TopFirst,
+    /// The first card in the list is the bottom card of the deck
+    BottomFirst,
+}
 
-        for i in 0..52 {
-            deck.push_back(Card::from_ordinals(i/13, i%13));
-        }
+/// The order of the cards of a deck at some point, created by ```Deck::snapshot()```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeckSnapshot<C: CardType = Card> {
+    cards: Vec<C>,
+}
 
+impl<C: CardType> Default for Deck<C> {
+    /// Generate a single standard deck of the card type in its default order (see ```CardType::standard_deck()```).
+    /// For French cards that is 52 cards, ordered by Suit (Clubs, Diamonds, Hearts, Spades) and rank (Ace to King).
+    /// This method will not add jokers to the deck. Those must be added separately.
+    fn default() -> Self {
         Deck {
-            cards: deck,
+            cards: C::standard_deck().into_iter().collect(),
+            history: None,
         }
     }
 }
@@ -35,41 +65,303 @@ impl Deck {
 
     /// Create an empty deck of cards
     pub fn new_empty() -> Self {
+        Deck::empty()
+    }
+}
+
+impl<C: CardType> Deck<C> {
+    /// Create an empty deck of any card type
+    pub fn empty() -> Self {
+        Deck {
+            cards: VecDeque::with_capacity(C::NUM_FACES),
+            history: None,
+        }
+    }
+
+    /// Create a deck from a list of cards. The first card in the list is at the bottom of the deck
+    pub fn from_cards(cards: Vec<C>) -> Self {
         Deck {
-            cards: VecDeque::with_capacity(52),
+            cards: cards.into(),
+            history: None,
         }
     }
 
-    /// Draw the card at the end of the deck
-    pub fn draw(&mut self) -> Option<Card> {
-        self.cards.pop_back()
+    /// Create a deck from a list of cards given in the stated orientation
+    pub fn from_cards_oriented(mut cards: Vec<C>, orientation: DeckOrientation) -> Self {
+        if orientation == DeckOrientation::TopFirst {
+            cards.reverse();
+        }
+        Deck::from_cards(cards)
+    }
+
+    /// Create a stacked deck for testing specific game situations. The ```top``` cards are placed on top of the deck so
+    /// that successive calls to ```draw_top()``` return them in the given order, followed by the cards of ```rest```.
+    /// One copy of each stacked card is taken out of ```rest```, so a stacked standard deck still contains each card
+    /// exactly once. Returns an error if ```rest``` does not hold a stacked card (or holds fewer copies of it than are
+    /// stacked). Shuffle ```rest``` beforehand if the remaining cards should be in random order.
+    pub fn stacked(top: &[C], mut rest: Deck<C>) -> Result<Self, DeckBuilderError<C>> {
+        for card in top {
+            if !rest.remove_card(card) {
+                return Err(DeckBuilderError::StackedCardNotInDeck(*card));
+            }
+        }
+        for card in top.iter().rev() {
+            rest.put_on_top(*card);
+        }
+        Ok(rest)
     }
 
-    /// Draw a card from the nth position
-    pub fn draw_nth(&mut self, n: usize) -> Option<Card> {
-        self.cards.remove(n)
+    /// Draw the card on top of the deck. This is the same as ```draw_top()```
+    pub fn draw(&mut self) -> Option<C> {
+        self.draw_top()
     }
 
-    /// Add a card to the deck
-    pub fn add(&mut self, card: Card) {
+    /// Draw the card on top of the deck
+    pub fn draw_top(&mut self) -> Option<C> {
+        self.draw_nth(self.len().checked_sub(1)?)
+    }
+
+    /// Draw the card at the bottom of the deck
+    pub fn draw_bottom(&mut self) -> Option<C> {
+        self.draw_nth(0)
+    }
+
+    /// Look at the card on top of the deck without removing it
+    pub fn peek_top(&self) -> Option<&C> {
+        self.cards.back()
+    }
+
+    /// Look at the card at the bottom of the deck without removing it
+    pub fn peek_bottom(&self) -> Option<&C> {
+        self.cards.front()
+    }
+
+    /// Draw a card from the nth position, counted from the bottom of the deck
+    pub fn draw_nth(&mut self, n: usize) -> Option<C> {
+        let card = self.cards.remove(n)?;
+        self.record(|| DeckEvent::Draw { card, position: n });
+        Some(card)
+    }
+
+    /// Remove the first occurrence of a card from the deck. Returns false if the card is not in the deck
+    pub fn remove_card(&mut self, card: &C) -> bool {
+        match self.cards.iter().position(|c| c == card) {
+            Some(index) => self.draw_nth(index).is_some(),
+            None => false,
+        }
+    }
+
+    /// Add a card to the deck. This is the same as ```put_on_top()```
+    pub fn add(&mut self, card: C) {
+        self.put_on_top(card);
+    }
+
+    /// Place a card on top of the deck
+    pub fn put_on_top(&mut self, card: C) {
         self.cards.push_back(card);
+        let position = self.len() - 1;
+        self.record(|| DeckEvent::Add { card, position });
+    }
+
+    /// Place a card at the bottom of the deck
+    pub fn put_on_bottom(&mut self, card: C) {
+        self.cards.push_front(card);
+        self.record(|| DeckEvent::Add { card, position: 0 });
+    }
+
+    /// Insert a card at the nth position, counted from the bottom of the deck. Positions beyond the top of the deck
+    /// place the card on top
+    pub fn insert_nth(&mut self, n: usize, card: C) {
+        let position = n.min(self.len());
+        self.cards.insert(position, card);
+        self.record(|| DeckEvent::Add { card, position });
     }
 
-    /// Add an entire deck to this deck
+    /// Add an entire deck on top of this deck, keeping its order. The other deck is left empty
     pub fn add_deck(&mut self, deck: &mut Self) {
+        let position = self.len();
+        let count = deck.len();
+
+        if self.history.is_some() {
+            let cards = deck.cards.iter().cloned().collect();
+            self.record(|| DeckEvent::AddDeck { cards, position });
+        }
+        deck.record(|| DeckEvent::Emptied { count });
         self.cards.append(&mut deck.cards)
     }
 
+    /// Cut the deck by moving the top ```count``` cards to the bottom of the deck, keeping their order
+    pub fn cut(&mut self, count: usize) {
+        let count = count.min(self.len());
+        self.cards.rotate_right(count);
+        self.record(|| DeckEvent::Cut { count });
+    }
+
+    /// Shuffle the deck using the thread-local random number generator
+    pub fn shuffle(&mut self) {
+        self.shuffle_with(&mut rand::thread_rng());
+    }
+
+    /// Shuffle the deck using the supplied random number generator. Use a seeded generator to get a reproducible order
+    pub fn shuffle_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        self.cards.make_contiguous().shuffle(rng);
+        if self.history.is_some() {
+            let order = self.cards.iter().cloned().collect();
+            self.record(|| DeckEvent::Shuffle { order });
+        }
+    }
+
+    /// Replace the order of the deck, e.g. when undoing a shuffle or restoring a snapshot
+    pub(crate) fn reorder(&mut self, order: Vec<C>) {
+        self.cards = order.into();
+        if self.history.is_some() {
+            let order = self.cards.iter().cloned().collect();
+            self.record(|| DeckEvent::Shuffle { order });
+        }
+    }
+
+    /// Take a snapshot of the order of the cards. Only the cards are copied, not the history, so this is much cheaper
+    /// than cloning a deck with a history. To undo single moves between several decks, ```Zones``` journals the moves
+    /// instead
+    pub fn snapshot(&self) -> DeckSnapshot<C> {
+        DeckSnapshot {
+            cards: self.cards.iter().cloned().collect(),
+        }
+    }
+
+    /// Put the cards back into the order of a snapshot. The history (if enabled) records this as a reordering of the
+    /// deck (```DeckEvent::Shuffle```), so it still accounts for every card
+    pub fn restore(&mut self, snapshot: &DeckSnapshot<C>) {
+        self.reorder(snapshot.cards.clone());
+    }
+
+    /// Start recording every mutation of the deck in a history with its own clock. Any existing history is replaced
+    pub fn enable_history(&mut self) {
+        self.enable_history_with_clock(HistoryClock::new());
+    }
+
+    /// Start recording every mutation of the deck, numbering records from a clock shared with other decks
+    pub fn enable_history_with_clock(&mut self, clock: HistoryClock) {
+        self.history = Some(DeckHistory::new(clock));
+    }
+
+    /// Stop recording mutations, returning the history recorded so far
+    pub fn disable_history(&mut self) -> Option<DeckHistory<C>> {
+        self.history.take()
+    }
+
+    /// Get the history of the deck, if it is enabled
+    pub fn history(&self) -> Option<&DeckHistory<C>> {
+        self.history.as_ref()
+    }
+
+    fn record<F: FnOnce() -> DeckEvent<C>>(&mut self, event: F) {
+        if let Some(history) = self.history.as_mut() {
+            history.record(event());
+        }
+    }
+
+    /// Iterate over the cards in the deck, from the bottom to the top of the deck
+    pub fn iter(&self) -> impl Iterator<Item = &C> {
+        self.cards.iter()
+    }
+
+    /// Get a copy of the cards in the deck, listed in the stated orientation
+    pub fn to_vec(&self, orientation: DeckOrientation) -> Vec<C> {
+        match orientation {
+            DeckOrientation::TopFirst => self.cards.iter().rev().cloned().collect(),
+            DeckOrientation::BottomFirst => self.cards.iter().cloned().collect(),
+        }
+    }
+
+    /// Get the next ```n``` cards that ```draw_top()``` would return, in the order they would be drawn, without removing them
+    pub fn peek_draws(&self, n: usize) -> Vec<C> {
+        self.cards.iter().rev().take(n).cloned().collect()
+    }
+
+    /// Assert that the next cards drawn from the deck will be ```expected```, in order. Panics with a description of
+    /// both sequences otherwise. Intended for tests using stacked decks.
+    pub fn assert_draws(&self, expected: &[C]) {
+        let actual = self.peek_draws(expected.len());
+        if actual != expected {
+            panic!("deck would draw {:?}, expected {:?}", actual, expected);
+        }
+    }
+
+    /// Iterate over all k-card combinations of the cards in this deck
+    pub fn combinations(&self, k: usize) -> Combinations<C> {
+        Combinations::new(self.cards.iter().cloned().collect(), k)
+    }
+
+    /// Iterate over all k-card combinations of the cards in this deck in parallel
+    #[cfg(feature = "rayon")]
+    pub fn par_combinations(&self, k: usize) -> impl rayon::iter::ParallelIterator<Item = Vec<C>>
+    where
+        C: Send + Sync,
+    {
+        crate::combinations::par_combinations(self.cards.iter().cloned().collect(), k)
+    }
+
+    /// Get the odds of drawing cards that match a predicate from the cards remaining in this deck
+    pub fn odds<P: Fn(&C) -> bool>(&self, predicate: P) -> DrawOdds {
+        DrawOdds::from_cards(self.iter().copied(), predicate)
+    }
+
+    /// Check the cards in this deck against an expected composition, reporting missing, duplicate and foreign cards
+    pub fn validate(&self, spec: &DeckSpec<C>) -> ValidationReport<C> {
+        spec.validate(self.iter())
+    }
+
+    /// Serialize the order of the deck into a canonical byte sequence. Each card is written as its ```CardType``` index,
+    /// from the bottom to the top of the deck, using one byte per card (or two big-endian bytes for card types with more
+    /// than 256 faces). Returns ```None``` if the deck holds a card that is not a face of its card type (e.g. a French
+    /// card without a suit that is not a joker), as such cards have no index of their own.
+    pub fn to_bytes(&self) -> Option<Vec<u8>> {
+        let wide = C::NUM_FACES > 256;
+        let mut bytes = Vec::with_capacity(self.len() * if wide { 2 } else { 1 });
+
+        for card in self.cards.iter() {
+            let index = card.to_index();
+            if index >= C::NUM_FACES {
+                return None;
+            }
+            if wide {
+                bytes.extend_from_slice(&(index as u16).to_be_bytes());
+            } else {
+                bytes.push(index as u8);
+            }
+        }
+        Some(bytes)
+    }
+
+    /// Calculate the SHA-256 fingerprint of the order of the deck (see ```to_bytes()```). Returns ```None``` if the deck
+    /// holds a card that is not a face of its card type
+    pub fn fingerprint(&self) -> Option<[u8; 32]> {
+        self.to_bytes().map(|bytes| sha256(&bytes))
+    }
+
     /// Get the number of cards in this deck
     pub fn len(&self) -> usize {
         self.cards.len()
     }
+
+    /// Returns true if there are no cards left in the deck
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Deck;
-    use crate::cards::{Rank, Suit};
+    use super::{Deck, DeckOrientation};
+    use crate::cards::{Card, Rank, Suit};
+    use crate::deck_builder::{DeckBuilder, DeckBuilderError};
+    use crate::deck_spec::DeckSpec;
+    use crate::history::{DeckEvent, HistoryClock};
+    use crate::german::GermanCard;
+    use crate::latin::LatinCard;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
 
     #[test]
     fn deck_default() {
@@ -131,4 +423,201 @@ mod tests {
         assert_eq!(d.len(), 49);
         assert_eq!(c, None);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn generic_decks() {
+        let mut latin = Deck::<LatinCard>::default();
+        let mut skat = Deck::<GermanCard>::default();
+
+        assert_eq!(latin.len(), 40);
+        assert_eq!(skat.len(), 32);
+
+        let mut second = Deck::<GermanCard>::default();
+        skat.add_deck(&mut second);
+        assert_eq!(skat.len(), 64);
+        assert!(second.is_empty());
+
+        latin.draw();
+        assert_eq!(latin.len(), 39);
+        assert!(Deck::<LatinCard>::empty().is_empty());
+    }
+
+    #[test]
+    fn shuffle_with_seed() {
+        let mut a = Deck::new();
+        let mut b = Deck::new();
+
+        a.shuffle_with(&mut StdRng::seed_from_u64(42));
+        b.shuffle_with(&mut StdRng::seed_from_u64(42));
+
+        assert_eq!(a.len(), 52);
+        assert!(a.iter().eq(b.iter()));
+        assert!(!a.iter().eq(Deck::new().iter()));
+    }
+
+    #[test]
+    fn validate() {
+        let mut d = Deck::new();
+        assert!(d.validate(&DeckSpec::default()).is_valid());
+
+        d.add(Card::joker());
+        assert_eq!(d.validate(&DeckSpec::default()).foreign, vec![(Card::joker(), 1)]);
+    }
+
+    #[test]
+    fn canonical_bytes_and_fingerprint() {
+        let mut d = Deck::new();
+        let bytes = d.to_bytes().unwrap();
+
+        assert_eq!(bytes.len(), 52);
+        assert_eq!(bytes[..3], [0, 1, 2]);
+        assert_eq!(bytes[51], 51);
+
+        let fingerprint = d.fingerprint();
+        assert_eq!(fingerprint, Deck::new().fingerprint());
+        d.draw();
+        assert_ne!(fingerprint, d.fingerprint());
+
+        // cards without an index of their own cannot be told apart
+        let unsuited = |rank| Deck::from_cards(vec![Card::from_suit_and_rank(Suit::None, rank)]);
+        assert_eq!(unsuited(Rank::Ace).to_bytes(), None);
+        assert_eq!(unsuited(Rank::King).fingerprint(), None);
+        assert_eq!(Deck::from_cards(vec![Card::joker()]).to_bytes(), Some(vec![52]));
+    }
+
+    #[test]
+    fn stacked() {
+        let eight = Card::from_suit_and_rank(Suit::Hearts, Rank::Eight);
+        let six = Card::from_suit_and_rank(Suit::Clubs, Rank::Six);
+        let top = [eight, six];
+        let mut d = Deck::stacked(&top, Deck::new()).unwrap();
+
+        assert_eq!(d.len(), 52);
+        assert!(d.validate(&DeckSpec::default()).is_valid());
+        d.assert_draws(&top);
+        assert_eq!(d.draw(), Some(eight));
+        assert_eq!(d.draw(), Some(six));
+        assert_eq!(d.draw(), Some(Card::from_suit_and_rank(Suit::Spades, Rank::King)));
+
+        assert_eq!(Deck::stacked(&[eight, eight], Deck::new()).unwrap_err(), DeckBuilderError::StackedCardNotInDeck(eight));
+        assert_eq!(Deck::stacked(&[Card::joker()], Deck::new()).unwrap_err(), DeckBuilderError::StackedCardNotInDeck(Card::joker()));
+    }
+
+    #[test]
+    #[should_panic(expected = "expected")]
+    fn assert_draws_panics() {
+        Deck::new().assert_draws(&[Card::new()]);
+    }
+
+    #[test]
+    fn remove_card() {
+        let mut d = Deck::new();
+
+        assert!(d.remove_card(&Card::new()));
+        assert!(!d.remove_card(&Card::new()));
+        assert_eq!(d.len(), 51);
+    }
+
+    #[test]
+    fn top_and_bottom() {
+        let ace_clubs = Card::from_suit_and_rank(Suit::Clubs, Rank::Ace);
+        let king_spades = Card::from_suit_and_rank(Suit::Spades, Rank::King);
+        let mut d = Deck::new();
+
+        assert_eq!(d.peek_top(), Some(&king_spades));
+        assert_eq!(d.peek_bottom(), Some(&ace_clubs));
+        assert_eq!(d.draw_bottom(), Some(ace_clubs));
+        assert_eq!(d.draw_top(), Some(king_spades));
+
+        d.put_on_bottom(king_spades);
+        d.put_on_top(ace_clubs);
+        assert_eq!(d.len(), 52);
+        assert_eq!(d.draw_nth(0), Some(king_spades));
+        assert_eq!(d.draw(), Some(ace_clubs));
+    }
+
+    #[test]
+    fn orientation() {
+        let d = Deck::new();
+        let top_first = d.to_vec(DeckOrientation::TopFirst);
+
+        assert_eq!(top_first[0], Card::from_suit_and_rank(Suit::Spades, Rank::King));
+        assert_eq!(d.to_vec(DeckOrientation::BottomFirst)[0], Card::new());
+        assert_eq!(d.peek_draws(3), top_first[..3].to_vec());
+
+        let mut rebuilt = Deck::from_cards_oriented(top_first, DeckOrientation::TopFirst);
+        assert_eq!(rebuilt.fingerprint(), d.fingerprint());
+        assert_eq!(rebuilt.draw_top(), Some(Card::from_suit_and_rank(Suit::Spades, Rank::King)));
+    }
+
+    #[test]
+    fn insert_nth() {
+        let mut d = Deck::new();
+        let card = d.draw_nth(5).unwrap();
+
+        d.insert_nth(5, card);
+        assert!(d.iter().eq(Deck::new().iter()));
+        d.insert_nth(100, card);
+        assert_eq!(d.peek_top(), Some(&card));
+    }
+
+    #[test]
+    fn cut() {
+        let mut d = Deck::new();
+        d.cut(2);
+
+        assert_eq!(d.peek_bottom(), Some(&Card::from_suit_and_rank(Suit::Spades, Rank::Queen)));
+        assert_eq!(d.peek_top(), Some(&Card::from_suit_and_rank(Suit::Spades, Rank::Jack)));
+        assert_eq!(d.len(), 52);
+    }
+
+    #[test]
+    fn snapshot_and_restore() {
+        let mut shoe = DeckBuilder::new().copies(6).build().unwrap();
+        shoe.enable_history();
+        let snapshot = shoe.snapshot();
+        let king = shoe.draw().unwrap();
+        shoe.cut(100);
+
+        shoe.restore(&snapshot);
+        assert_eq!(shoe.len(), 312);
+        assert_eq!(shoe.peek_top(), Some(&king));
+        assert_eq!(shoe.snapshot(), snapshot);
+        let history = shoe.history().unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.records()[2].event, DeckEvent::Shuffle { order: shoe.iter().cloned().collect() });
+    }
+
+    #[test]
+    fn history() {
+        let clock = HistoryClock::new();
+        let mut shoe = Deck::new();
+        let mut hand = Deck::new_empty();
+        shoe.enable_history_with_clock(clock.clone());
+        hand.enable_history_with_clock(clock);
+
+        let card = shoe.draw().unwrap();
+        hand.add(card);
+        shoe.cut(10);
+        shoe.shuffle_with(&mut StdRng::seed_from_u64(1));
+        shoe.add_deck(&mut hand);
+
+        let shoe_history = shoe.history().unwrap();
+        let hand_history = hand.history().unwrap();
+        assert_eq!(shoe_history.len(), 4);
+        assert_eq!(shoe_history.records()[0].event, DeckEvent::Draw { card, position: 51 });
+        assert_eq!(shoe_history.records()[1].event, DeckEvent::Cut { count: 10 });
+        assert_eq!(shoe_history.records()[3].event, DeckEvent::AddDeck { cards: vec![card], position: 51 });
+        assert_eq!(hand_history.records()[0].event, DeckEvent::Add { card, position: 0 });
+        assert_eq!(hand_history.records()[1].event, DeckEvent::Emptied { count: 1 });
+
+        let mut sequences: Vec<u64> = shoe_history.records().iter().chain(hand_history.records()).map(|r| r.sequence).collect();
+        sequences.sort();
+        assert_eq!(sequences, (0..6).collect::<Vec<u64>>());
+        assert_eq!(shoe_history.records_for(&card).count(), 2);
+
+        assert!(shoe.disable_history().is_some());
+        shoe.draw();
+        assert!(shoe.history().is_none());
+    }
+}
@@ -0,0 +1,285 @@
+use rand::Rng;
+use crate::card_type::CardType;
+use crate::cards::{Card, Rank, Suit};
+use crate::deck::Deck;
+use crate::deck_spec::DeckSpec;
+
+const ALL_SUITS: [Suit; 4] = [Suit::Clubs, Suit::Diamonds, Suit::Hearts, Suit::Spades];
+
+const ALL_RANKS: [Rank; 13] = [
+    Rank::Ace, Rank::Two, Rank::Three, Rank::Four, Rank::Five, Rank::Six, Rank::Seven,
+    Rank::Eight, Rank::Nine, Rank::Ten, Rank::Jack, Rank::Queen, Rank::King,
+];
+
+/// The error returned when a ```DeckBuilder``` cannot build its deck, or ```Deck::stacked()``` cannot stack its cards
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DeckBuilderError<C: CardType = Card> {
+    /// A card stacked on top of the deck is not in its composition (or is stacked more times than the deck holds it)
+    StackedCardNotInDeck(C),
+}
+
+impl<C: CardType + std::fmt::Display> std::fmt::Display for DeckBuilderError<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeckBuilderError::StackedCardNotInDeck(card) => write!(f, "the stacked card {} is not in the deck", card),
+        }
+    }
+}
+
+impl<C: CardType + std::fmt::Display> std::error::Error for DeckBuilderError<C> {}
+
+/// Use this to build French decks with a composition other than the standard 52 cards, such as the reduced decks used
+/// for Piquet, Euchre or Durak, doubled decks like Pinochle, multi-deck shoes or decks with jokers.
+///
+/// The generated deck is ordered the same way as ```Deck::default()```: copy by copy, then by suit and rank, with any
+/// jokers added on top of the deck. The deck can optionally be shuffled, and a sequence of cards can be stacked on top of
+/// the deck (see ```Deck::stacked()```) to test specific game situations.
+#[derive(Debug, Clone)]
+pub struct DeckBuilder {
+    suits: Vec<Suit>,
+    ranks: Vec<Rank>,
+    copies: usize,
+    jokers: usize,
+    top: Vec<Card>,
+    shuffled: bool,
+}
+
+impl Default for DeckBuilder {
+    /// A builder for a single standard deck of 52 cards without jokers
+    fn default() -> Self {
+        DeckBuilder {
+            suits: ALL_SUITS.to_vec(),
+            ranks: ALL_RANKS.to_vec(),
+            copies: 1,
+            jokers: 0,
+            top: Vec::new(),
+            shuffled: false,
+        }
+    }
+}
+
+impl DeckBuilder {
+    /// Create a builder for a single standard deck of 52 cards
+    pub fn new() -> Self {
+        DeckBuilder::default()
+    }
+
+    /// 32 cards, Seven to Ace of each suit, as used for Piquet, Belote and French-suited Skat
+    pub fn piquet() -> Self {
+        DeckBuilder::new().ranks(&[
+            Rank::Ace, Rank::Seven, Rank::Eight, Rank::Nine, Rank::Ten, Rank::Jack, Rank::Queen, Rank::King,
+        ])
+    }
+
+    /// 32 cards, Seven to Ace of each suit. This is the same composition as ```DeckBuilder::piquet()```
+    pub fn skat() -> Self {
+        DeckBuilder::piquet()
+    }
+
+    /// 24 cards, Nine to Ace of each suit
+    pub fn euchre() -> Self {
+        DeckBuilder::new().ranks(&[Rank::Ace, Rank::Nine, Rank::Ten, Rank::Jack, Rank::Queen, Rank::King])
+    }
+
+    /// 48 cards, two copies of Nine to Ace of each suit
+    pub fn pinochle() -> Self {
+        DeckBuilder::euchre().copies(2)
+    }
+
+    /// 36 cards, Six to Ace of each suit, as used for short-deck (6+) Hold'em and Durak
+    pub fn short_deck() -> Self {
+        DeckBuilder::new().without_ranks(&[Rank::Two, Rank::Three, Rank::Four, Rank::Five])
+    }
+
+    /// 40 cards, the standard deck without the Eights, Nines and Tens
+    pub fn forty_card() -> Self {
+        DeckBuilder::new().without_ranks(&[Rank::Eight, Rank::Nine, Rank::Ten])
+    }
+
+    /// Only include cards of the given suits
+    pub fn suits(mut self, suits: &[Suit]) -> Self {
+        self.suits = suits.iter().filter(|s| **s != Suit::None).cloned().collect();
+        self
+    }
+
+    /// Only include cards of the given ranks
+    pub fn ranks(mut self, ranks: &[Rank]) -> Self {
+        self.ranks = ranks.iter().filter(|r| **r != Rank::Joker).cloned().collect();
+        self
+    }
+
+    /// Exclude cards of the given suits
+    pub fn without_suits(mut self, suits: &[Suit]) -> Self {
+        self.suits.retain(|s| !suits.contains(s));
+        self
+    }
+
+    /// Exclude cards of the given ranks
+    pub fn without_ranks(mut self, ranks: &[Rank]) -> Self {
+        self.ranks.retain(|r| !ranks.contains(r));
+        self
+    }
+
+    /// Set the number of copies of each suited card, e.g. 6 for a six-deck shoe
+    pub fn copies(mut self, copies: usize) -> Self {
+        self.copies = copies;
+        self
+    }
+
+    /// Set the number of jokers added to the deck. Jokers are not affected by the number of copies
+    pub fn jokers(mut self, jokers: usize) -> Self {
+        self.jokers = jokers;
+        self
+    }
+
+    /// Stack the given cards on top of the deck, so that ```draw_top()``` returns them in this order before any
+    /// other card. The stacked cards replace their counterparts in the composition, so the deck must hold each of them
+    /// (see ```build()```)
+    pub fn with_top(mut self, cards: &[Card]) -> Self {
+        self.top = cards.to_vec();
+        self
+    }
+
+    /// Shuffle the deck (apart from any stacked cards) when it is built
+    pub fn shuffled(mut self) -> Self {
+        self.shuffled = true;
+        self
+    }
+
+    /// Get the number of cards the built deck will contain
+    pub fn len(&self) -> usize {
+        self.suits.len() * self.ranks.len() * self.copies + self.jokers
+    }
+
+    /// Returns true if the built deck will not contain any cards
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Build the deck, using the thread-local random number generator if the deck is shuffled. Returns an error if a
+    /// stacked card is not in the composition, rather than adding it to the deck
+    pub fn build(&self) -> Result<Deck, DeckBuilderError> {
+        self.build_with(&mut rand::thread_rng())
+    }
+
+    /// Build the deck, using the supplied random number generator if the deck is shuffled. Returns an error if a
+    /// stacked card is not in the composition, rather than adding it to the deck
+    pub fn build_with<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<Deck, DeckBuilderError> {
+        let mut deck = self.build_unstacked();
+        if self.shuffled {
+            deck.shuffle_with(rng);
+        }
+        Deck::stacked(&self.top, deck)
+    }
+
+    /// Get the composition of the deck this builder generates, for validating decks with ```Deck::validate()```
+    pub fn spec(&self) -> DeckSpec<Card> {
+        DeckSpec::from_cards(self.build_unstacked().iter())
+    }
+
+    fn build_unstacked(&self) -> Deck {
+        let mut cards = Vec::with_capacity(self.len());
+
+        for _ in 0..self.copies {
+            for suit in self.suits.iter() {
+                for rank in self.ranks.iter() {
+                    cards.push(Card::from_suit_and_rank(*suit, *rank));
+                }
+            }
+        }
+        for _ in 0..self.jokers {
+            cards.push(Card::joker());
+        }
+
+        Deck::from_cards(cards)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DeckBuilder, DeckBuilderError};
+    use crate::cards::{Card, Rank, Suit};
+    use crate::deck::Deck;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn default_matches_deck_default() {
+        assert!(DeckBuilder::new().build().unwrap().iter().eq(Deck::new().iter()));
+    }
+
+    #[test]
+    fn preset_sizes() {
+        assert_eq!(DeckBuilder::piquet().build().unwrap().len(), 32);
+        assert_eq!(DeckBuilder::skat().build().unwrap().len(), 32);
+        assert_eq!(DeckBuilder::euchre().build().unwrap().len(), 24);
+        assert_eq!(DeckBuilder::pinochle().build().unwrap().len(), 48);
+        assert_eq!(DeckBuilder::short_deck().build().unwrap().len(), 36);
+        assert_eq!(DeckBuilder::forty_card().build().unwrap().len(), 40);
+    }
+
+    #[test]
+    fn pinochle_is_doubled() {
+        let deck = DeckBuilder::pinochle().build().unwrap();
+        let nine_of_hearts = Card::from_suit_and_rank(Suit::Hearts, Rank::Nine);
+
+        assert_eq!(deck.iter().filter(|c| **c == nine_of_hearts).count(), 2);
+        assert!(deck.iter().all(|c| *c.rank() == Rank::Ace || *c.rank() >= Rank::Nine));
+    }
+
+    #[test]
+    fn filters_copies_and_jokers() {
+        let builder = DeckBuilder::new().suits(&[Suit::Hearts, Suit::Spades]).without_suits(&[Suit::Spades]).copies(3).jokers(2);
+        let mut deck = builder.build().unwrap();
+
+        assert_eq!(builder.len(), 41);
+        assert_eq!(deck.len(), 41);
+        assert_eq!(deck.draw(), Some(Card::joker()));
+        assert_eq!(deck.draw(), Some(Card::joker()));
+        assert_eq!(deck.draw(), Some(Card::from_suit_and_rank(Suit::Hearts, Rank::King)));
+        assert!(deck.iter().all(|c| *c.suit() == Suit::Hearts));
+    }
+
+    #[test]
+    fn spec_validates_built_deck() {
+        let builder = DeckBuilder::pinochle();
+        let mut deck = builder.build().unwrap();
+
+        assert!(deck.validate(&builder.spec()).is_valid());
+        deck.draw();
+        assert_eq!(deck.validate(&builder.spec()).missing.len(), 1);
+    }
+
+    #[test]
+    fn stacked_blackjack_split() {
+        let eight_h = Card::from_suit_and_rank(Suit::Hearts, Rank::Eight);
+        let eight_s = Card::from_suit_and_rank(Suit::Spades, Rank::Eight);
+        let six_d = Card::from_suit_and_rank(Suit::Diamonds, Rank::Six);
+        let top = [eight_h, six_d, eight_s];
+        let builder = DeckBuilder::new().copies(6).shuffled().with_top(&top);
+        let mut shoe = builder.build_with(&mut StdRng::seed_from_u64(7)).unwrap();
+
+        assert_eq!(shoe.len(), 312);
+        assert!(shoe.validate(&builder.spec()).is_valid());
+        shoe.assert_draws(&top);
+
+        let same = builder.build_with(&mut StdRng::seed_from_u64(7)).unwrap();
+        assert_eq!(shoe.fingerprint(), same.fingerprint());
+
+        for card in top.iter() {
+            assert_eq!(shoe.draw().as_ref(), Some(card));
+        }
+    }
+
+    #[test]
+    fn stacked_cards_must_be_in_the_deck() {
+        let two = Card::from_suit_and_rank(Suit::Hearts, Rank::Two);
+        let nine = Card::from_suit_and_rank(Suit::Hearts, Rank::Nine);
+
+        assert_eq!(DeckBuilder::euchre().with_top(&[nine, two]).build().unwrap_err(), DeckBuilderError::StackedCardNotInDeck(two));
+        assert_eq!(DeckBuilder::euchre().with_top(&[nine, nine]).build().unwrap_err(), DeckBuilderError::StackedCardNotInDeck(nine));
+        assert_eq!(DeckBuilder::pinochle().with_top(&[nine, nine]).build().unwrap().len(), 48);
+        assert_eq!(DeckBuilder::new().with_top(&[Card::joker()]).build().unwrap_err().to_string(), "the stacked card JOKER- is not in the deck");
+        assert_eq!(DeckBuilder::new().jokers(1).with_top(&[Card::joker()]).build().unwrap().len(), 53);
+    }
+}
@@ -0,0 +1,221 @@
+use crate::card_type::CardType;
+
+/// Describes the expected composition of a collection of cards, i.e. how many copies of each card face it should hold.
+///
+/// A spec is used to validate decks (see ```Deck::validate()```) or any other collection of cards, which makes it
+/// possible to assert card conservation over a deck and all of the hands and discard piles that were dealt from it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeckSpec<C: CardType> {
+    counts: Vec<usize>,
+    _marker: std::marker::PhantomData<C>,
+}
+
+impl<C: CardType> Default for DeckSpec<C> {
+    /// The composition of a single standard deck of the card type
+    fn default() -> Self {
+        DeckSpec::standard(1)
+    }
+}
+
+impl<C: CardType> DeckSpec<C> {
+    /// Create a spec that expects no cards at all
+    pub fn empty() -> Self {
+        DeckSpec {
+            counts: vec![0; C::NUM_FACES],
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Create a spec for a number of standard decks of the card type
+    pub fn standard(copies: usize) -> Self {
+        let mut spec = DeckSpec::empty();
+        for card in C::standard_deck() {
+            spec.add(card, copies);
+        }
+        spec
+    }
+
+    /// Create a spec that expects exactly the given cards. Cards that are not a face of the card type are ignored
+    pub fn from_cards<'a, I: IntoIterator<Item = &'a C>>(cards: I) -> Self
+    where
+        C: 'a,
+    {
+        let mut spec = DeckSpec::empty();
+        for card in cards {
+            spec.add(*card, 1);
+        }
+        spec
+    }
+
+    /// Expect an additional number of copies of a card. Returns false, leaving the spec unchanged, if the card is not a
+    /// face of the card type (see ```CardType::to_index()```)
+    pub fn add(&mut self, card: C, copies: usize) -> bool {
+        match self.counts.get_mut(card.to_index()) {
+            Some(count) => {
+                *count += copies;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Expect an additional number of copies of a card. Useful for adding jokers to a spec. Cards that are not a face
+    /// of the card type are ignored
+    pub fn with(mut self, card: C, copies: usize) -> Self {
+        self.add(card, copies);
+        self
+    }
+
+    /// Get the number of copies of a card that are expected
+    pub fn count(&self, card: &C) -> usize {
+        self.counts.get(card.to_index()).copied().unwrap_or(0)
+    }
+
+    /// Get the total number of cards expected
+    pub fn len(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    /// Returns true if the spec expects no cards
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Compare a collection of cards against this spec and report any discrepancies
+    pub fn validate<'a, I: IntoIterator<Item = &'a C>>(&self, cards: I) -> ValidationReport<C>
+    where
+        C: 'a,
+    {
+        let mut actual = vec![0; C::NUM_FACES];
+        // cards that are not a face of the card type, which no spec can expect
+        let mut invalid: Vec<(C, usize)> = Vec::new();
+        for card in cards {
+            match actual.get_mut(card.to_index()) {
+                Some(count) => *count += 1,
+                None => match invalid.iter_mut().find(|(c, _)| c == card) {
+                    Some((_, count)) => *count += 1,
+                    None => invalid.push((*card, 1)),
+                },
+            }
+        }
+
+        let mut report = ValidationReport {
+            missing: Vec::new(),
+            duplicates: Vec::new(),
+            foreign: Vec::new(),
+        };
+        for (index, (expected, actual)) in self.counts.iter().zip(actual.iter()).enumerate() {
+            let card = match C::from_index(index) {
+                Some(card) => card,
+                None => continue,
+            };
+            if actual < expected {
+                report.missing.push((card, expected - actual));
+            } else if *expected == 0 && *actual > 0 {
+                report.foreign.push((card, *actual));
+            } else if actual > expected {
+                report.duplicates.push((card, actual - expected));
+            }
+        }
+        report.foreign.extend(invalid);
+
+        report
+    }
+}
+
+/// The result of validating a collection of cards against a ```DeckSpec```. Each entry pairs a card with the number of
+/// copies involved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationReport<C: CardType> {
+    /// Cards that are expected, but not present (or present fewer times than expected)
+    pub missing: Vec<(C, usize)>,
+    /// Cards that are present more times than expected
+    pub duplicates: Vec<(C, usize)>,
+    /// Cards that are present but not part of the expected composition at all
+    pub foreign: Vec<(C, usize)>,
+}
+
+impl<C: CardType> ValidationReport<C> {
+    /// Returns true if the collection matched the spec exactly
+    pub fn is_valid(&self) -> bool {
+        self.missing.is_empty() && self.duplicates.is_empty() && self.foreign.is_empty()
+    }
+}
+
+impl<C: CardType> std::fmt::Display for ValidationReport<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_valid() {
+            return write!(f, "valid");
+        }
+
+        let sections = [("missing", &self.missing), ("duplicates", &self.duplicates), ("foreign", &self.foreign)];
+        let mut first = true;
+        for (name, entries) in sections.iter().filter(|(_, entries)| !entries.is_empty()) {
+            if !first {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}:", name)?;
+            for (card, count) in entries.iter() {
+                write!(f, " {:?}x{}", card, count)?;
+            }
+            first = false;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DeckSpec;
+    use crate::cards::{Card, Rank, Suit};
+    use crate::deck::Deck;
+
+    #[test]
+    fn standard_spec() {
+        let spec = DeckSpec::<Card>::standard(6);
+
+        assert_eq!(spec.len(), 312);
+        assert_eq!(spec.count(&Card::from_suit_and_rank(Suit::Hearts, Rank::Queen)), 6);
+        assert_eq!(spec.count(&Card::joker()), 0);
+    }
+
+    #[test]
+    fn add_cards() {
+        let mut spec = DeckSpec::<Card>::empty();
+
+        assert!(spec.add(Card::joker(), 2));
+        assert!(!spec.add(Card::from_suit_and_rank(Suit::None, Rank::Ace), 1));
+        assert_eq!(spec.len(), 2);
+        assert_eq!(spec.count(&Card::joker()), 2);
+    }
+
+    #[test]
+    fn validate_report() {
+        let ace = Card::from_suit_and_rank(Suit::Clubs, Rank::Ace);
+        let king = Card::from_suit_and_rank(Suit::Spades, Rank::King);
+        let mut deck = Deck::new();
+
+        assert!(DeckSpec::standard(1).validate(deck.iter()).is_valid());
+
+        deck.draw();
+        deck.add(ace);
+        deck.add(Card::joker());
+
+        let report = DeckSpec::standard(1).validate(deck.iter());
+        assert!(!report.is_valid());
+        assert_eq!(report.missing, vec![(king, 1)]);
+        assert_eq!(report.duplicates, vec![(ace, 1)]);
+        assert_eq!(report.foreign, vec![(Card::joker(), 1)]);
+    }
+
+    #[test]
+    fn conservation_across_collections() {
+        let mut deck = Deck::new();
+        let hand = [deck.draw().unwrap(), deck.draw().unwrap()];
+        let spec = DeckSpec::standard(1).with(Card::joker(), 1);
+
+        let report = spec.validate(deck.iter().chain(hand.iter()));
+        assert_eq!(report.missing, vec![(Card::joker(), 1)]);
+        assert!(report.duplicates.is_empty() && report.foreign.is_empty());
+    }
+}
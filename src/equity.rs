@@ -0,0 +1,525 @@
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use rand::seq::index::sample;
+use crate::card_set::CardSet;
+use crate::cards::Card;
+use crate::combinations::{binomial, remaining_boards};
+use crate::poker_lookup::LookupEvaluator;
+use crate::range::Range;
+
+/// The number of times a deal of the players' ranges is attempted before giving up on finding combos without shared
+/// cards
+const MAX_DEAL_ATTEMPTS: usize = 1000;
+
+/// Describes why an equity calculation could not be run
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EquityError {
+    /// Equity is calculated for 2 to 10 players
+    PlayerCount(usize),
+    /// The board holds more than five cards
+    BoardTooLong(usize),
+    /// A card appears more than once among the hands, board and dead cards
+    DuplicateCard(Card),
+    /// Jokers cannot be used in Hold'em
+    Joker,
+    /// The player's range holds no combos once the board and dead cards are removed
+    EmptyRange(usize),
+    /// The ranges cannot be dealt without giving the same card to more than one player
+    ConflictingRanges,
+    /// Too few cards are left in the deck to deal the hands and the rest of the board
+    NotEnoughCards,
+}
+
+impl std::fmt::Display for EquityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EquityError::PlayerCount(count) => write!(f, "equity needs 2 to 10 players, got {}", count),
+            EquityError::BoardTooLong(len) => write!(f, "the board holds at most 5 cards, got {}", len),
+            EquityError::DuplicateCard(card) => write!(f, "{} is used more than once", card),
+            EquityError::Joker => write!(f, "jokers cannot be used in Hold'em"),
+            EquityError::EmptyRange(player) => write!(f, "the range of player {} is empty", player),
+            EquityError::ConflictingRanges => write!(f, "the ranges cannot be dealt without sharing cards"),
+            EquityError::NotEnoughCards => write!(f, "too few cards are left to deal the hands and the board"),
+        }
+    }
+}
+
+impl std::error::Error for EquityError {}
+
+/// The outcomes of a single player over all of the boards that were dealt. When ranges are dealt exhaustively, each
+/// board also counts for the weight of the deal it was dealt for (see ```weighted_wins()```), and the fractions are
+/// weighted the same way.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct PlayerEquity {
+    wins: u64,
+    ties: u64,
+    losses: u64,
+    weighted_wins: f64,
+    weighted_ties: f64,
+    weighted_losses: f64,
+    share: f64,
+}
+
+impl PlayerEquity {
+    /// Get the number of boards the player won outright
+    pub fn wins(&self) -> u64 {
+        self.wins
+    }
+
+    /// Get the number of boards on which the player split the pot
+    pub fn ties(&self) -> u64 {
+        self.ties
+    }
+
+    /// Get the number of boards the player lost
+    pub fn losses(&self) -> u64 {
+        self.losses
+    }
+
+    /// Get the boards the player won outright, each counted for the weight of its deal. This is the same as
+    /// ```wins()``` unless weighted ranges were dealt exhaustively
+    pub fn weighted_wins(&self) -> f64 {
+        self.weighted_wins
+    }
+
+    /// Get the boards on which the player split the pot, each counted for the weight of its deal
+    pub fn weighted_ties(&self) -> f64 {
+        self.weighted_ties
+    }
+
+    /// Get the boards the player lost, each counted for the weight of its deal
+    pub fn weighted_losses(&self) -> f64 {
+        self.weighted_losses
+    }
+
+    /// Get the fraction of boards the player won outright
+    pub fn win(&self) -> f64 {
+        self.fraction(self.weighted_wins)
+    }
+
+    /// Get the fraction of boards on which the player split the pot
+    pub fn tie(&self) -> f64 {
+        self.fraction(self.weighted_ties)
+    }
+
+    /// Get the fraction of boards the player lost
+    pub fn lose(&self) -> f64 {
+        self.fraction(self.weighted_losses)
+    }
+
+    /// Get the player's share of the pot, counting split pots as the fraction of the pot won
+    pub fn equity(&self) -> f64 {
+        self.fraction(self.share)
+    }
+
+    fn fraction(&self, count: f64) -> f64 {
+        let boards = self.weighted_wins + self.weighted_ties + self.weighted_losses;
+        if boards == 0.0 {
+            return 0.0;
+        }
+        count / boards
+    }
+}
+
+/// The result of an equity calculation, listing the players in the order their hands were given
+#[derive(Debug, Clone, PartialEq)]
+pub struct EquityResult {
+    players: Vec<PlayerEquity>,
+    boards: u64,
+    exact: bool,
+}
+
+impl EquityResult {
+    /// Get the outcomes of each player
+    pub fn players(&self) -> &[PlayerEquity] {
+        &self.players
+    }
+
+    /// Get the number of boards that were evaluated
+    pub fn boards(&self) -> u64 {
+        self.boards
+    }
+
+    /// Returns true if every possible board was evaluated, or false if the boards were sampled
+    pub fn is_exact(&self) -> bool {
+        self.exact
+    }
+}
+
+/// Calculates the equity of Texas Hold'em hands, given an optional board and dead cards (e.g. folded or exposed cards).
+///
+/// When the number of possible run-outs is at most ```max_boards()```, every one of them is evaluated and the result is
+/// exact. Otherwise ```trials()``` boards are sampled at random, reproducibly from the seed.
+#[derive(Debug, Clone)]
+pub struct EquityCalculator {
+    board: Vec<Card>,
+    dead: Vec<Card>,
+    max_boards: u64,
+    trials: usize,
+    seed: u64,
+}
+
+impl Default for EquityCalculator {
+    fn default() -> Self {
+        EquityCalculator {
+            board: Vec::new(),
+            dead: Vec::new(),
+            max_boards: 2_000_000,
+            trials: 100_000,
+            seed: 0,
+        }
+    }
+}
+
+impl EquityCalculator {
+    /// Create a calculator without a board or dead cards, which enumerates up to 2,000,000 boards (every preflop
+    /// run-out of two players) and samples 100,000 boards otherwise
+    pub fn new() -> Self {
+        EquityCalculator::default()
+    }
+
+    /// Set the cards already dealt to the board
+    pub fn board(mut self, board: &[Card]) -> Self {
+        self.board = board.to_vec();
+        self
+    }
+
+    /// Set the cards that cannot appear on the board
+    pub fn dead(mut self, dead: &[Card]) -> Self {
+        self.dead = dead.to_vec();
+        self
+    }
+
+    /// Set the largest number of boards to enumerate exhaustively
+    pub fn max_boards(mut self, max_boards: u64) -> Self {
+        self.max_boards = max_boards;
+        self
+    }
+
+    /// Set the number of boards to sample when there are too many to enumerate
+    pub fn trials(mut self, trials: usize) -> Self {
+        self.trials = trials.max(1);
+        self
+    }
+
+    /// Set the seed used to sample boards
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Calculate the equity of each player's hole cards
+    pub fn calculate(&self, hands: &[[Card; 2]]) -> Result<EquityResult, EquityError> {
+        if hands.len() < 2 || hands.len() > 10 {
+            return Err(EquityError::PlayerCount(hands.len()));
+        }
+        let hands: Vec<CardSet> = hands.iter().map(|h| h.iter().collect()).collect();
+        let known = self.known_cards(hands.iter().flat_map(|h| h.iter()))?;
+        if 52 - known.len() < 5 - self.board.len() {
+            return Err(EquityError::NotEnoughCards);
+        }
+        Ok(self.run(&hands, &known))
+    }
+
+    /// Calculate the equity of each player's range. Combos holding a card of the board or the dead cards are removed
+    /// from the ranges, and so are deals that would give the same card to more than one player. Each deal is weighted by
+    /// the product of the weights of its combos.
+    ///
+    /// When the number of deals times the number of boards is at most ```max_boards()```, every board of every deal is
+    /// evaluated. Otherwise ```trials()``` deals are sampled in proportion to their weights, each with a random board.
+    pub fn calculate_ranges(&self, ranges: &[Range]) -> Result<EquityResult, EquityError> {
+        if ranges.len() < 2 || ranges.len() > 10 {
+            return Err(EquityError::PlayerCount(ranges.len()));
+        }
+        let known = self.known_cards(std::iter::empty())?;
+        let ranges: Vec<Range> = ranges.iter().map(|r| r.without(&known)).collect();
+        if let Some(player) = ranges.iter().position(|r| r.total_weight() <= 0.0) {
+            return Err(EquityError::EmptyRange(player));
+        }
+
+        let unseen = 52usize
+            .checked_sub(known.len() + 2 * ranges.len())
+            .filter(|unseen| *unseen >= 5 - self.board.len())
+            .ok_or(EquityError::NotEnoughCards)?;
+        let boards = binomial(unseen, 5 - self.board.len());
+        let deals = ranges.iter().fold(1u64, |deals, r| deals.saturating_mul(r.len() as u64));
+        let result = if deals.saturating_mul(boards) <= self.max_boards {
+            let mut players = vec![PlayerEquity::default(); ranges.len()];
+            let mut boards = 0;
+            self.deal_ranges(&ranges, &mut Vec::new(), known, 1.0, &mut players, &mut boards);
+            EquityResult {
+                players,
+                boards,
+                exact: true,
+            }
+        } else {
+            self.sample_ranges(&ranges, &known)?
+        };
+
+        if result.boards == 0 {
+            return Err(EquityError::ConflictingRanges);
+        }
+        Ok(result)
+    }
+
+    /// Check the board and dead cards along with the given hole cards, returning all of the cards that are known
+    fn known_cards<I: IntoIterator<Item = Card>>(&self, hole: I) -> Result<CardSet, EquityError> {
+        if self.board.len() > 5 {
+            return Err(EquityError::BoardTooLong(self.board.len()));
+        }
+        let mut known = CardSet::new();
+        for card in hole.into_iter().chain(self.board.iter().cloned()).chain(self.dead.iter().cloned()) {
+            if card.is_joker() {
+                return Err(EquityError::Joker);
+            }
+            if !known.insert(card) {
+                return Err(EquityError::DuplicateCard(card));
+            }
+        }
+        Ok(known)
+    }
+
+    /// Deal the rest of the board for a set of hands whose cards (along with the board and dead cards) are ```known```
+    fn run(&self, hands: &[CardSet], known: &CardSet) -> EquityResult {
+        let mut players = vec![PlayerEquity::default(); hands.len()];
+        let unseen = CardSet::full().difference(known);
+
+        let exact = binomial(unseen.len(), 5 - self.board.len()) <= self.max_boards;
+        let boards = if exact {
+            self.enumerate_boards(hands, known, 1.0, &mut players)
+        } else {
+            let mut rng = StdRng::seed_from_u64(self.seed);
+            let mut strengths = vec![0; hands.len()];
+            for _ in 0..self.trials {
+                showdown(hands, &self.sample_board(&mut rng, known), 1.0, &mut strengths, &mut players);
+            }
+            self.trials as u64
+        };
+
+        EquityResult {
+            players,
+            boards,
+            exact,
+        }
+    }
+
+    /// Evaluate every way to complete the board, returning the number of boards
+    fn enumerate_boards(&self, hands: &[CardSet], known: &CardSet, weight: f64, players: &mut [PlayerEquity]) -> u64 {
+        let board: CardSet = self.board.iter().collect();
+        let mut strengths = vec![0; hands.len()];
+        let mut boards = 0;
+        for run_out in remaining_boards(known, 5 - self.board.len()) {
+            showdown(hands, &board.union(&run_out), weight, &mut strengths, players);
+            boards += 1;
+        }
+        boards
+    }
+
+    /// Complete the board with random cards that are not known
+    fn sample_board(&self, rng: &mut StdRng, known: &CardSet) -> CardSet {
+        let board: CardSet = self.board.iter().collect();
+        let unseen: Vec<Card> = CardSet::full().difference(known).iter().collect();
+        let run_out: CardSet = sample(rng, unseen.len(), 5 - self.board.len()).iter().map(|i| unseen[i]).collect();
+        board.union(&run_out)
+    }
+
+    /// Deal every combination of combos from the ranges of the players after those already dealt to ```hands```
+    fn deal_ranges(&self, ranges: &[Range], hands: &mut Vec<CardSet>, known: CardSet, weight: f64, players: &mut [PlayerEquity], boards: &mut u64) {
+        match ranges.get(hands.len()) {
+            None => *boards += self.enumerate_boards(hands, &known, weight, players),
+            Some(range) => {
+                for (combo, combo_weight) in range.sets().iter().filter(|(c, w)| *w > 0.0 && c.is_disjoint(&known)) {
+                    hands.push(*combo);
+                    self.deal_ranges(ranges, hands, known.union(combo), weight * combo_weight, players, boards);
+                    hands.pop();
+                }
+            }
+        }
+    }
+
+    /// Sample deals of the ranges in proportion to their weights, rejecting deals in which players share a card
+    fn sample_ranges(&self, ranges: &[Range], known: &CardSet) -> Result<EquityResult, EquityError> {
+        let cumulative: Vec<Vec<f64>> = ranges.iter().map(|r| {
+            r.sets().iter().scan(0.0, |total, (_, w)| {
+                *total += w;
+                Some(*total)
+            }).collect()
+        }).collect();
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut players = vec![PlayerEquity::default(); ranges.len()];
+        let mut strengths = vec![0; ranges.len()];
+        let mut hands = vec![CardSet::new(); ranges.len()];
+
+        for _ in 0..self.trials {
+            let mut dealt = None;
+            for _ in 0..MAX_DEAL_ATTEMPTS {
+                let mut used = *known;
+                let valid = ranges.iter().zip(cumulative.iter()).zip(hands.iter_mut()).all(|((range, weights), hand)| {
+                    let x = rng.gen::<f64>() * weights[weights.len() - 1];
+                    *hand = range.sets()[weights.partition_point(|w| *w <= x).min(weights.len() - 1)].0;
+                    let disjoint = hand.is_disjoint(&used);
+                    used = used.union(hand);
+                    disjoint
+                });
+                if valid {
+                    dealt = Some(used);
+                    break;
+                }
+            }
+            let used = dealt.ok_or(EquityError::ConflictingRanges)?;
+            showdown(&hands, &self.sample_board(&mut rng, &used), 1.0, &mut strengths, &mut players);
+        }
+
+        Ok(EquityResult {
+            players,
+            boards: self.trials as u64,
+            exact: false,
+        })
+    }
+}
+
+/// Evaluate every hand on a complete board, and tally the outcome with the given weight
+fn showdown(hands: &[CardSet], board: &CardSet, weight: f64, strengths: &mut [u32], players: &mut [PlayerEquity]) {
+    for (strength, hand) in strengths.iter_mut().zip(hands.iter()) {
+        *strength = LookupEvaluator::strength(&hand.union(board));
+    }
+    let best = *strengths.iter().max().unwrap_or(&0);
+    let winners = strengths.iter().filter(|s| **s == best).count();
+
+    for (player, strength) in players.iter_mut().zip(strengths.iter()) {
+        if *strength < best {
+            player.losses += 1;
+            player.weighted_losses += weight;
+        } else if winners == 1 {
+            player.wins += 1;
+            player.weighted_wins += weight;
+            player.share += weight;
+        } else {
+            player.ties += 1;
+            player.weighted_ties += weight;
+            player.share += weight / winners as f64;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EquityCalculator, EquityError};
+    use crate::cards::{cards, Card, Rank};
+    use crate::deck::Deck;
+    use crate::range::Range;
+
+    fn hand(hole: &str) -> [Card; 2] {
+        let c = cards(hole);
+        [c[0], c[1]]
+    }
+
+    #[test]
+    fn exact_on_the_flop() {
+        let result = EquityCalculator::new()
+            .board(&cards("Ah 7c 2d"))
+            .calculate(&[hand("As Ks"), hand("Qh Qd")])
+            .unwrap();
+
+        assert!(result.is_exact());
+        assert_eq!(result.boards(), 990);
+        let (ace, queens) = (result.players()[0], result.players()[1]);
+        assert_eq!(ace.wins() + ace.ties() + ace.losses(), 990);
+        // the queens need one of two queens (or runner-runner help) to win
+        assert_eq!(queens.wins(), ace.losses());
+        assert!(ace.equity() > 0.9 && ace.equity() < 0.95);
+        assert!((ace.equity() + queens.equity() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn split_pots_and_dead_cards() {
+        // both players play the board
+        let result = EquityCalculator::new()
+            .board(&cards("Ts Js Qs Ks As"))
+            .calculate(&[hand("2c 3d"), hand("4h 5c"), hand("6d 7h")])
+            .unwrap();
+        assert_eq!(result.boards(), 1);
+        assert!(result.players().iter().all(|p| p.ties() == 1 && (p.equity() - 1.0 / 3.0).abs() < 1e-12));
+
+        // with both remaining kings dead, only the three remaining queens make the best hand
+        let dead = EquityCalculator::new().board(&cards("Kh 9c 2d 3c")).dead(&cards("Kc Kd"));
+        let result = dead.calculate(&[hand("Ks Qs"), hand("Ac Ad")]).unwrap();
+        assert_eq!(result.boards(), 42);
+        assert_eq!(result.players()[0].wins(), 3);
+    }
+
+    #[test]
+    fn sampled_preflop() {
+        let calculator = EquityCalculator::new().max_boards(0).trials(20_000).seed(3);
+        let result = calculator.calculate(&[hand("Ac Ad"), hand("Kc Kd")]).unwrap();
+
+        assert!(!result.is_exact());
+        assert_eq!(result.boards(), 20_000);
+        assert!((result.players()[0].equity() - 0.82).abs() < 0.02);
+        assert_eq!(calculator.calculate(&[hand("Ac Ad"), hand("Kc Kd")]), Ok(result));
+    }
+
+    #[test]
+    fn invalid_input() {
+        let calculator = EquityCalculator::new();
+
+        assert_eq!(calculator.calculate(&[hand("Ac Ad")]), Err(EquityError::PlayerCount(1)));
+        assert_eq!(calculator.calculate(&[hand("Ac Ad"), hand("Ac Kd")]), Err(EquityError::DuplicateCard(cards("Ac")[0])));
+        assert_eq!(calculator.clone().board(&cards("2c 3c 4c 5c 6c 7c")).calculate(&[hand("Ac Ad"), hand("Kc Kd")]), Err(EquityError::BoardTooLong(6)));
+        assert_eq!(calculator.calculate(&[hand("Ac Ad"), [Card::joker(), cards("Kh")[0]]]), Err(EquityError::Joker));
+    }
+
+    fn range(range: &str) -> Range {
+        range.parse().unwrap()
+    }
+
+    #[test]
+    fn range_against_range() {
+        let river = EquityCalculator::new().board(&cards("3c 4d 8h 9s Td"));
+        let result = river.calculate_ranges(&[range("AA:0.25, 22"), range("KK")]).unwrap();
+
+        assert!(result.is_exact());
+        assert_eq!(result.boards(), 72);
+        assert!((result.players()[0].equity() - 1.5 / 7.5).abs() < 1e-12);
+        // the aces win 36 boards and the deuces lose 36, but each board of the aces only counts for a quarter
+        let player = result.players()[0];
+        assert_eq!((player.wins(), player.losses()), (36, 36));
+        assert_eq!((player.weighted_wins(), player.weighted_losses()), (9.0, 36.0));
+
+        // a range of a single combo is the same as the hand itself
+        let flop = EquityCalculator::new().board(&cards("Ah 7c 2d"));
+        let ranges = flop.calculate_ranges(&[range("AsKs"), range("QhQd")]).unwrap();
+        let hands = flop.calculate(&[hand("As Ks"), hand("Qh Qd")]).unwrap();
+        assert_eq!(ranges, hands);
+    }
+
+    #[test]
+    fn sampled_ranges() {
+        let calculator = EquityCalculator::new().max_boards(1_000).trials(5_000).seed(11);
+        let result = calculator.calculate_ranges(&[range("AA"), range("KK")]).unwrap();
+
+        assert!(!result.is_exact());
+        assert!((result.players()[0].equity() - 0.82).abs() < 0.03);
+        assert_eq!(calculator.calculate_ranges(&[range("AA"), range("KK")]), Ok(result));
+    }
+
+    #[test]
+    fn invalid_ranges() {
+        let aces = EquityCalculator::new().board(&cards("Ac Ad Ah"));
+
+        assert_eq!(aces.calculate_ranges(&[range("AA"), range("KK")]), Err(EquityError::EmptyRange(0)));
+        assert_eq!(EquityCalculator::new().calculate_ranges(&[range("AsAh"), range("AsAd")]), Err(EquityError::ConflictingRanges));
+        assert_eq!(
+            EquityCalculator::new().max_boards(0).calculate_ranges(&[range("AsAh"), range("AsAd")]),
+            Err(EquityError::ConflictingRanges)
+        );
+        assert_eq!(EquityCalculator::new().calculate_ranges(&[range("KK")]), Err(EquityError::PlayerCount(1)));
+
+        // 44 dead cards leave too few for the board, or even for the hands of five players
+        let dead: Vec<Card> = Deck::new().iter().filter(|c| *c.rank() != Rank::Ace && *c.rank() != Rank::King).cloned().collect();
+        let calculator = EquityCalculator::new().dead(&dead);
+        assert_eq!(calculator.calculate_ranges(&[range("AA"), range("KK")]), Err(EquityError::NotEnoughCards));
+        assert_eq!(calculator.calculate_ranges(&vec![range("AA,KK"); 5]), Err(EquityError::NotEnoughCards));
+        assert_eq!(calculator.calculate(&[hand("Ac Ad"), hand("Kc Kd")]), Err(EquityError::NotEnoughCards));
+    }
+}
@@ -0,0 +1,301 @@
+use rand::RngCore;
+use crate::card_type::CardType;
+use crate::deck::Deck;
+use crate::sha256::{from_hex, sha256, to_hex};
+
+/// The secret seed chosen by the server before a hand is played. Only its ```Commitment``` is published before the
+/// hand, the seed itself is revealed afterwards so that players can verify the shuffle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerSeed([u8; 32]);
+
+impl ServerSeed {
+    /// Generate a new random server seed
+    pub fn generate() -> Self {
+        ServerSeed(rand::random())
+    }
+
+    /// Construct a server seed from raw bytes
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        ServerSeed(bytes)
+    }
+
+    /// Parse a server seed from its hexadecimal representation (as revealed to players)
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let bytes = from_hex(hex)?;
+        if bytes.len() != 32 {
+            return None;
+        }
+        let mut seed = [0u8; 32];
+        seed.copy_from_slice(&bytes);
+        Some(ServerSeed(seed))
+    }
+
+    /// Get the raw bytes of the seed
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Calculate the commitment (the SHA-256 hash) of this seed, which is published before the hand is played
+    pub fn commitment(&self) -> Commitment {
+        Commitment(sha256(&self.0))
+    }
+}
+
+impl std::fmt::Display for ServerSeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", to_hex(&self.0))
+    }
+}
+
+/// A published commitment to a ```ServerSeed```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Commitment([u8; 32]);
+
+impl Commitment {
+    /// Parse a commitment from its hexadecimal representation
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        ServerSeed::from_hex(hex).map(|seed| Commitment(seed.0))
+    }
+
+    /// Get the raw bytes of the commitment
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Returns true if the server seed matches this commitment
+    pub fn matches(&self, seed: &ServerSeed) -> bool {
+        seed.commitment() == *self
+    }
+}
+
+impl std::fmt::Display for Commitment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", to_hex(&self.0))
+    }
+}
+
+/// A deterministic random number generator derived from a server seed, a client seed and a nonce (e.g. the hand
+/// number). The output stream is the concatenation of ```SHA-256(server_seed || client_seed || nonce || counter)```
+/// blocks, so anybody who knows the three inputs can reproduce it.
+#[derive(Debug, Clone)]
+pub struct FairRng {
+    input: Vec<u8>,
+    counter: u64,
+    block: [u8; 32],
+    offset: usize,
+}
+
+impl FairRng {
+    /// Create the generator for the given seeds and nonce
+    pub fn new(server_seed: &ServerSeed, client_seed: &[u8], nonce: u64) -> Self {
+        let mut input = Vec::with_capacity(32 + client_seed.len() + 16);
+        input.extend_from_slice(server_seed.as_bytes());
+        input.extend_from_slice(client_seed);
+        input.extend_from_slice(&nonce.to_be_bytes());
+
+        FairRng {
+            input,
+            counter: 0,
+            block: [0u8; 32],
+            offset: 32,
+        }
+    }
+
+    fn next_byte(&mut self) -> u8 {
+        if self.offset == self.block.len() {
+            let mut message = self.input.clone();
+            message.extend_from_slice(&self.counter.to_be_bytes());
+            self.block = sha256(&message);
+            self.counter += 1;
+            self.offset = 0;
+        }
+        self.offset += 1;
+        self.block[self.offset - 1]
+    }
+}
+
+impl RngCore for FairRng {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0u8; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for byte in dest.iter_mut() {
+            *byte = self.next_byte();
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+/// Describes why a shuffle failed verification
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FairnessError {
+    /// The revealed server seed does not match the commitment published before the hand
+    CommitmentMismatch,
+    /// Replaying the shuffle does not produce the order that was dealt
+    OrderMismatch,
+    /// A deck holds a card that is not a face of its card type, so its order has no fingerprint
+    InvalidCard,
+}
+
+impl std::fmt::Display for FairnessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FairnessError::CommitmentMismatch => write!(f, "server seed does not match the published commitment"),
+            FairnessError::OrderMismatch => write!(f, "shuffle does not reproduce the dealt order"),
+            FairnessError::InvalidCard => write!(f, "a deck holds a card that is not a face of its card type"),
+        }
+    }
+}
+
+impl std::error::Error for FairnessError {}
+
+/// A provably fair shuffle built on a commit/reveal scheme:
+///
+/// 1. The server generates a ```ServerSeed``` and publishes its ```Commitment``` before the hand.
+/// 2. The player supplies a client seed, so that the server cannot choose the order on its own.
+/// 3. The deck is shuffled with ```Deck::shuffle_with()``` using the ```FairRng``` derived from both seeds and the nonce.
+/// 4. After the hand the server seed is revealed, and anybody can replay the shuffle with ```FairShuffle::verify()```.
+///
+/// Note that the shuffle algorithm is provided by the ```rand``` crate, so verifiers must use the same version of
+/// card-core.
+#[derive(Debug, Clone)]
+pub struct FairShuffle {
+    server_seed: ServerSeed,
+    client_seed: Vec<u8>,
+    nonce: u64,
+}
+
+impl FairShuffle {
+    /// Describe a shuffle by its seeds and nonce
+    pub fn new(server_seed: ServerSeed, client_seed: &[u8], nonce: u64) -> Self {
+        FairShuffle {
+            server_seed,
+            client_seed: client_seed.to_vec(),
+            nonce,
+        }
+    }
+
+    /// Get the server seed of this shuffle
+    pub fn server_seed(&self) -> &ServerSeed {
+        &self.server_seed
+    }
+
+    /// Get the commitment to publish before the hand is played
+    pub fn commitment(&self) -> Commitment {
+        self.server_seed.commitment()
+    }
+
+    /// Get the random number generator for this shuffle
+    pub fn rng(&self) -> FairRng {
+        FairRng::new(&self.server_seed, &self.client_seed, self.nonce)
+    }
+
+    /// Shuffle a deck
+    pub fn shuffle<C: CardType>(&self, deck: &mut Deck<C>) {
+        deck.shuffle_with(&mut self.rng());
+    }
+
+    /// Verify that the revealed seed matches the commitment, and that shuffling ```initial``` produces ```shuffled```
+    pub fn verify<C: CardType>(&self, commitment: &Commitment, initial: &Deck<C>, shuffled: &Deck<C>) -> Result<(), FairnessError> {
+        let replayed = self.replay(commitment, initial)?;
+        let expected = replayed.fingerprint().ok_or(FairnessError::InvalidCard)?;
+        if shuffled.fingerprint().ok_or(FairnessError::InvalidCard)? != expected {
+            return Err(FairnessError::OrderMismatch);
+        }
+        Ok(())
+    }
+
+    /// Verify that the revealed seed matches the commitment, and that the cards dealt (in the order they were drawn
+    /// with ```Deck::draw()```) are the ones the shuffle of ```initial``` produces
+    pub fn verify_dealt<C: CardType>(&self, commitment: &Commitment, initial: &Deck<C>, dealt: &[C]) -> Result<(), FairnessError> {
+        let mut replayed = self.replay(commitment, initial)?;
+        for card in dealt {
+            if replayed.draw().as_ref() != Some(card) {
+                return Err(FairnessError::OrderMismatch);
+            }
+        }
+        Ok(())
+    }
+
+    fn replay<C: CardType>(&self, commitment: &Commitment, initial: &Deck<C>) -> Result<Deck<C>, FairnessError> {
+        if !commitment.matches(&self.server_seed) {
+            return Err(FairnessError::CommitmentMismatch);
+        }
+        let mut deck = initial.clone();
+        self.shuffle(&mut deck);
+        Ok(deck)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Commitment, FairShuffle, FairnessError, ServerSeed};
+    use crate::cards::{Card, Rank, Suit};
+    use crate::deck::Deck;
+
+    #[test]
+    fn commitment_round_trip() {
+        let seed = ServerSeed::generate();
+        let commitment = seed.commitment();
+
+        assert!(commitment.matches(&seed));
+        assert_eq!(ServerSeed::from_hex(&seed.to_string()), Some(seed.clone()));
+        assert_eq!(Commitment::from_hex(&commitment.to_string()), Some(commitment));
+        assert!(!commitment.matches(&ServerSeed::from_bytes([0u8; 32])));
+    }
+
+    #[test]
+    fn shuffle_is_deterministic() {
+        let fair = FairShuffle::new(ServerSeed::from_bytes([7u8; 32]), b"client", 1);
+        let mut a = Deck::new();
+        let mut b = Deck::new();
+
+        fair.shuffle(&mut a);
+        fair.shuffle(&mut b);
+        assert_eq!(a.fingerprint(), b.fingerprint());
+        assert_ne!(a.fingerprint(), Deck::new().fingerprint());
+
+        let mut c = Deck::new();
+        FairShuffle::new(ServerSeed::from_bytes([7u8; 32]), b"client", 2).shuffle(&mut c);
+        assert_ne!(a.fingerprint(), c.fingerprint());
+    }
+
+    #[test]
+    fn verify_shuffle() {
+        let fair = FairShuffle::new(ServerSeed::generate(), b"player chosen seed", 42);
+        let commitment = fair.commitment();
+        let initial = Deck::new();
+        let mut shoe = initial.clone();
+        fair.shuffle(&mut shoe);
+
+        assert_eq!(fair.verify(&commitment, &initial, &shoe), Ok(()));
+
+        let dealt = vec![shoe.draw().unwrap(), shoe.draw().unwrap(), shoe.draw().unwrap()];
+        assert_eq!(fair.verify_dealt(&commitment, &initial, &dealt), Ok(()));
+        assert_eq!(fair.verify_dealt(&commitment, &initial, &dealt[1..]), Err(FairnessError::OrderMismatch));
+        assert_eq!(fair.verify(&commitment, &initial, &initial), Err(FairnessError::OrderMismatch));
+
+        let other = ServerSeed::generate().commitment();
+        assert_eq!(fair.verify(&other, &initial, &shoe), Err(FairnessError::CommitmentMismatch));
+
+        let unsuited = |rank| Deck::from_cards(vec![Card::from_suit_and_rank(Suit::None, rank), Card::joker()]);
+        let initial = unsuited(Rank::Ace);
+        let mut shoe = initial.clone();
+        fair.shuffle(&mut shoe);
+        assert_eq!(fair.verify(&commitment, &initial, &shoe), Err(FairnessError::InvalidCard));
+        assert_eq!(fair.verify(&commitment, &initial, &unsuited(Rank::King)), Err(FairnessError::InvalidCard));
+    }
+}
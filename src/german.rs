@@ -0,0 +1,176 @@
+use crate::card_type::CardType;
+
+/// Represents the suit of a German-suited card: Acorns, Leaves, Hearts or Bells
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GermanSuit {
+    Acorns,
+    Leaves,
+    Hearts,
+    Bells,
+}
+
+impl std::fmt::Display for GermanSuit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            GermanSuit::Acorns => "a",
+            GermanSuit::Leaves => "l",
+            GermanSuit::Hearts => "h",
+            GermanSuit::Bells => "b",
+        })
+    }
+}
+
+impl GermanSuit {
+    /// Convert a GermanSuit variant to its ordinal value
+    pub fn to_ordinal(&self) -> u8 {
+        match self {
+            GermanSuit::Acorns => 0,
+            GermanSuit::Leaves => 1,
+            GermanSuit::Hearts => 2,
+            GermanSuit::Bells => 3,
+        }
+    }
+
+    /// Convert an ordinal value to a ```GermanSuit``` variant. Returns ```None``` for values outside of 0..3
+    pub fn from_ordinal(suit: u8) -> Option<Self> {
+        match suit {
+            0 => Some(GermanSuit::Acorns),
+            1 => Some(GermanSuit::Leaves),
+            2 => Some(GermanSuit::Hearts),
+            3 => Some(GermanSuit::Bells),
+            _ => None,
+        }
+    }
+}
+
+/// Represents the rank of a card in a 32-card German (Skat) deck: Seven to Ten, Unter, Ober, King and Ace (Daus)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GermanRank {
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Unter,
+    Ober,
+    King,
+    Ace,
+}
+
+impl std::fmt::Display for GermanRank {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GermanRank::Ten => write!(f, "T"),
+            GermanRank::Unter => write!(f, "U"),
+            GermanRank::Ober => write!(f, "O"),
+            GermanRank::King => write!(f, "K"),
+            GermanRank::Ace => write!(f, "A"),
+            _ => write!(f, "{}", self.to_ordinal() + 7),
+        }
+    }
+}
+
+impl GermanRank {
+    /// Convert a GermanRank variant to its ordinal value
+    pub fn to_ordinal(&self) -> u8 {
+        match self {
+            GermanRank::Seven => 0,
+            GermanRank::Eight => 1,
+            GermanRank::Nine => 2,
+            GermanRank::Ten => 3,
+            GermanRank::Unter => 4,
+            GermanRank::Ober => 5,
+            GermanRank::King => 6,
+            GermanRank::Ace => 7,
+        }
+    }
+
+    /// Convert an ordinal value to a ```GermanRank``` variant. Returns ```None``` for values outside of 0..7
+    pub fn from_ordinal(rank: u8) -> Option<Self> {
+        match rank {
+            0 => Some(GermanRank::Seven),
+            1 => Some(GermanRank::Eight),
+            2 => Some(GermanRank::Nine),
+            3 => Some(GermanRank::Ten),
+            4 => Some(GermanRank::Unter),
+            5 => Some(GermanRank::Ober),
+            6 => Some(GermanRank::King),
+            7 => Some(GermanRank::Ace),
+            _ => None,
+        }
+    }
+}
+
+/// Represents a card from a 32-card German-suited deck as used for Skat, Schafkopf and Doppelkopf
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GermanCard {
+    suit: GermanSuit,
+    rank: GermanRank,
+}
+
+impl std::fmt::Display for GermanCard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.rank, self.suit)
+    }
+}
+
+impl GermanCard {
+    /// Construct a card using suit and rank values
+    pub fn new(suit: GermanSuit, rank: GermanRank) -> Self {
+        GermanCard {
+            suit,
+            rank,
+        }
+    }
+
+    /// Get the rank of a card
+    pub fn rank(&self) -> &GermanRank {
+        &self.rank
+    }
+
+    /// Get the suit of a card
+    pub fn suit(&self) -> &GermanSuit {
+        &self.suit
+    }
+}
+
+impl CardType for GermanCard {
+    const NUM_FACES: usize = 32;
+
+    fn to_index(&self) -> usize {
+        self.suit.to_ordinal() as usize * 8 + self.rank.to_ordinal() as usize
+    }
+
+    fn from_index(index: usize) -> Option<Self> {
+        if index >= Self::NUM_FACES {
+            return None;
+        }
+        Some(GermanCard {
+            suit: GermanSuit::from_ordinal((index / 8) as u8)?,
+            rank: GermanRank::from_ordinal((index % 8) as u8)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GermanCard, GermanRank, GermanSuit};
+    use crate::card_type::CardType;
+
+    #[test]
+    fn german_standard_deck() {
+        let deck = GermanCard::standard_deck();
+
+        assert_eq!(deck.len(), 32);
+        assert_eq!(deck[0], GermanCard::new(GermanSuit::Acorns, GermanRank::Seven));
+        assert_eq!(deck[31], GermanCard::new(GermanSuit::Bells, GermanRank::Ace));
+    }
+
+    #[test]
+    fn german_display() {
+        assert_eq!(GermanCard::new(GermanSuit::Leaves, GermanRank::Unter).to_string(), "Ul");
+        assert_eq!(GermanCard::new(GermanSuit::Hearts, GermanRank::Nine).to_string(), "9h");
+    }
+}
@@ -0,0 +1,196 @@
+use crate::card_type::CardType;
+
+/// Represents the month (flower) of a Hanafuda card
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HanafudaMonth {
+    Pine,
+    Plum,
+    Cherry,
+    Wisteria,
+    Iris,
+    Peony,
+    BushClover,
+    Pampas,
+    Chrysanthemum,
+    Maple,
+    Willow,
+    Paulownia,
+}
+
+impl HanafudaMonth {
+    /// Convert a HanafudaMonth variant to its ordinal value (January = 0)
+    pub fn to_ordinal(&self) -> u8 {
+        match self {
+            HanafudaMonth::Pine => 0,
+            HanafudaMonth::Plum => 1,
+            HanafudaMonth::Cherry => 2,
+            HanafudaMonth::Wisteria => 3,
+            HanafudaMonth::Iris => 4,
+            HanafudaMonth::Peony => 5,
+            HanafudaMonth::BushClover => 6,
+            HanafudaMonth::Pampas => 7,
+            HanafudaMonth::Chrysanthemum => 8,
+            HanafudaMonth::Maple => 9,
+            HanafudaMonth::Willow => 10,
+            HanafudaMonth::Paulownia => 11,
+        }
+    }
+
+    /// Convert an ordinal value to a ```HanafudaMonth``` variant. Returns ```None``` for values outside of 0..11
+    pub fn from_ordinal(month: u8) -> Option<Self> {
+        match month {
+            0 => Some(HanafudaMonth::Pine),
+            1 => Some(HanafudaMonth::Plum),
+            2 => Some(HanafudaMonth::Cherry),
+            3 => Some(HanafudaMonth::Wisteria),
+            4 => Some(HanafudaMonth::Iris),
+            5 => Some(HanafudaMonth::Peony),
+            6 => Some(HanafudaMonth::BushClover),
+            7 => Some(HanafudaMonth::Pampas),
+            8 => Some(HanafudaMonth::Chrysanthemum),
+            9 => Some(HanafudaMonth::Maple),
+            10 => Some(HanafudaMonth::Willow),
+            11 => Some(HanafudaMonth::Paulownia),
+            _ => None,
+        }
+    }
+}
+
+/// The scoring category of a Hanafuda card
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HanafudaKind {
+    Bright,
+    Animal,
+    Ribbon,
+    Chaff,
+}
+
+/// The kinds of the four cards of each month, from most to least valuable
+const KINDS: [[HanafudaKind; 4]; 12] = {
+    use HanafudaKind::*;
+    [
+        [Bright, Ribbon, Chaff, Chaff],     // Pine
+        [Animal, Ribbon, Chaff, Chaff],     // Plum
+        [Bright, Ribbon, Chaff, Chaff],     // Cherry
+        [Animal, Ribbon, Chaff, Chaff],     // Wisteria
+        [Animal, Ribbon, Chaff, Chaff],     // Iris
+        [Animal, Ribbon, Chaff, Chaff],     // Peony
+        [Animal, Ribbon, Chaff, Chaff],     // Bush clover
+        [Bright, Animal, Chaff, Chaff],     // Pampas
+        [Animal, Ribbon, Chaff, Chaff],     // Chrysanthemum
+        [Animal, Ribbon, Chaff, Chaff],     // Maple
+        [Bright, Animal, Ribbon, Chaff],    // Willow
+        [Bright, Chaff, Chaff, Chaff],      // Paulownia
+    ]
+};
+
+/// Represents a Hanafuda card. Each month has four cards, identified by their position (0..3) within the month,
+/// ordered from the most to the least valuable card.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "SerializedHanafudaCard"))]
+pub struct HanafudaCard {
+    month: HanafudaMonth,
+    position: u8,
+}
+
+/// The serialized fields of a ```HanafudaCard```, which are checked by ```HanafudaCard::new()``` when deserializing
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct SerializedHanafudaCard {
+    month: HanafudaMonth,
+    position: u8,
+}
+
+#[cfg(feature = "serde")]
+impl std::convert::TryFrom<SerializedHanafudaCard> for HanafudaCard {
+    type Error = &'static str;
+
+    fn try_from(card: SerializedHanafudaCard) -> Result<Self, Self::Error> {
+        HanafudaCard::new(card.month, card.position).ok_or("the position of a Hanafuda card must be 0 to 3")
+    }
+}
+
+impl std::fmt::Display for HanafudaCard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}-{:?}", self.month, self.kind())
+    }
+}
+
+impl HanafudaCard {
+    /// Construct a card from its month and position within the month. Returns ```None``` if the position is not 0..3
+    pub fn new(month: HanafudaMonth, position: u8) -> Option<Self> {
+        if position > 3 {
+            return None;
+        }
+        Some(HanafudaCard {
+            month,
+            position,
+        })
+    }
+
+    /// Get the month of a card
+    pub fn month(&self) -> &HanafudaMonth {
+        &self.month
+    }
+
+    /// Get the position of the card within its month
+    pub fn position(&self) -> u8 {
+        self.position
+    }
+
+    /// Get the scoring category of a card
+    pub fn kind(&self) -> HanafudaKind {
+        KINDS[self.month.to_ordinal() as usize][self.position as usize]
+    }
+}
+
+impl CardType for HanafudaCard {
+    const NUM_FACES: usize = 48;
+
+    fn to_index(&self) -> usize {
+        self.month.to_ordinal() as usize * 4 + self.position as usize
+    }
+
+    fn from_index(index: usize) -> Option<Self> {
+        HanafudaCard::new(HanafudaMonth::from_ordinal((index / 4) as u8)?, (index % 4) as u8)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HanafudaCard, HanafudaKind, HanafudaMonth};
+    use crate::card_type::CardType;
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialized_position() {
+        use super::SerializedHanafudaCard;
+        use std::convert::TryFrom;
+
+        let card = HanafudaCard::try_from(SerializedHanafudaCard { month: HanafudaMonth::Cherry, position: 1 });
+        assert_eq!(card, Ok(HanafudaCard::new(HanafudaMonth::Cherry, 1).unwrap()));
+        assert!(HanafudaCard::try_from(SerializedHanafudaCard { month: HanafudaMonth::Pine, position: 9 }).is_err());
+    }
+
+    #[test]
+    fn hanafuda_composition() {
+        let deck = HanafudaCard::standard_deck();
+        let count = |kind| deck.iter().filter(|c| c.kind() == kind).count();
+
+        assert_eq!(deck.len(), 48);
+        assert_eq!(count(HanafudaKind::Bright), 5);
+        assert_eq!(count(HanafudaKind::Animal), 9);
+        assert_eq!(count(HanafudaKind::Ribbon), 10);
+        assert_eq!(count(HanafudaKind::Chaff), 24);
+    }
+
+    #[test]
+    fn hanafuda_new() {
+        assert_eq!(HanafudaCard::new(HanafudaMonth::Willow, 4), None);
+        assert_eq!(HanafudaCard::new(HanafudaMonth::Willow, 0).unwrap().kind(), HanafudaKind::Bright);
+        assert_eq!(HanafudaCard::from_index(48), None);
+    }
+}
@@ -0,0 +1,873 @@
+use std::fmt::Write;
+use crate::cards::Card;
+use crate::deck::Deck;
+use crate::poker::PokerEvaluator;
+use crate::table::{Action, BettingStructure, Street, Table, TableConfig, TableError, TableEvent, MAX_SEATS};
+
+/// The error returned when a hand history cannot be parsed, holding the number and text of the offending line (for JSON
+/// histories, the line the hand starts on and the offending value or the JSON error)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseHistoryError {
+    line: usize,
+    text: String,
+}
+
+impl ParseHistoryError {
+    pub(crate) fn new(line: usize, text: &str) -> Self {
+        ParseHistoryError { line, text: text.to_string() }
+    }
+
+    /// Get the number of the offending line, starting at 1
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+impl std::fmt::Display for ParseHistoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid hand history line {}: '{}'", self.line, self.text)
+    }
+}
+
+impl std::error::Error for ParseHistoryError {}
+
+/// A player dealt into a recorded hand
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HistoryPlayer {
+    /// The seat number, starting at 1
+    pub seat: usize,
+    pub name: String,
+    /// The stack at the start of the hand
+    pub stack: u64,
+    /// The hole cards, if the history reveals them (dealt to the hero, or shown)
+    pub hole_cards: Option<[Card; 2]>,
+}
+
+/// What a player did in a recorded hand. Amounts are the chips the player put in, apart from raises, which are the
+/// increase over the previous bet and the total the player raised to
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HistoryActionKind {
+    Ante(u64),
+    SmallBlind(u64),
+    BigBlind(u64),
+    Fold,
+    Check,
+    Call(u64),
+    Bet(u64),
+    Raise { by: u64, to: u64 },
+    /// The part of a bet nobody called was returned
+    Uncalled(u64),
+    Show([Card; 2]),
+    Muck,
+    /// The player won chips from a pot (0 is the main pot, then the side pots)
+    Collect { amount: u64, pot: usize },
+}
+
+/// An action in a recorded hand
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HistoryAction {
+    /// The street of the action. Showdowns happen on the last street that was dealt
+    pub street: Street,
+    pub player: String,
+    pub kind: HistoryActionKind,
+    pub all_in: bool,
+}
+
+/// A hand of Hold'em as recorded in a hand history.
+///
+/// Hands are written and read in the PokerStars text format with ```to_pokerstars()``` and ```parse_pokerstars()```,
+/// recorded from a ```Table``` with ```from_table()```, and played again on a table with ```replay()```. Amounts are in
+/// chips, or in cents when the history has a currency (e.g. "$0.25" is 25).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HandHistory {
+    pub id: String,
+    pub table_name: String,
+    pub structure: BettingStructure,
+    pub small_blind: u64,
+    pub big_blind: u64,
+    pub ante: u64,
+    /// The currency symbol of cash games, e.g. "$"
+    pub currency: Option<String>,
+    /// The date and time as written in the history
+    pub date: Option<String>,
+    pub max_seats: usize,
+    /// The seat number of the button
+    pub button: usize,
+    pub players: Vec<HistoryPlayer>,
+    pub actions: Vec<HistoryAction>,
+    pub board: Vec<Card>,
+    pub rake: u64,
+}
+
+impl HandHistory {
+    /// Record the current (or last) hand of a table. Every player's hole cards are known to the table, so they are
+    /// all recorded
+    pub fn from_table(table: &Table, id: &str, table_name: &str) -> Self {
+        let events = table.events();
+        let config = table.config();
+        let name = |seat: usize| table.name(seat).unwrap_or_default().to_string();
+
+        // the stacks at the start of the hand are the current stacks plus what the players put in, minus their winnings
+        let mut stacks: Vec<i128> = (0..table.seats()).map(|s| table.stack(s).unwrap_or(0) as i128).collect();
+        for event in events.iter() {
+            match event {
+                TableEvent::Ante { seat, amount }
+                | TableEvent::SmallBlind { seat, amount }
+                | TableEvent::BigBlind { seat, amount }
+                | TableEvent::Action { seat, amount, .. } => stacks[*seat] += *amount as i128,
+                TableEvent::UncalledBet { seat, amount } | TableEvent::Won { seat, amount, .. } => stacks[*seat] -= *amount as i128,
+                _ => {}
+            }
+        }
+
+        let mut history = HandHistory {
+            id: id.to_string(),
+            table_name: table_name.to_string(),
+            structure: config.structure,
+            small_blind: config.small_blind,
+            big_blind: config.big_blind,
+            ante: config.ante,
+            currency: None,
+            date: None,
+            max_seats: table.seats(),
+            button: 0,
+            players: Vec::new(),
+            actions: Vec::new(),
+            board: table.board().to_vec(),
+            rake: 0,
+        };
+
+        let mut street = Street::Preflop;
+        let mut bets = vec![0; table.seats()];
+        let mut remaining: Vec<u64> = stacks.iter().map(|s| (*s).max(0) as u64).collect();
+        for event in events.iter() {
+            let (seat, kind, all_in) = match event {
+                TableEvent::HandStarted { button } => {
+                    history.button = button + 1;
+                    continue;
+                }
+                TableEvent::Ante { seat, amount } => {
+                    remaining[*seat] -= amount;
+                    (*seat, HistoryActionKind::Ante(*amount), remaining[*seat] == 0)
+                }
+                TableEvent::SmallBlind { seat, amount } => {
+                    remaining[*seat] -= amount;
+                    bets[*seat] += amount;
+                    (*seat, HistoryActionKind::SmallBlind(*amount), remaining[*seat] == 0)
+                }
+                TableEvent::BigBlind { seat, amount } => {
+                    remaining[*seat] -= amount;
+                    bets[*seat] += amount;
+                    (*seat, HistoryActionKind::BigBlind(*amount), remaining[*seat] == 0)
+                }
+                TableEvent::HoleCards { seat, cards } => {
+                    history.players.push(HistoryPlayer {
+                        seat: seat + 1,
+                        name: name(*seat),
+                        stack: stacks[*seat].max(0) as u64,
+                        hole_cards: Some(*cards),
+                    });
+                    continue;
+                }
+                TableEvent::Board { street: s, .. } => {
+                    street = *s;
+                    bets.iter_mut().for_each(|b| *b = 0);
+                    continue;
+                }
+                TableEvent::Action { seat, action, amount, all_in } => {
+                    let current = bets.iter().max().cloned().unwrap_or(0);
+                    bets[*seat] += amount;
+                    let kind = match action {
+                        Action::Fold => HistoryActionKind::Fold,
+                        Action::Check => HistoryActionKind::Check,
+                        Action::Call => HistoryActionKind::Call(*amount),
+                        Action::Bet(_) => HistoryActionKind::Bet(*amount),
+                        Action::Raise(to) => HistoryActionKind::Raise { by: to - current, to: *to },
+                    };
+                    (*seat, kind, *all_in)
+                }
+                TableEvent::UncalledBet { seat, amount } => (*seat, HistoryActionKind::Uncalled(*amount), false),
+                TableEvent::Showdown { seat, cards, .. } => (*seat, HistoryActionKind::Show(*cards), false),
+                TableEvent::Won { seat, amount, pot } => (*seat, HistoryActionKind::Collect { amount: *amount, pot: *pot }, false),
+            };
+            // the uncalled bet goes back on the street it was made, before the board is run out
+            let street = match kind {
+                HistoryActionKind::Uncalled(_) => history.actions.last().map_or(street, |a| a.street),
+                _ => street,
+            };
+            history.actions.push(HistoryAction { street, player: name(seat), kind, all_in });
+        }
+        history.players.sort_by_key(|p| p.seat);
+        history
+    }
+
+    /// Parse a single hand in the PokerStars text format. Lines the parser does not know (e.g. chat) are skipped
+    pub fn parse_pokerstars(text: &str) -> Result<HandHistory, ParseHistoryError> {
+        let mut hands = HandHistory::parse_pokerstars_all(text)?.into_iter();
+        match (hands.next(), hands.next()) {
+            (Some(hand), None) => Ok(hand),
+            (None, _) => Err(ParseHistoryError::new(1, text.lines().next().unwrap_or_default())),
+            (Some(_), Some(second)) => {
+                let (line, text) = lines(text).find(|(_, l)| is_header(l) && l.contains(&format!("#{}:", second.id))).unwrap_or((1, ""));
+                Err(ParseHistoryError::new(line, text))
+            }
+        }
+    }
+
+    /// Parse every hand of a PokerStars hand history file, where hands start with their "PokerStars Hand #" line
+    pub fn parse_pokerstars_all(text: &str) -> Result<Vec<HandHistory>, ParseHistoryError> {
+        let mut hands: Vec<Vec<(usize, &str)>> = Vec::new();
+        for (number, line) in lines(text) {
+            if is_header(line) {
+                hands.push(Vec::new());
+            }
+            match hands.last_mut() {
+                Some(hand) => hand.push((number, line)),
+                None if line.is_empty() => {}
+                None => return Err(ParseHistoryError::new(number, line)),
+            }
+        }
+        hands.iter().map(|hand| parse_hand(hand)).collect()
+    }
+
+    /// Write the hand in the PokerStars text format
+    pub fn to_pokerstars(&self) -> String {
+        let mut out = String::new();
+        let structure = match self.structure {
+            BettingStructure::NoLimit => "No Limit",
+            BettingStructure::PotLimit => "Pot Limit",
+            BettingStructure::FixedLimit => "Limit",
+        };
+        // limit games show the small and big bet instead of the blinds
+        let stakes = match self.structure {
+            BettingStructure::FixedLimit => (self.big_blind, 2 * self.big_blind),
+            _ => (self.small_blind, self.big_blind),
+        };
+        let _ = write!(out, "PokerStars Hand #{}:  Hold'em {} ({}/{})", self.id, structure, self.amount(stakes.0), self.amount(stakes.1));
+        if let Some(date) = &self.date {
+            let _ = write!(out, " - {}", date);
+        }
+        let _ = writeln!(out);
+        let _ = writeln!(out, "Table '{}' {}-max Seat #{} is the button", self.table_name, self.max_seats, self.button);
+        for p in self.players.iter() {
+            let _ = writeln!(out, "Seat {}: {} ({} in chips)", p.seat, p.name, self.amount(p.stack));
+        }
+
+        let posts = self.actions.iter().take_while(|a| is_post(&a.kind)).count();
+        for action in self.actions[..posts].iter() {
+            let _ = writeln!(out, "{}", self.action_line(action));
+        }
+        let _ = writeln!(out, "*** HOLE CARDS ***");
+        for p in self.players.iter() {
+            let shows = self.actions.iter().any(|a| a.player == p.name && matches!(a.kind, HistoryActionKind::Show(_)));
+            if let (Some([a, b]), false) = (p.hole_cards, shows) {
+                let _ = writeln!(out, "Dealt to {} [{} {}]", p.name, a, b);
+            }
+        }
+
+        let mut street = Street::Preflop;
+        let mut showdown = false;
+        for action in self.actions[posts..].iter() {
+            while street < action.street {
+                street = self.write_street(&mut out, street);
+            }
+            if !showdown && matches!(action.kind, HistoryActionKind::Show(_) | HistoryActionKind::Muck) {
+                let _ = writeln!(out, "*** SHOW DOWN ***");
+                showdown = true;
+            }
+            let _ = writeln!(out, "{}", self.action_line(action));
+        }
+        while street < Street::River && self.board.len() > board_len(street) {
+            street = self.write_street(&mut out, street);
+        }
+
+        let _ = writeln!(out, "*** SUMMARY ***");
+        let collected: u64 = self.actions.iter().map(|a| match a.kind {
+            HistoryActionKind::Collect { amount, .. } => amount,
+            _ => 0,
+        }).sum();
+        let _ = writeln!(out, "Total pot {} | Rake {}", self.amount(collected + self.rake), self.amount(self.rake));
+        if !self.board.is_empty() {
+            let _ = writeln!(out, "Board [{}]", cards_text(&self.board));
+        }
+        for p in self.players.iter() {
+            let _ = writeln!(out, "{}", self.summary_line(p));
+        }
+        out
+    }
+
+    /// Play the hand again on a table with the history's stakes, seats and button, dealing the known cards to the
+    /// same players and board (and the other cards in deck order). Returns the table once every fold, check, call,
+    /// bet and raise of the history was played, or the first error, e.g. when an action is out of turn or not legal, or
+    /// when a card is dealt twice or the big blind is 0
+    pub fn replay(&self) -> Result<Table, TableError> {
+        if repeats_a_card(self) {
+            return Err(TableError::InvalidDeck);
+        }
+        if self.big_blind == 0 {
+            return Err(TableError::InvalidBlinds);
+        }
+        let seats = self.players.iter().map(|p| p.seat).max().unwrap_or(0).max(self.max_seats).max(2);
+        if seats > MAX_SEATS {
+            return Err(TableError::SeatOutOfRange(seats - 1));
+        }
+        let config = TableConfig {
+            structure: self.structure,
+            small_blind: self.small_blind,
+            big_blind: self.big_blind,
+            ante: self.ante,
+        };
+        let mut table = Table::new(config, seats);
+        for p in self.players.iter() {
+            table.sit(p.seat.checked_sub(1).ok_or(TableError::SeatOutOfRange(0))?, &p.name, p.stack)?;
+        }
+        let button = self.button.checked_sub(1).ok_or(TableError::SeatOutOfRange(0))?;
+        table.set_button(button)?;
+
+        // the table deals one card to each player from the left of the button, then the second, then burns a card
+        // before each street
+        let mut dealt: Vec<&HistoryPlayer> = self.players.iter().filter(|p| p.stack > 0).collect();
+        dealt.sort_by_key(|p| (p.seat - 1 + seats - button - 1) % seats);
+        let count = dealt.len();
+        let mut draws = vec![None; 2 * count + 8];
+        for (i, p) in dealt.iter().enumerate() {
+            if let Some([a, b]) = p.hole_cards {
+                draws[i] = Some(a);
+                draws[count + i] = Some(b);
+            }
+        }
+        for (card, position) in self.board.iter().zip([1, 2, 3, 5, 7].iter()) {
+            draws[2 * count + position] = Some(*card);
+        }
+        let mut unknown = Deck::new().iter().filter(|c| !draws.contains(&Some(**c))).cloned().collect::<Vec<Card>>().into_iter();
+        let top: Vec<Card> = draws.into_iter().filter_map(|c| c.or_else(|| unknown.next())).collect();
+        table.start_hand(Deck::stacked(&top, Deck::new()).map_err(|_| TableError::InvalidDeck)?)?;
+
+        for action in self.actions.iter() {
+            let replayed = match action.kind {
+                HistoryActionKind::Fold => Action::Fold,
+                HistoryActionKind::Check => Action::Check,
+                HistoryActionKind::Call(_) => Action::Call,
+                HistoryActionKind::Bet(amount) => Action::Bet(amount),
+                HistoryActionKind::Raise { to, .. } => Action::Raise(to),
+                _ => continue,
+            };
+            let seat = self.players.iter().find(|p| p.name == action.player).map(|p| p.seat - 1);
+            if seat.is_none() || table.to_act() != seat {
+                return Err(TableError::IllegalAction(replayed));
+            }
+            table.act(replayed)?;
+        }
+        Ok(table)
+    }
+
+    /// Format an amount of chips, or of cents if the hand has a currency
+    fn amount(&self, amount: u64) -> String {
+        match &self.currency {
+            None => amount.to_string(),
+            Some(symbol) => match amount % 100 {
+                0 => format!("{}{}", symbol, amount / 100),
+                cents => format!("{}{}.{:02}", symbol, amount / 100, cents),
+            },
+        }
+    }
+
+    fn action_line(&self, action: &HistoryAction) -> String {
+        let name = &action.player;
+        let line = match action.kind {
+            HistoryActionKind::Ante(amount) => format!("{}: posts the ante {}", name, self.amount(amount)),
+            HistoryActionKind::SmallBlind(amount) => format!("{}: posts small blind {}", name, self.amount(amount)),
+            HistoryActionKind::BigBlind(amount) => format!("{}: posts big blind {}", name, self.amount(amount)),
+            HistoryActionKind::Fold => format!("{}: folds", name),
+            HistoryActionKind::Check => format!("{}: checks", name),
+            HistoryActionKind::Call(amount) => format!("{}: calls {}", name, self.amount(amount)),
+            HistoryActionKind::Bet(amount) => format!("{}: bets {}", name, self.amount(amount)),
+            HistoryActionKind::Raise { by, to } => format!("{}: raises {} to {}", name, self.amount(by), self.amount(to)),
+            HistoryActionKind::Uncalled(amount) => format!("Uncalled bet ({}) returned to {}", self.amount(amount), name),
+            HistoryActionKind::Show(hole) => {
+                let cards: Vec<Card> = hole.iter().chain(self.board.iter()).cloned().collect();
+                match PokerEvaluator::evaluate(&cards) {
+                    Some(rank) => format!("{}: shows [{}] ({})", name, cards_text(&hole), rank.description()),
+                    None => format!("{}: shows [{}]", name, cards_text(&hole)),
+                }
+            }
+            HistoryActionKind::Muck => format!("{}: mucks hand", name),
+            HistoryActionKind::Collect { amount, pot } => {
+                let side_pots = self.actions.iter().any(|a| matches!(a.kind, HistoryActionKind::Collect { pot, .. } if pot > 0));
+                let from = match (side_pots, pot) {
+                    (false, _) => "pot".to_string(),
+                    (true, 0) => "main pot".to_string(),
+                    (true, pot) => format!("side pot-{}", pot),
+                };
+                format!("{} collected {} from {}", name, self.amount(amount), from)
+            }
+        };
+        if action.all_in {
+            line + " and is all-in"
+        } else {
+            line
+        }
+    }
+
+    /// Write the header of the street after ```street```, returning that street
+    fn write_street(&self, out: &mut String, street: Street) -> Street {
+        let (next, header) = match street {
+            Street::Preflop => (Street::Flop, "FLOP"),
+            Street::Flop => (Street::Turn, "TURN"),
+            _ => (Street::River, "RIVER"),
+        };
+        let shown = board_len(street).min(self.board.len());
+        let dealt = board_len(next).min(self.board.len());
+        let _ = match street {
+            Street::Preflop => writeln!(out, "*** {} *** [{}]", header, cards_text(&self.board[..dealt])),
+            _ => writeln!(out, "*** {} *** [{}] [{}]", header, cards_text(&self.board[..shown]), cards_text(&self.board[shown..dealt])),
+        };
+        next
+    }
+
+    fn summary_line(&self, player: &HistoryPlayer) -> String {
+        let mut line = format!("Seat {}: {}", player.seat, player.name);
+        if player.seat == self.button {
+            line.push_str(" (button)");
+        }
+        let actions: Vec<&HistoryAction> = self.actions.iter().filter(|a| a.player == player.name).collect();
+        if actions.iter().any(|a| matches!(a.kind, HistoryActionKind::SmallBlind(_))) {
+            line.push_str(" (small blind)");
+        }
+        if actions.iter().any(|a| matches!(a.kind, HistoryActionKind::BigBlind(_))) {
+            line.push_str(" (big blind)");
+        }
+
+        let won: u64 = actions.iter().map(|a| match a.kind {
+            HistoryActionKind::Collect { amount, .. } => amount,
+            _ => 0,
+        }).sum();
+        let shown = actions.iter().find_map(|a| match a.kind {
+            HistoryActionKind::Show(cards) => Some(cards),
+            _ => None,
+        });
+        if let Some(fold) = actions.iter().find(|a| a.kind == HistoryActionKind::Fold) {
+            match fold.street {
+                Street::Preflop => line.push_str(" folded before Flop"),
+                street => {
+                    let _ = write!(line, " folded on the {:?}", street);
+                }
+            }
+        } else if let Some(cards) = shown {
+            let _ = match won {
+                0 => write!(line, " showed [{}] and lost", cards_text(&cards)),
+                won => write!(line, " showed [{}] and won ({})", cards_text(&cards), self.amount(won)),
+            };
+        } else if won > 0 {
+            let _ = write!(line, " collected ({})", self.amount(won));
+        } else if actions.iter().any(|a| a.kind == HistoryActionKind::Muck) {
+            line.push_str(" mucked");
+        }
+        line
+    }
+}
+
+/// The lines of a text with their numbers (starting at 1), trimmed
+fn lines(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines().enumerate().map(|(i, line)| (i + 1, line.trim_start_matches('\u{feff}').trim()))
+}
+
+fn is_header(line: &str) -> bool {
+    line.starts_with("PokerStars ") && (line.contains(" Hand #") || line.contains(" Game #"))
+}
+
+fn is_post(kind: &HistoryActionKind) -> bool {
+    matches!(kind, HistoryActionKind::Ante(_) | HistoryActionKind::SmallBlind(_) | HistoryActionKind::BigBlind(_))
+}
+
+/// The number of board cards dealt by the street
+pub(crate) fn board_len(street: Street) -> usize {
+    match street {
+        Street::Preflop => 0,
+        Street::Flop => 3,
+        Street::Turn => 4,
+        Street::River => 5,
+    }
+}
+
+/// Returns true if the same card is among the hole cards or the board more than once
+fn repeats_a_card(hand: &HandHistory) -> bool {
+    let known: Vec<Card> = hand.players.iter().filter_map(|p| p.hole_cards).flatten().chain(hand.board.iter().cloned()).collect();
+    known.iter().enumerate().any(|(i, card)| known[..i].contains(card))
+}
+
+fn cards_text(cards: &[Card]) -> String {
+    cards.iter().map(|c| c.to_string()).collect::<Vec<String>>().join(" ")
+}
+
+/// Parse the cards of every bracketed group in the text, e.g. "[Ah Kd 2c] [7s]"
+fn parse_cards(text: &str) -> Option<Vec<Card>> {
+    let mut cards = Vec::new();
+    for group in text.split('[').skip(1) {
+        let (group, _) = group.split_once(']')?;
+        for card in group.split_whitespace() {
+            cards.push(card.parse().ok()?);
+        }
+    }
+    Some(cards)
+}
+
+fn parse_hole_cards(text: &str) -> Option<[Card; 2]> {
+    match parse_cards(text)?.as_slice() {
+        [a, b] => Some([*a, *b]),
+        _ => None,
+    }
+}
+
+/// Parse an amount of chips, or of cents (e.g. "$1.50") if the hand has a currency
+fn parse_amount(text: &str, currency: bool) -> Option<u64> {
+    let digits = text.trim().trim_start_matches(|c: char| !c.is_ascii_digit()).replace(',', "");
+    if !currency {
+        return digits.parse().ok();
+    }
+    let (whole, cents) = digits.split_once('.').unwrap_or((&digits, "0"));
+    if cents.is_empty() || cents.len() > 2 || !cents.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let scale = if cents.len() == 1 { 10 } else { 1 };
+    Some(whole.parse::<u64>().ok()? * 100 + cents.parse::<u64>().ok()? * scale)
+}
+
+/// Parse the lines of a single hand, starting with its header
+fn parse_hand(lines: &[(usize, &str)]) -> Result<HandHistory, ParseHistoryError> {
+    let (number, header) = lines[0];
+    let error = ParseHistoryError::new;
+    let header_error = || error(number, header);
+
+    let id = header.split_once('#').and_then(|(_, rest)| rest.split_once(':')).map(|(id, _)| id.trim()).ok_or_else(header_error)?;
+    let game = &header[header.find("Hold'em").ok_or_else(header_error)?..];
+    let structure = if game.starts_with("Hold'em No Limit") {
+        BettingStructure::NoLimit
+    } else if game.starts_with("Hold'em Pot Limit") {
+        BettingStructure::PotLimit
+    } else if game.starts_with("Hold'em Limit") {
+        BettingStructure::FixedLimit
+    } else {
+        return Err(header_error());
+    };
+
+    let stakes = game.split_once('(').and_then(|(_, s)| s.split_once(')')).map(|(s, _)| s).ok_or_else(header_error)?;
+    let (low, high) = stakes.split_whitespace().next().and_then(|s| s.split_once('/')).ok_or_else(header_error)?;
+    let symbol: String = low.chars().take_while(|c| !c.is_ascii_digit()).collect();
+    let currency = !symbol.is_empty();
+    let (low, high) = (parse_amount(low, currency).ok_or_else(header_error)?, parse_amount(high, currency).ok_or_else(header_error)?);
+    let (small_blind, big_blind) = match structure {
+        BettingStructure::FixedLimit => (low / 2, low),
+        _ => (low, high),
+    };
+    if big_blind == 0 {
+        return Err(header_error());
+    }
+
+    let mut hand = HandHistory {
+        id: id.to_string(),
+        table_name: String::new(),
+        structure,
+        small_blind,
+        big_blind,
+        ante: 0,
+        currency: if currency { Some(symbol) } else { None },
+        date: header.rsplit_once(" - ").map(|(_, date)| date.to_string()).filter(|d| d.contains('/')),
+        max_seats: 0,
+        button: 0,
+        players: Vec::new(),
+        actions: Vec::new(),
+        board: Vec::new(),
+        rake: 0,
+    };
+
+    let mut street = Street::Preflop;
+    let mut dealt = false;
+    let mut summary = false;
+    for (number, line) in lines[1..].iter().cloned() {
+        let error = || error(number, line);
+        let amount = |text: &str| parse_amount(text, currency).ok_or_else(error);
+
+        if summary {
+            if let Some(rake) = line.strip_prefix("Total pot ").and_then(|l| l.split_once("| Rake ")).map(|(_, r)| r) {
+                hand.rake = amount(rake.split_whitespace().next().unwrap_or_default())?;
+            }
+        } else if let Some(rest) = line.strip_prefix("Table '") {
+            let (name, rest) = rest.rsplit_once('\'').ok_or_else(error)?;
+            hand.table_name = name.to_string();
+            for word in rest.split_whitespace() {
+                if let Some(max) = word.strip_suffix("-max") {
+                    hand.max_seats = max.parse().map_err(|_| error())?;
+                } else if let Some(button) = word.strip_prefix('#') {
+                    hand.button = button.parse().map_err(|_| error())?;
+                }
+            }
+        } else if line.starts_with("Seat ") && !dealt {
+            let (seat, rest) = line["Seat ".len()..].split_once(": ").ok_or_else(error)?;
+            let (name, chips) = rest.rsplit_once(" (").filter(|(_, c)| c.contains(" in chips")).ok_or_else(error)?;
+            if !chips.contains("sitting out") {
+                hand.players.push(HistoryPlayer {
+                    seat: seat.parse().map_err(|_| error())?,
+                    name: name.to_string(),
+                    stack: amount(chips.split(" in chips").next().unwrap_or_default())?,
+                    hole_cards: None,
+                });
+            }
+        } else if let Some(marker) = line.strip_prefix("*** ") {
+            dealt = true;
+            let next = if marker.starts_with("FLOP") {
+                Some(Street::Flop)
+            } else if marker.starts_with("TURN") {
+                Some(Street::Turn)
+            } else if marker.starts_with("RIVER") {
+                Some(Street::River)
+            } else {
+                summary = marker.starts_with("SUMMARY");
+                None
+            };
+            if let Some(next) = next {
+                street = next;
+                hand.board = parse_cards(marker).filter(|b| b.len() == board_len(street)).ok_or_else(error)?;
+                if repeats_a_card(&hand) {
+                    return Err(error());
+                }
+            }
+        } else if let Some(rest) = line.strip_prefix("Dealt to ") {
+            dealt = true;
+            let name = player_prefix(&hand, rest, " [").ok_or_else(error)?;
+            let cards = parse_hole_cards(&rest[name.len()..]).ok_or_else(error)?;
+            if let Some(p) = hand.players.iter_mut().find(|p| p.name == name) {
+                p.hole_cards = Some(cards);
+            }
+            if repeats_a_card(&hand) {
+                return Err(error());
+            }
+        } else if let Some(rest) = line.strip_prefix("Uncalled bet (") {
+            let (uncalled, name) = rest.split_once(") returned to ").ok_or_else(error)?;
+            let kind = HistoryActionKind::Uncalled(amount(uncalled)?);
+            hand.actions.push(HistoryAction { street, player: name.to_string(), kind, all_in: false });
+        } else if let Some(name) = player_prefix(&hand, line, " collected ") {
+            let rest = &line[name.len() + " collected ".len()..];
+            let (collected, from) = rest.split_once(" from ").ok_or_else(error)?;
+            let pot = match from.trim() {
+                "pot" | "main pot" => 0,
+                "side pot" => 1,
+                side => side.strip_prefix("side pot-").and_then(|p| p.parse().ok()).ok_or_else(error)?,
+            };
+            let kind = HistoryActionKind::Collect { amount: amount(collected)?, pot };
+            hand.actions.push(HistoryAction { street, player: name, kind, all_in: false });
+        } else if let Some(name) = player_prefix(&hand, line, ": ") {
+            let rest = &line[name.len() + 2..];
+            let (rest, all_in) = match rest.strip_suffix(" and is all-in") {
+                Some(rest) => (rest, true),
+                None => (rest, false),
+            };
+            let kind = if rest == "folds" || rest.starts_with("folds [") {
+                HistoryActionKind::Fold
+            } else if rest == "checks" {
+                HistoryActionKind::Check
+            } else if rest == "mucks hand" {
+                HistoryActionKind::Muck
+            } else if let Some(called) = rest.strip_prefix("calls ") {
+                HistoryActionKind::Call(amount(called)?)
+            } else if let Some(bet) = rest.strip_prefix("bets ") {
+                HistoryActionKind::Bet(amount(bet)?)
+            } else if let Some(raise) = rest.strip_prefix("raises ") {
+                let (by, to) = raise.split_once(" to ").ok_or_else(error)?;
+                let (by, to) = (amount(by)?, amount(to)?);
+                // the raise is counted from the bet called, so it cannot add up to less than itself
+                if to < by {
+                    return Err(error());
+                }
+                HistoryActionKind::Raise { by, to }
+            } else if let Some(ante) = rest.strip_prefix("posts the ante ") {
+                HistoryActionKind::Ante(amount(ante)?)
+            } else if let Some(blind) = rest.strip_prefix("posts small blind ") {
+                HistoryActionKind::SmallBlind(amount(blind)?)
+            } else if let Some(blind) = rest.strip_prefix("posts big blind ") {
+                HistoryActionKind::BigBlind(amount(blind)?)
+            } else if let Some(shown) = rest.strip_prefix("shows ") {
+                let cards = parse_hole_cards(shown.split(" (").next().unwrap_or_default()).ok_or_else(error)?;
+                if let Some(p) = hand.players.iter_mut().find(|p| p.name == name) {
+                    p.hole_cards = p.hole_cards.or(Some(cards));
+                }
+                if repeats_a_card(&hand) {
+                    return Err(error());
+                }
+                HistoryActionKind::Show(cards)
+            } else {
+                continue;
+            };
+            if let HistoryActionKind::Ante(ante) = kind {
+                hand.ante = hand.ante.max(ante);
+            }
+            hand.actions.push(HistoryAction { street, player: name, kind, all_in });
+        }
+    }
+
+    if hand.players.is_empty() {
+        return Err(header_error());
+    }
+    hand.max_seats = hand.max_seats.max(hand.players.iter().map(|p| p.seat).max().unwrap_or(0));
+    Ok(hand)
+}
+
+/// Find the (longest) name of a player that the line starts with, followed by the separator
+fn player_prefix(hand: &HandHistory, line: &str, separator: &str) -> Option<String> {
+    hand.players.iter()
+        .map(|p| &p.name)
+        .filter(|name| line.starts_with(name.as_str()) && line[name.len()..].starts_with(separator))
+        .max_by_key(|name| name.len())
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HandHistory, HistoryActionKind};
+    use crate::cards::cards;
+    use crate::deck::Deck;
+    use crate::table::{Action, Street, Table, TableConfig, TableError};
+
+    const CASH_HAND: &str = "PokerStars Hand #208765064830:  Hold'em No Limit ($0.05/$0.10 USD) - 2020/01/19 17:12:38 ET
+Table 'Alcor III' 6-max Seat #3 is the button
+Seat 1: Alice ($10 in chips)
+Seat 3: Bob (the Builder) ($12.35 in chips)
+Seat 4: Carol ($4.20 in chips)
+Seat 6: Dave ($10.05 in chips) is sitting out
+Carol: posts small blind $0.05
+Alice: posts big blind $0.10
+*** HOLE CARDS ***
+Dealt to Alice [Ah Kd]
+Bob (the Builder): raises $0.20 to $0.30
+Carol: calls $0.25
+Alice: folds
+Dave said, \"nh\"
+*** FLOP *** [2c 7d Th]
+Carol: bets $3.90 and is all-in
+Bob (the Builder): calls $3.90
+*** TURN *** [2c 7d Th] [Ks]
+*** RIVER *** [2c 7d Th Ks] [5s]
+*** SHOW DOWN ***
+Carol: shows [Tc Td] (three of a kind, Tens)
+Bob (the Builder): shows [Ac Kc] (a pair of Kings)
+Carol collected $8.30 from pot
+*** SUMMARY ***
+Total pot $8.50 | Rake $0.20
+Board [2c 7d Th Ks 5s]
+Seat 1: Alice (big blind) folded before Flop
+Seat 3: Bob (the Builder) (button) showed [Ac Kc] and lost with a pair of Kings
+Seat 4: Carol (small blind) showed [Tc Td] and won ($8.30) with three of a kind, Tens
+";
+
+    #[test]
+    fn parse_cash_hand() {
+        let hand = HandHistory::parse_pokerstars(CASH_HAND).unwrap();
+
+        assert_eq!(hand.id, "208765064830");
+        assert_eq!(hand.table_name, "Alcor III");
+        assert_eq!((hand.small_blind, hand.big_blind, hand.currency.as_deref()), (5, 10, Some("$")));
+        assert_eq!(hand.date.as_deref(), Some("2020/01/19 17:12:38 ET"));
+        assert_eq!((hand.max_seats, hand.button, hand.rake), (6, 3, 20));
+        assert_eq!(hand.players.len(), 3);
+        assert_eq!(hand.players[1].name, "Bob (the Builder)");
+        assert_eq!(hand.players[1].stack, 1235);
+        assert_eq!(hand.players[0].hole_cards, Some([cards("Ah")[0], cards("Kd")[0]]));
+        assert_eq!(hand.players[2].hole_cards, Some([cards("Tc")[0], cards("Td")[0]]));
+        assert_eq!(hand.board, cards("2c 7d Th Ks 5s"));
+
+        let kinds: Vec<HistoryActionKind> = hand.actions.iter().map(|a| a.kind).collect();
+        assert_eq!(kinds[2], HistoryActionKind::Raise { by: 20, to: 30 });
+        assert_eq!(kinds[4], HistoryActionKind::Fold);
+        assert_eq!(kinds[5], HistoryActionKind::Bet(390));
+        assert!(hand.actions[5].all_in && hand.actions[5].street == Street::Flop);
+        assert_eq!(kinds.last(), Some(&HistoryActionKind::Collect { amount: 830, pot: 0 }));
+
+        // the hand plays out the same way on the table engine
+        let table = hand.replay().unwrap();
+        assert!(!table.in_hand());
+        assert_eq!(table.stack(3), Some(420 + 420 + 10));
+        assert_eq!(table.stack(2), Some(1235 - 420));
+    }
+
+    #[test]
+    fn table_round_trip() {
+        let top = cards("Ah Kh 2c As Kd 7d 3s Qc Jh 2d 4d 5s 9c 8h");
+        let mut table = Table::new(TableConfig::no_limit(5, 10).ante(1), 6);
+        table.sit(0, "Alice", 1000).unwrap();
+        table.sit(2, "Bob", 100).unwrap();
+        table.sit(4, "Carol", 300).unwrap();
+        table.start_hand(Deck::stacked(&top, Deck::new()).unwrap()).unwrap();
+        table.act(Action::Raise(30)).unwrap();
+        table.act(Action::Raise(99)).unwrap();
+        table.act(Action::Call).unwrap();
+        table.act(Action::Raise(400)).unwrap();
+        table.act(Action::Call).unwrap();
+
+        let history = HandHistory::from_table(&table, "1", "Test");
+        assert_eq!(history.players.iter().map(|p| p.stack).collect::<Vec<u64>>(), vec![1000, 100, 300]);
+        assert!(history.actions.contains(&super::HistoryAction {
+            street: Street::Preflop,
+            player: "Alice".to_string(),
+            kind: HistoryActionKind::Raise { by: 301, to: 400 },
+            all_in: false,
+        }));
+
+        let text = history.to_pokerstars();
+        assert!(text.contains("Bob: raises 69 to 99 and is all-in\n"));
+        assert!(text.contains("Uncalled bet (101) returned to Alice\n"));
+        assert!(text.contains("*** RIVER *** [Qc Jh 2d 5s] [8h]\n"));
+        assert!(text.contains("Bob collected 300 from main pot\n"));
+
+        let parsed = HandHistory::parse_pokerstars(&text).unwrap();
+        assert_eq!(parsed, history);
+        let replayed = parsed.replay().unwrap();
+        for seat in 0..6 {
+            assert_eq!(replayed.stack(seat), table.stack(seat));
+        }
+        assert_eq!(replayed.events(), table.events());
+    }
+
+    #[test]
+    fn parse_files() {
+        let second = CASH_HAND.replace("#208765064830", "#208765064831");
+        let file = format!("\u{feff}{}\n\n\n{}", CASH_HAND, second);
+        let hands = HandHistory::parse_pokerstars_all(&file).unwrap();
+
+        assert_eq!(hands.len(), 2);
+        assert_eq!(hands[1].id, "208765064831");
+        assert_eq!(HandHistory::parse_pokerstars(&file).unwrap_err().line(), 33);
+    }
+
+    #[test]
+    fn invalid_histories() {
+        let bad_amount = CASH_HAND.replace("calls $0.25", "calls $0.2x5");
+        let error = HandHistory::parse_pokerstars(&bad_amount).unwrap_err();
+        assert_eq!(error.line(), 12);
+        assert_eq!(error.to_string(), "invalid hand history line 12: 'Carol: calls $0.2x5'");
+
+        assert!(HandHistory::parse_pokerstars(&CASH_HAND.replace("No Limit", "Razz")).is_err());
+        assert!(HandHistory::parse_pokerstars(&CASH_HAND.replace("[2c 7d Th]", "[2c 7d]")).is_err());
+        assert!(HandHistory::parse_pokerstars("Dealt to Alice [Ah Kd]").is_err());
+
+        // cards dealt twice and raises to less than the raise itself
+        let error = HandHistory::parse_pokerstars(&CASH_HAND.replace("[2c 7d Th]", "[2c 7d Kd]")).unwrap_err();
+        assert_eq!(error.line(), 15);
+        let error = HandHistory::parse_pokerstars(&CASH_HAND.replace("[Ks]", "[Kd]")).unwrap_err();
+        assert_eq!(error.line(), 18);
+        let error = HandHistory::parse_pokerstars(&CASH_HAND.replace("shows [Tc Td]", "shows [Tc Th]")).unwrap_err();
+        assert_eq!(error.line(), 21);
+        let error = HandHistory::parse_pokerstars(&CASH_HAND.replace("raises $0.20 to $0.30", "raises $0.30 to $0.20")).unwrap_err();
+        assert_eq!(error.to_string(), "invalid hand history line 11: 'Bob (the Builder): raises $0.30 to $0.20'");
+        let mut hand = HandHistory::parse_pokerstars(CASH_HAND).unwrap();
+        hand.board[4] = cards("Ah")[0];
+        assert_eq!(hand.replay().err(), Some(TableError::InvalidDeck));
+        let mut hand = HandHistory::parse_pokerstars(CASH_HAND).unwrap();
+        hand.big_blind = 0;
+        assert_eq!(hand.replay().err(), Some(TableError::InvalidBlinds));
+        assert!(HandHistory::parse_pokerstars("").is_err());
+    }
+}
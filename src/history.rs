@@ -0,0 +1,175 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use crate::card_type::CardType;
+
+/// A source of sequence numbers for history records. Decks that share a clock (e.g. a shoe, the players' hands and the
+/// discard pile) number their records from the same sequence, so their histories can be merged into a single, totally
+/// ordered audit trail.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryClock(Arc<AtomicU64>);
+
+impl HistoryClock {
+    /// Create a new clock, starting at sequence number 0
+    pub fn new() -> Self {
+        HistoryClock::default()
+    }
+
+    /// Get the next sequence number
+    pub fn tick(&self) -> u64 {
+        self.0.fetch_add(1, Ordering::SeqCst)
+    }
+}
+
+/// Describes a single mutation of a deck. Positions are counted from the bottom of the deck (see ```Deck```).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DeckEvent<C: CardType> {
+    /// A card was removed from the given position
+    Draw { card: C, position: usize },
+    /// A card was placed at the given position
+    Add { card: C, position: usize },
+    /// Another deck was placed on top of this deck. ```position``` is the position of its bottom card
+    AddDeck { cards: Vec<C>, position: usize },
+    /// All of the cards were moved to another deck (see ```Deck::add_deck()```)
+    Emptied { count: usize },
+    /// The deck was shuffled (or otherwise reordered), resulting in the given order (bottom first)
+    Shuffle { order: Vec<C> },
+    /// The top ```count``` cards were moved to the bottom of the deck
+    Cut { count: usize },
+}
+
+/// A ```DeckEvent``` together with its sequence number
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeckRecord<C: CardType> {
+    pub sequence: u64,
+    pub event: DeckEvent<C>,
+}
+
+impl<C: CardType> DeckRecord<C> {
+    /// Returns true if the record involves the given card. Shuffles involve every card in the deck
+    pub fn involves(&self, card: &C) -> bool {
+        match &self.event {
+            DeckEvent::Draw { card: c, .. } | DeckEvent::Add { card: c, .. } => c == card,
+            DeckEvent::AddDeck { cards, .. } | DeckEvent::Shuffle { order: cards } => cards.contains(card),
+            DeckEvent::Emptied { .. } | DeckEvent::Cut { .. } => false,
+        }
+    }
+}
+
+/// The audit trail of a deck, recording every mutation in the order it happened. Enable it with
+/// ```Deck::enable_history()```.
+///
+/// The clock is not serialized. A deserialized history numbers new records from a clock of its own, which resumes after
+/// the sequence number of its last record.
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "SerializedHistory<C>"))]
+pub struct DeckHistory<C: CardType> {
+    #[cfg_attr(feature = "serde", serde(skip))]
+    clock: HistoryClock,
+    records: Vec<DeckRecord<C>>,
+}
+
+/// The serialized fields of a ```DeckHistory```
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct SerializedHistory<C: CardType> {
+    records: Vec<DeckRecord<C>>,
+}
+
+#[cfg(feature = "serde")]
+impl<C: CardType> From<SerializedHistory<C>> for DeckHistory<C> {
+    fn from(history: SerializedHistory<C>) -> Self {
+        let next = history.records.last().map(|r| r.sequence + 1).unwrap_or(0);
+        DeckHistory {
+            clock: HistoryClock(Arc::new(AtomicU64::new(next))),
+            records: history.records,
+        }
+    }
+}
+
+impl<C: CardType> DeckHistory<C> {
+    /// Create an empty history numbering its records from the given clock
+    pub fn new(clock: HistoryClock) -> Self {
+        DeckHistory {
+            clock,
+            records: Vec::new(),
+        }
+    }
+
+    /// Append an event to the history
+    pub fn record(&mut self, event: DeckEvent<C>) {
+        let sequence = self.clock.tick();
+        self.records.push(DeckRecord {
+            sequence,
+            event,
+        });
+    }
+
+    /// Get all of the records, oldest first
+    pub fn records(&self) -> &[DeckRecord<C>] {
+        &self.records
+    }
+
+    /// Get the records that involve a specific card, oldest first
+    pub fn records_for<'a>(&'a self, card: &'a C) -> impl Iterator<Item = &'a DeckRecord<C>> {
+        self.records.iter().filter(move |r| r.involves(card))
+    }
+
+    /// Get the records with a sequence number greater than or equal to ```sequence```
+    pub fn since(&self, sequence: u64) -> impl Iterator<Item = &DeckRecord<C>> {
+        self.records.iter().filter(move |r| r.sequence >= sequence)
+    }
+
+    /// Remove all records, returning them
+    pub fn take(&mut self) -> Vec<DeckRecord<C>> {
+        std::mem::take(&mut self.records)
+    }
+
+    /// Get the number of records
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns true if nothing has been recorded
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DeckEvent, DeckHistory, HistoryClock};
+    use crate::cards::Card;
+
+    #[test]
+    fn shared_clock() {
+        let clock = HistoryClock::new();
+        let mut a = DeckHistory::<Card>::new(clock.clone());
+        let mut b = DeckHistory::<Card>::new(clock);
+
+        a.record(DeckEvent::Cut { count: 1 });
+        b.record(DeckEvent::Add { card: Card::new(), position: 0 });
+        a.record(DeckEvent::Draw { card: Card::new(), position: 0 });
+
+        let sequences: Vec<u64> = a.records().iter().map(|r| r.sequence).collect();
+        assert_eq!(sequences, vec![0, 2]);
+        assert_eq!(b.records()[0].sequence, 1);
+        assert_eq!(a.records_for(&Card::new()).count(), 1);
+        assert_eq!(a.since(1).count(), 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialized_clock() {
+        let mut history = DeckHistory::<Card>::from(super::SerializedHistory { records: Vec::new() });
+        history.record(DeckEvent::Cut { count: 1 });
+        assert_eq!(history.records()[0].sequence, 0);
+
+        let records = vec![super::DeckRecord { sequence: 7, event: DeckEvent::Cut { count: 1 } }];
+        let mut history = DeckHistory::<Card>::from(super::SerializedHistory { records });
+        history.record(DeckEvent::Cut { count: 2 });
+        assert_eq!(history.records()[1].sequence, 8);
+    }
+}
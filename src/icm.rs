@@ -0,0 +1,313 @@
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+/// The largest number of players the exact calculation can be set to handle
+const MAX_EXACT_LIMIT: usize = 20;
+
+/// Describes why an ICM calculation could not be run
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum IcmError {
+    /// There are no stacks
+    NoPlayers,
+    /// None of the stacks hold any chips
+    NoChips,
+    /// There is no player with the index
+    PlayerOutOfRange(usize),
+    /// A player was matched up against themselves
+    SamePlayer(usize),
+}
+
+impl std::fmt::Display for IcmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IcmError::NoPlayers => write!(f, "there are no stacks"),
+            IcmError::NoChips => write!(f, "none of the stacks hold any chips"),
+            IcmError::PlayerOutOfRange(player) => write!(f, "there is no player {}", player),
+            IcmError::SamePlayer(player) => write!(f, "player {} cannot play against themselves", player),
+        }
+    }
+}
+
+impl std::error::Error for IcmError {}
+
+/// How the chances of each finishing order are derived from the stacks
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IcmModel {
+    /// The Independent Chip Model (the Malmuth-Harville model): a player finishes first with the probability of their
+    /// share of the chips, and the rest of the places are filled the same way among the players left
+    Harville,
+    /// The Malmuth-Weitzman model: the next player to bust is picked with a probability inversely proportional to
+    /// their stack, filling the places from the last one up
+    MalmuthWeitzman,
+}
+
+/// The result of an ICM calculation, listing the players in the order their stacks were given
+#[derive(Debug, Clone, PartialEq)]
+pub struct IcmResult {
+    equities: Vec<f64>,
+    places: Vec<Vec<f64>>,
+    exact: bool,
+}
+
+impl IcmResult {
+    /// Get the prize equity of each player
+    pub fn equities(&self) -> &[f64] {
+        &self.equities
+    }
+
+    /// Get the prize equity of the player. Panics if there is no player with the index
+    pub fn equity(&self, player: usize) -> f64 {
+        self.equities[player]
+    }
+
+    /// Get the probability of the player finishing in each place, first place first. Panics if there is no player with
+    /// the index
+    pub fn places(&self, player: usize) -> &[f64] {
+        &self.places[player]
+    }
+
+    /// Returns true if every finishing order was accounted for, or false if the finishing orders were sampled
+    pub fn is_exact(&self) -> bool {
+        self.exact
+    }
+}
+
+/// Converts tournament stacks into prize equity, given the prizes by finishing place (first place first).
+///
+/// With at most ```max_exact()``` players holding chips, the probability of every set of top (or, for
+/// Malmuth-Weitzman, bottom) finishers is worked out exactly, which takes time and memory in proportion to 2^n.
+/// Otherwise ```trials()``` finishing orders are sampled at random, reproducibly from the seed. Players without chips
+/// share the last places equally.
+#[derive(Debug, Clone)]
+pub struct IcmCalculator {
+    model: IcmModel,
+    max_exact: usize,
+    trials: usize,
+    seed: u64,
+}
+
+impl Default for IcmCalculator {
+    fn default() -> Self {
+        IcmCalculator {
+            model: IcmModel::Harville,
+            max_exact: 12,
+            trials: 100_000,
+            seed: 0,
+        }
+    }
+}
+
+impl IcmCalculator {
+    /// Create a Harville calculator, which is exact for up to 12 players and samples 100,000 finishing orders otherwise
+    pub fn new() -> Self {
+        IcmCalculator::default()
+    }
+
+    /// Set the model
+    pub fn model(mut self, model: IcmModel) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// Set the largest number of players to calculate exactly, up to 20
+    pub fn max_exact(mut self, max_exact: usize) -> Self {
+        self.max_exact = max_exact.min(MAX_EXACT_LIMIT);
+        self
+    }
+
+    /// Set the number of finishing orders to sample when there are too many players to calculate exactly
+    pub fn trials(mut self, trials: usize) -> Self {
+        self.trials = trials.max(1);
+        self
+    }
+
+    /// Set the seed used to sample finishing orders
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Calculate the prize equity of each stack. Prizes past the number of players are ignored, and places past the
+    /// prizes win nothing
+    pub fn calculate(&self, stacks: &[u64], prizes: &[u64]) -> Result<IcmResult, IcmError> {
+        if stacks.is_empty() {
+            return Err(IcmError::NoPlayers);
+        }
+        let alive: Vec<usize> = (0..stacks.len()).filter(|p| stacks[*p] > 0).collect();
+        if alive.is_empty() {
+            return Err(IcmError::NoChips);
+        }
+        let weights: Vec<f64> = alive
+            .iter()
+            .map(|p| match self.model {
+                IcmModel::Harville => stacks[*p] as f64,
+                IcmModel::MalmuthWeitzman => 1.0 / stacks[*p] as f64,
+            })
+            .collect();
+        let exact = alive.len() <= self.max_exact;
+        // the probability of each live player finishing in each place, counting from the first place picked
+        let picked = if exact { exact_places(&weights) } else { self.sample_places(&weights) };
+
+        let n = stacks.len();
+        let mut places = vec![vec![0.0; n]; n];
+        for (i, p) in alive.iter().enumerate() {
+            for (k, probability) in picked[i].iter().enumerate() {
+                let place = match self.model {
+                    IcmModel::Harville => k,
+                    IcmModel::MalmuthWeitzman => alive.len() - 1 - k,
+                };
+                places[*p][place] = *probability;
+            }
+        }
+        let busted = n - alive.len();
+        for p in (0..n).filter(|p| stacks[*p] == 0) {
+            for place in places[p][alive.len()..].iter_mut() {
+                *place = 1.0 / busted as f64;
+            }
+        }
+
+        let prize = |place: usize| prizes.get(place).copied().unwrap_or(0) as f64;
+        let equities = places.iter().map(|p| p.iter().enumerate().map(|(place, pr)| pr * prize(place)).sum()).collect();
+        Ok(IcmResult { equities, places, exact })
+    }
+
+    /// Calculate the bubble factor of a player against an opponent: the prize equity the player risks by losing an
+    /// all-in against the opponent, over the equity they gain by winning it. A chip EV decision needs to win this many
+    /// times as often as it loses to break even in prize equity. The player and the opponent must be different players
+    pub fn bubble_factor(&self, stacks: &[u64], prizes: &[u64], player: usize, opponent: usize) -> Result<f64, IcmError> {
+        for p in [player, opponent] {
+            if p >= stacks.len() {
+                return Err(IcmError::PlayerOutOfRange(p));
+            }
+        }
+        if player == opponent {
+            return Err(IcmError::SamePlayer(player));
+        }
+        let before = self.calculate(stacks, prizes)?.equity(player);
+        let at_risk = stacks[player].min(stacks[opponent]);
+        let mut won = stacks.to_vec();
+        won[player] += at_risk;
+        won[opponent] -= at_risk;
+        let mut lost = stacks.to_vec();
+        lost[player] -= at_risk;
+        lost[opponent] += at_risk;
+        let gain = self.calculate(&won, prizes)?.equity(player) - before;
+        let loss = before - self.calculate(&lost, prizes)?.equity(player);
+        Ok(if gain > 0.0 { loss / gain } else { f64::INFINITY })
+    }
+
+    /// Sample the orders in which the players are picked. Picking by weight one at a time is the same as ordering the
+    /// players by an exponential random variable divided by their weight (the Plackett-Luce model)
+    fn sample_places(&self, weights: &[f64]) -> Vec<Vec<f64>> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut counts = vec![vec![0u64; weights.len()]; weights.len()];
+        let mut keys: Vec<(f64, usize)> = vec![(0.0, 0); weights.len()];
+        for _ in 0..self.trials {
+            for (i, w) in weights.iter().enumerate() {
+                let u: f64 = rng.gen();
+                keys[i] = (-(1.0 - u).ln() / w, i);
+            }
+            keys.sort_by(|a, b| a.0.total_cmp(&b.0));
+            for (k, (_, i)) in keys.iter().enumerate() {
+                counts[*i][k] += 1;
+            }
+        }
+        counts.into_iter().map(|c| c.into_iter().map(|c| c as f64 / self.trials as f64).collect()).collect()
+    }
+}
+
+/// Work out the probability of each player being picked in each position, picking players one at a time in
+/// proportion to their weights, by going through every set of players that can be picked first
+fn exact_places(weights: &[f64]) -> Vec<Vec<f64>> {
+    let n = weights.len();
+    let mut places = vec![vec![0.0; n]; n];
+    let mut sets = vec![0.0; 1 << n];
+    sets[0] = 1.0;
+    let total: f64 = weights.iter().sum();
+    for set in 0..(1usize << n) - 1 {
+        let probability = sets[set];
+        if probability == 0.0 {
+            continue;
+        }
+        let left = total - (0..n).filter(|i| set & (1 << i) != 0).map(|i| weights[i]).sum::<f64>();
+        let position = set.count_ones() as usize;
+        for i in (0..n).filter(|i| set & (1 << i) == 0) {
+            let p = probability * weights[i] / left;
+            places[i][position] += p;
+            sets[set | (1 << i)] += p;
+        }
+    }
+    places
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IcmCalculator, IcmError, IcmModel};
+
+    fn close(a: f64, b: f64, tolerance: f64) -> bool {
+        (a - b).abs() < tolerance
+    }
+
+    #[test]
+    fn harville() {
+        let result = IcmCalculator::new().calculate(&[3, 1], &[1, 0]).unwrap();
+        assert!(result.is_exact());
+        assert!(close(result.equity(0), 0.75, 1e-12) && close(result.equity(1), 0.25, 1e-12));
+
+        // 50/30/20 stacks for a 70/30 payout: 1st 50% * 70, 2nd (.3 * 50/70 + .2 * 50/80) * 30
+        let result = IcmCalculator::new().calculate(&[50, 30, 20], &[70, 30]).unwrap();
+        assert!(close(result.equity(0), 35.0 + (0.3 * 50.0 / 70.0 + 0.2 * 50.0 / 80.0) * 30.0, 1e-9));
+        assert!(close(result.equities().iter().sum(), 100.0, 1e-9));
+        for player in 0..3 {
+            assert!(close(result.places(player).iter().sum(), 1.0, 1e-12));
+        }
+        assert!(close(result.places(2)[0], 0.2, 1e-12));
+
+        // equal stacks share equally, and players without chips share the last places
+        let result = IcmCalculator::new().calculate(&[100, 0, 100, 0], &[50, 30, 20, 10]).unwrap();
+        assert_eq!(result.equities(), &[40.0, 15.0, 40.0, 15.0]);
+        assert_eq!(IcmCalculator::new().calculate(&[], &[1]), Err(IcmError::NoPlayers));
+        assert_eq!(IcmCalculator::new().calculate(&[0, 0], &[1]), Err(IcmError::NoChips));
+    }
+
+    #[test]
+    fn malmuth_weitzman() {
+        let calculator = IcmCalculator::new().model(IcmModel::MalmuthWeitzman);
+        // heads-up, busting is inversely proportional to the stack: 3:1 chips bust 1:3
+        let result = calculator.calculate(&[3, 1], &[1, 0]).unwrap();
+        assert!(close(result.equity(0), 0.75, 1e-12));
+
+        // the shortest stack is the most likely to bust first
+        let result = calculator.calculate(&[50, 30, 20], &[50, 30, 20]).unwrap();
+        assert!(close(result.places(2)[2], (1.0 / 20.0) / (1.0 / 50.0 + 1.0 / 30.0 + 1.0 / 20.0), 1e-12));
+        assert!(close(result.equities().iter().sum(), 100.0, 1e-9));
+        assert!(result.equity(0) > result.equity(1) && result.equity(1) > result.equity(2));
+    }
+
+    #[test]
+    fn sampling() {
+        let stacks = [4000, 3500, 3000, 2500, 2000, 1500, 1200, 1000, 800, 500];
+        let prizes = [500, 300, 200];
+        let exact = IcmCalculator::new().calculate(&stacks, &prizes).unwrap();
+        let sampled = IcmCalculator::new().max_exact(5).trials(200_000).seed(7).calculate(&stacks, &prizes).unwrap();
+        assert!(exact.is_exact() && !sampled.is_exact());
+        for player in 0..stacks.len() {
+            assert!(close(exact.equity(player), sampled.equity(player), 2.0));
+        }
+        assert!(close(sampled.equities().iter().sum(), 1000.0, 1e-6));
+        assert_eq!(sampled, IcmCalculator::new().max_exact(5).trials(200_000).seed(7).calculate(&stacks, &prizes).unwrap());
+    }
+
+    #[test]
+    fn bubble_factor() {
+        let calculator = IcmCalculator::new();
+        // winner takes all: prize equity is proportional to chips
+        assert!(close(calculator.bubble_factor(&[100, 100, 100], &[1], 0, 1).unwrap(), 1.0, 1e-12));
+        // on the bubble of a 3-handed, 2-paid game, the covered medium stack risks more than it stands to win
+        let factor = calculator.bubble_factor(&[5000, 3000, 2000], &[60, 40], 1, 0).unwrap();
+        assert!(factor > 1.5);
+        assert_eq!(calculator.bubble_factor(&[1, 1], &[1], 0, 2), Err(IcmError::PlayerOutOfRange(2)));
+        assert_eq!(calculator.bubble_factor(&[1, 1], &[1], 1, 1), Err(IcmError::SamePlayer(1)));
+    }
+}
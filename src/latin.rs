@@ -0,0 +1,184 @@
+use crate::card_type::CardType;
+
+/// Represents the suit of a Latin-suited (Italian or Spanish) card: Coins, Cups, Swords or Batons
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LatinSuit {
+    Coins,
+    Cups,
+    Swords,
+    Batons,
+}
+
+impl std::fmt::Display for LatinSuit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            LatinSuit::Coins => "o",
+            LatinSuit::Cups => "c",
+            LatinSuit::Swords => "e",
+            LatinSuit::Batons => "b",
+        })
+    }
+}
+
+impl LatinSuit {
+    /// Convert a LatinSuit variant to its ordinal value
+    pub fn to_ordinal(&self) -> u8 {
+        match self {
+            LatinSuit::Coins => 0,
+            LatinSuit::Cups => 1,
+            LatinSuit::Swords => 2,
+            LatinSuit::Batons => 3,
+        }
+    }
+
+    /// Convert an ordinal value to a ```LatinSuit``` variant. Returns ```None``` for values outside of 0..3
+    pub fn from_ordinal(suit: u8) -> Option<Self> {
+        match suit {
+            0 => Some(LatinSuit::Coins),
+            1 => Some(LatinSuit::Cups),
+            2 => Some(LatinSuit::Swords),
+            3 => Some(LatinSuit::Batons),
+            _ => None,
+        }
+    }
+}
+
+/// Represents the rank of a card in a 40-card Latin deck: Ace to Seven followed by the three court cards.
+/// The court cards are known as Fante/Sota (Jack), Cavallo/Caballo (Knight) and Re/Rey (King).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LatinRank {
+    Ace,
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Jack,
+    Knight,
+    King,
+}
+
+impl std::fmt::Display for LatinRank {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LatinRank::Ace => write!(f, "A"),
+            LatinRank::Jack => write!(f, "J"),
+            LatinRank::Knight => write!(f, "C"),
+            LatinRank::King => write!(f, "K"),
+            _ => write!(f, "{}", self.to_ordinal() + 1),
+        }
+    }
+}
+
+impl LatinRank {
+    /// Convert a LatinRank variant to its ordinal value
+    pub fn to_ordinal(&self) -> u8 {
+        match self {
+            LatinRank::Ace => 0,
+            LatinRank::Two => 1,
+            LatinRank::Three => 2,
+            LatinRank::Four => 3,
+            LatinRank::Five => 4,
+            LatinRank::Six => 5,
+            LatinRank::Seven => 6,
+            LatinRank::Jack => 7,
+            LatinRank::Knight => 8,
+            LatinRank::King => 9,
+        }
+    }
+
+    /// Convert an ordinal value to a ```LatinRank``` variant. Returns ```None``` for values outside of 0..9
+    pub fn from_ordinal(rank: u8) -> Option<Self> {
+        match rank {
+            0 => Some(LatinRank::Ace),
+            1 => Some(LatinRank::Two),
+            2 => Some(LatinRank::Three),
+            3 => Some(LatinRank::Four),
+            4 => Some(LatinRank::Five),
+            5 => Some(LatinRank::Six),
+            6 => Some(LatinRank::Seven),
+            7 => Some(LatinRank::Jack),
+            8 => Some(LatinRank::Knight),
+            9 => Some(LatinRank::King),
+            _ => None,
+        }
+    }
+}
+
+/// Represents a card from a 40-card Italian or Spanish deck
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LatinCard {
+    suit: LatinSuit,
+    rank: LatinRank,
+}
+
+impl std::fmt::Display for LatinCard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.rank, self.suit)
+    }
+}
+
+impl LatinCard {
+    /// Construct a card using suit and rank values
+    pub fn new(suit: LatinSuit, rank: LatinRank) -> Self {
+        LatinCard {
+            suit,
+            rank,
+        }
+    }
+
+    /// Get the rank of a card
+    pub fn rank(&self) -> &LatinRank {
+        &self.rank
+    }
+
+    /// Get the suit of a card
+    pub fn suit(&self) -> &LatinSuit {
+        &self.suit
+    }
+}
+
+impl CardType for LatinCard {
+    const NUM_FACES: usize = 40;
+
+    fn to_index(&self) -> usize {
+        self.suit.to_ordinal() as usize * 10 + self.rank.to_ordinal() as usize
+    }
+
+    fn from_index(index: usize) -> Option<Self> {
+        if index >= Self::NUM_FACES {
+            return None;
+        }
+        Some(LatinCard {
+            suit: LatinSuit::from_ordinal((index / 10) as u8)?,
+            rank: LatinRank::from_ordinal((index % 10) as u8)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LatinCard, LatinRank, LatinSuit};
+    use crate::card_type::CardType;
+
+    #[test]
+    fn latin_standard_deck() {
+        let deck = LatinCard::standard_deck();
+
+        assert_eq!(deck.len(), 40);
+        assert_eq!(deck[0], LatinCard::new(LatinSuit::Coins, LatinRank::Ace));
+        assert_eq!(deck[39], LatinCard::new(LatinSuit::Batons, LatinRank::King));
+    }
+
+    #[test]
+    fn latin_index_round_trip() {
+        for i in 0..LatinCard::NUM_FACES {
+            assert_eq!(LatinCard::from_index(i).unwrap().to_index(), i);
+        }
+        assert_eq!(LatinCard::from_index(40), None);
+    }
+}
@@ -1,5 +1,58 @@
+mod card_set;
+mod card_type;
 mod cards;
+mod combinations;
 mod deck;
+mod deck_builder;
+mod deck_spec;
+mod equity;
+mod fair;
+mod german;
+mod hand_history;
+mod hanafuda;
+mod history;
+mod icm;
+mod latin;
+#[cfg(feature = "ohh")]
+mod ohh;
+mod poker;
+mod poker_lookup;
+mod poker_variants;
+mod pots;
+mod probability;
+mod range;
+mod sha256;
+mod simulation;
+mod table;
+mod tarot;
+mod tournament;
+mod video_poker;
+mod zones;
 
-pub use crate::cards::{Card, Rank, Suit};
-pub use crate::deck::Deck;
\ No newline at end of file
+pub use crate::card_set::{CardSet, CardSetIter};
+pub use crate::card_type::CardType;
+pub use crate::cards::{Card, ParseCardError, Rank, Suit};
+pub use crate::combinations::{binomial, rank_combination, remaining_boards, unrank_combination, CardSetCombinations, Combinations};
+pub use crate::deck::{Deck, DeckOrientation, DeckSnapshot};
+pub use crate::deck_builder::{DeckBuilder, DeckBuilderError};
+pub use crate::deck_spec::{DeckSpec, ValidationReport};
+pub use crate::equity::{EquityCalculator, EquityError, EquityResult, PlayerEquity};
+pub use crate::fair::{Commitment, FairRng, FairShuffle, FairnessError, ServerSeed};
+pub use crate::german::{GermanCard, GermanRank, GermanSuit};
+pub use crate::hand_history::{HandHistory, HistoryAction, HistoryActionKind, HistoryPlayer, ParseHistoryError};
+pub use crate::hanafuda::{HanafudaCard, HanafudaKind, HanafudaMonth};
+pub use crate::history::{DeckEvent, DeckHistory, DeckRecord, HistoryClock};
+pub use crate::icm::{IcmCalculator, IcmError, IcmModel, IcmResult};
+pub use crate::latin::{LatinCard, LatinRank, LatinSuit};
+pub use crate::poker::{HandCategory, HandRank, PokerEvaluator};
+pub use crate::poker_lookup::LookupEvaluator;
+pub use crate::poker_variants::{AceToFiveEvaluator, BadugiEvaluator, DeuceToSevenEvaluator, HandEvaluator, OmahaEvaluator, OmahaHiLoEvaluator, ShortDeckEvaluator};
+pub use crate::pots::{Award, Pot, PotManager, Settlement, Share};
+pub use crate::probability::DrawOdds;
+pub use crate::range::{ParseRangeError, Range};
+pub use crate::simulation::{derive_seed, Estimate, MonteCarlo, Shuffle};
+pub use crate::table::{Action, BettingStructure, LegalActions, Street, Table, TableConfig, TableError, TableEvent, MAX_SEATS};
+pub use crate::tarot::{TarotCard, TarotEvaluator, TarotRank};
+pub use crate::tournament::{BlindLevel, BlindSchedule, Clock, LevelLength, ManualClock, PayoutStructure, Standing, SystemClock, Tournament, TournamentError};
+pub use crate::video_poker::{DrawOutcome, HoldValue, Paytable, VideoPoker, VideoPokerError, VideoPokerGame, VideoPokerHand};
+pub use crate::zones::{Snapshot, ZoneId, Zones};
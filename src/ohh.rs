@@ -0,0 +1,545 @@
+use serde::{Deserialize, Serialize};
+use crate::cards::Card;
+use crate::hand_history::{board_len, HandHistory, HistoryAction, HistoryActionKind, HistoryPlayer, ParseHistoryError};
+use crate::pots::PotManager;
+use crate::table::{BettingStructure, Street};
+
+/// The version of the Open Hand History specification that is written
+const SPEC_VERSION: &str = "1.4.6";
+
+/// The currency symbols of hand histories and the ISO codes used for them in Open Hand History
+const CURRENCIES: [(&str, &str); 3] = [("$", "USD"), ("€", "EUR"), ("£", "GBP")];
+
+/// The names of the betting rounds, in order
+const STREETS: [(Street, &str); 4] = [(Street::Preflop, "Preflop"), (Street::Flop, "Flop"), (Street::Turn, "Turn"), (Street::River, "River")];
+
+const SHOWDOWN: &str = "Showdown";
+
+#[derive(Serialize, Deserialize)]
+struct Document {
+    ohh: Ohh,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Ohh {
+    spec_version: String,
+    #[serde(default)]
+    site_name: String,
+    #[serde(default)]
+    internal_version: String,
+    #[serde(default)]
+    tournament: bool,
+    game_number: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    start_date_utc: Option<String>,
+    #[serde(default)]
+    table_name: String,
+    game_type: String,
+    bet_limit: BetLimit,
+    table_size: usize,
+    #[serde(default)]
+    currency: String,
+    dealer_seat: usize,
+    small_blind_amount: f64,
+    big_blind_amount: f64,
+    #[serde(default)]
+    ante_amount: f64,
+    players: Vec<Player>,
+    rounds: Vec<Round>,
+    #[serde(default)]
+    pots: Vec<Pot>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BetLimit {
+    bet_type: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Player {
+    id: usize,
+    seat: usize,
+    name: String,
+    starting_stack: f64,
+    #[serde(default)]
+    is_sitting_out: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Round {
+    id: usize,
+    street: String,
+    #[serde(default, with = "card_strings")]
+    cards: Vec<Card>,
+    actions: Vec<RoundAction>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RoundAction {
+    action_number: usize,
+    player_id: usize,
+    action: String,
+    #[serde(default)]
+    amount: f64,
+    #[serde(default)]
+    is_allin: bool,
+    #[serde(default, with = "card_strings", skip_serializing_if = "Vec::is_empty")]
+    cards: Vec<Card>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Pot {
+    number: usize,
+    amount: f64,
+    #[serde(default)]
+    rake: f64,
+    player_wins: Vec<PlayerWin>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PlayerWin {
+    player_id: usize,
+    win_amount: f64,
+}
+
+/// Cards are written as in text hand histories ("Ah", "Td"), using their ```Display``` and ```FromStr``` impls
+mod card_strings {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use crate::cards::Card;
+
+    pub fn serialize<S: Serializer>(cards: &[Card], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(cards.iter().map(|c| c.to_string()))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Card>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?.iter().map(|c| c.parse().map_err(D::Error::custom)).collect()
+    }
+}
+
+impl HandHistory {
+    /// Write the hand as an Open Hand History JSON document, on a single line so that a file can hold a hand per line.
+    /// The amounts of actions are the chips they add (so a raise is written as the chips added to the player's bet),
+    /// and uncalled bets are left out of the pots. The date is only written if it is in UTC (see ```utc_date()```)
+    pub fn to_ohh(&self) -> String {
+        let id = |name: &str| self.players.iter().position(|p| p.name == name).unwrap_or(self.players.len());
+        let action = |player_id: usize, name: &str, amount: u64, all_in: bool, cards: Vec<Card>| RoundAction {
+            action_number: 0,
+            player_id,
+            action: name.to_string(),
+            amount: self.decimal(amount),
+            is_allin: all_in,
+            cards,
+        };
+
+        // a round for each street that was dealt or played, and for the showdown if there was one
+        let played = self.actions.iter().map(|a| a.street).max().unwrap_or(Street::Preflop);
+        let dealt = STREETS.iter().filter(|(street, _)| board_len(*street) <= self.board.len()).count();
+        let mut rounds: Vec<Round> = STREETS.iter().take(dealt.max(played as usize + 1)).map(|(street, name)| Round {
+            id: *street as usize,
+            street: name.to_string(),
+            cards: self.board.iter().take(board_len(*street)).skip(board_len(previous(*street))).cloned().collect(),
+            actions: Vec::new(),
+        }).collect();
+        if self.actions.iter().any(|a| matches!(a.kind, HistoryActionKind::Show(_) | HistoryActionKind::Muck)) {
+            rounds.push(Round { id: rounds.len(), street: SHOWDOWN.to_string(), cards: Vec::new(), actions: Vec::new() });
+        }
+        let showdown = rounds.len() - 1;
+
+        let mut pots: Vec<Pot> = Vec::new();
+        let mut bets = vec![0; self.players.len() + 1];
+        let mut street = Street::Preflop;
+        for a in self.actions.iter() {
+            let player_id = id(&a.player);
+            if a.street != street {
+                street = a.street;
+                bets.iter_mut().for_each(|b| *b = 0);
+            }
+            let (name, added) = match a.kind {
+                HistoryActionKind::Ante(amount) => ("Post Ante", amount),
+                HistoryActionKind::SmallBlind(amount) => ("Post SB", amount),
+                HistoryActionKind::BigBlind(amount) => ("Post BB", amount),
+                HistoryActionKind::Fold => ("Fold", 0),
+                HistoryActionKind::Check => ("Check", 0),
+                HistoryActionKind::Call(amount) => ("Call", amount),
+                HistoryActionKind::Bet(amount) => ("Bet", amount),
+                HistoryActionKind::Raise { to, .. } => ("Raise", to.saturating_sub(bets[player_id])),
+                HistoryActionKind::Show(cards) => {
+                    rounds[showdown].actions.push(action(player_id, "Shows Cards", 0, false, cards.to_vec()));
+                    continue;
+                }
+                HistoryActionKind::Muck => {
+                    rounds[showdown].actions.push(action(player_id, "Mucks Cards", 0, false, Vec::new()));
+                    continue;
+                }
+                HistoryActionKind::Uncalled(_) => continue,
+                HistoryActionKind::Collect { amount, pot } => {
+                    while pots.len() <= pot {
+                        pots.push(Pot { number: pots.len(), amount: 0.0, rake: 0.0, player_wins: Vec::new() });
+                    }
+                    pots[pot].amount += self.decimal(amount);
+                    pots[pot].player_wins.push(PlayerWin { player_id, win_amount: self.decimal(amount) });
+                    continue;
+                }
+            };
+            if !matches!(a.kind, HistoryActionKind::Ante(_)) {
+                bets[player_id] += added;
+            }
+            rounds[street as usize].actions.push(action(player_id, name, added, a.all_in, Vec::new()));
+        }
+
+        // the hole cards are dealt once the blinds are posted, and cards that are shown later are not dealt face up
+        let posts = rounds[0].actions.iter().take_while(|a| a.action.starts_with("Post ")).count();
+        let shown: Vec<&str> = self.actions.iter().filter(|a| matches!(a.kind, HistoryActionKind::Show(_))).map(|a| a.player.as_str()).collect();
+        let dealt: Vec<RoundAction> = self.players.iter().enumerate()
+            .filter(|(_, p)| !shown.contains(&p.name.as_str()))
+            .filter_map(|(i, p)| Some(action(i, "Dealt Cards", 0, false, p.hole_cards?.to_vec())))
+            .collect();
+        rounds[0].actions.splice(posts..posts, dealt);
+        for (number, a) in rounds.iter_mut().flat_map(|r| r.actions.iter_mut()).enumerate() {
+            a.action_number = number + 1;
+        }
+
+        if self.rake > 0 {
+            if pots.is_empty() {
+                pots.push(Pot { number: 0, amount: 0.0, rake: 0.0, player_wins: Vec::new() });
+            }
+            pots[0].rake = self.decimal(self.rake);
+            pots[0].amount += self.decimal(self.rake);
+        }
+
+        let currency = self.currency.as_deref().map_or("", |symbol| {
+            CURRENCIES.iter().find(|(s, _)| *s == symbol).map_or(symbol, |(_, code)| code)
+        });
+        let document = Document {
+            ohh: Ohh {
+                spec_version: SPEC_VERSION.to_string(),
+                site_name: String::new(),
+                internal_version: env!("CARGO_PKG_VERSION").to_string(),
+                tournament: false,
+                game_number: self.id.clone(),
+                start_date_utc: self.date.as_deref().and_then(utc_date),
+                table_name: self.table_name.clone(),
+                game_type: "Holdem".to_string(),
+                bet_limit: BetLimit {
+                    bet_type: match self.structure {
+                        BettingStructure::NoLimit => "NL",
+                        BettingStructure::PotLimit => "PL",
+                        BettingStructure::FixedLimit => "FL",
+                    }.to_string(),
+                },
+                table_size: self.max_seats,
+                currency: currency.to_string(),
+                dealer_seat: self.button,
+                small_blind_amount: self.decimal(self.small_blind),
+                big_blind_amount: self.decimal(self.big_blind),
+                ante_amount: self.decimal(self.ante),
+                players: self.players.iter().enumerate().map(|(id, p)| Player {
+                    id,
+                    seat: p.seat,
+                    name: p.name.clone(),
+                    starting_stack: self.decimal(p.stack),
+                    is_sitting_out: false,
+                }).collect(),
+                rounds,
+                pots,
+            },
+        };
+        serde_json::to_string(&document).unwrap_or_default()
+    }
+
+    /// Parse a hand from an Open Hand History JSON document. Only Hold'em hands can be read. Actions that do not move
+    /// chips from the players to the pot the usual way (e.g. straddles and dead blinds) are skipped, and players who sit
+    /// out are left out
+    pub fn parse_ohh(json: &str) -> Result<HandHistory, ParseHistoryError> {
+        let document: Document = serde_json::from_str(json).map_err(|e| ParseHistoryError::new(e.line(), &e.to_string()))?;
+        document.ohh.into_history(1)
+    }
+
+    /// Parse every hand of a file of Open Hand History JSON documents, separated by whitespace (e.g. one per line)
+    pub fn parse_ohh_all(text: &str) -> Result<Vec<HandHistory>, ParseHistoryError> {
+        let mut documents = serde_json::Deserializer::from_str(text).into_iter::<Document>();
+        let mut hands = Vec::new();
+        loop {
+            let start = documents.byte_offset();
+            match documents.next() {
+                None => return Ok(hands),
+                Some(Err(e)) => return Err(ParseHistoryError::new(e.line(), &e.to_string())),
+                Some(Ok(document)) => {
+                    let skipped = text[start..].len() - text[start..].trim_start().len();
+                    let line = text[..start + skipped].matches('\n').count() + 1;
+                    hands.push(document.ohh.into_history(line)?);
+                }
+            }
+        }
+    }
+
+    /// Convert an amount of chips, or of cents if the hand has a currency, to the decimal amounts of the format
+    fn decimal(&self, amount: u64) -> f64 {
+        match self.currency {
+            Some(_) => amount as f64 / 100.0,
+            None => amount as f64,
+        }
+    }
+}
+
+impl Ohh {
+    fn into_history(self, line: usize) -> Result<HandHistory, ParseHistoryError> {
+        let error = |text: &str| ParseHistoryError::new(line, text);
+        if self.game_type != "Holdem" {
+            return Err(error(&self.game_type));
+        }
+        let structure = match self.bet_limit.bet_type.as_str() {
+            "NL" => BettingStructure::NoLimit,
+            "PL" => BettingStructure::PotLimit,
+            "FL" => BettingStructure::FixedLimit,
+            other => return Err(error(other)),
+        };
+        let currency = match self.currency.as_str() {
+            "" => None,
+            code => Some(CURRENCIES.iter().find(|(_, c)| *c == code).map_or(code, |(symbol, _)| symbol).to_string()),
+        };
+        let scale = if currency.is_some() { 100.0 } else { 1.0 };
+        let amount = |value: f64| {
+            Some((value * scale).round()).filter(|v| v.is_finite() && *v >= 0.0).map(|v| v as u64).ok_or_else(|| error(&value.to_string()))
+        };
+
+        let mut players = Vec::new();
+        for p in self.players.iter().filter(|p| !p.is_sitting_out) {
+            players.push(HistoryPlayer { seat: p.seat, name: p.name.clone(), stack: amount(p.starting_stack)?, hole_cards: None });
+        }
+        let player = |id: usize| {
+            self.players.iter().find(|p| p.id == id && !p.is_sitting_out).map(|p| (p.seat, p.name.clone())).ok_or_else(|| error(&id.to_string()))
+        };
+
+        let mut board = Vec::new();
+        let mut actions: Vec<HistoryAction> = Vec::new();
+        let mut pots = PotManager::new(self.table_size.max(1), self.dealer_seat);
+        let mut bets: Vec<(usize, u64)> = Vec::new();
+        let mut street = Street::Preflop;
+        for round in self.rounds.iter() {
+            if round.street != SHOWDOWN {
+                street = STREETS.iter().find(|(_, name)| *name == round.street).map(|(s, _)| *s).ok_or_else(|| error(&round.street))?;
+                board.extend(round.cards.iter());
+                bets.clear();
+            }
+            for a in round.actions.iter() {
+                let (seat, name) = player(a.player_id)?;
+                let added = amount(a.amount)?;
+                let bet = bets.iter().find(|(s, _)| *s == seat).map_or(0, |(_, b)| *b);
+                let hole_cards = || match a.cards.as_slice() {
+                    [first, second] => Ok([*first, *second]),
+                    _ => Err(error(&a.action)),
+                };
+                let kind = match a.action.as_str() {
+                    "Post Ante" => HistoryActionKind::Ante(added),
+                    "Post SB" => HistoryActionKind::SmallBlind(added),
+                    "Post BB" => HistoryActionKind::BigBlind(added),
+                    "Fold" => HistoryActionKind::Fold,
+                    "Check" => HistoryActionKind::Check,
+                    "Call" => HistoryActionKind::Call(added),
+                    "Bet" => HistoryActionKind::Bet(added),
+                    "Raise" => {
+                        let current = bets.iter().map(|(_, b)| *b).max().unwrap_or(0);
+                        let by = (bet + added).checked_sub(current).ok_or_else(|| error(&a.action))?;
+                        HistoryActionKind::Raise { by, to: bet + added }
+                    }
+                    "Shows Cards" => HistoryActionKind::Show(hole_cards()?),
+                    "Mucks Cards" => HistoryActionKind::Muck,
+                    "Dealt Cards" => {
+                        let cards = hole_cards()?;
+                        if let Some(p) = players.iter_mut().find(|p| p.seat == seat) {
+                            p.hole_cards = Some(cards);
+                        }
+                        continue;
+                    }
+                    _ => continue,
+                };
+
+                match kind {
+                    HistoryActionKind::Fold => pots.fold(seat),
+                    HistoryActionKind::Show(cards) => {
+                        if let Some(p) = players.iter_mut().find(|p| p.seat == seat) {
+                            p.hole_cards = p.hole_cards.or(Some(cards));
+                        }
+                    }
+                    HistoryActionKind::Ante(_) => pots.contribute(seat, added),
+                    _ if added > 0 => {
+                        pots.contribute(seat, added);
+                        match bets.iter_mut().find(|(s, _)| *s == seat) {
+                            Some((_, b)) => *b += added,
+                            None => bets.push((seat, added)),
+                        }
+                    }
+                    _ => {}
+                }
+                actions.push(HistoryAction { street, player: name, kind, all_in: a.is_allin });
+            }
+        }
+
+        // the uncalled bet goes back at the end of the betting of the street it was made on
+        if let Some((seat, returned)) = pots.uncalled() {
+            let (_, name) = self.players.iter().find(|p| p.seat == seat).map(|p| (p.seat, p.name.clone())).ok_or_else(|| error(&seat.to_string()))?;
+            let betting = |a: &HistoryAction| !matches!(a.kind, HistoryActionKind::Show(_) | HistoryActionKind::Muck);
+            let on = actions.iter().rev().find(|a| a.player == name && betting(a)).map_or(street, |a| a.street);
+            let position = actions.iter().rposition(|a| a.street == on && betting(a)).map_or(actions.len(), |i| i + 1);
+            actions.insert(position, HistoryAction { street: on, player: name, kind: HistoryActionKind::Uncalled(returned), all_in: false });
+        }
+
+        let mut rake = 0;
+        let mut ohh_pots: Vec<&Pot> = self.pots.iter().collect();
+        ohh_pots.sort_by_key(|p| p.number);
+        for pot in ohh_pots {
+            rake += amount(pot.rake)?;
+            for win in pot.player_wins.iter() {
+                let (_, name) = player(win.player_id)?;
+                let kind = HistoryActionKind::Collect { amount: amount(win.win_amount)?, pot: pot.number };
+                actions.push(HistoryAction { street, player: name, kind, all_in: false });
+            }
+        }
+
+        Ok(HandHistory {
+            id: self.game_number.clone(),
+            table_name: self.table_name.clone(),
+            structure,
+            small_blind: amount(self.small_blind_amount)?,
+            big_blind: amount(self.big_blind_amount)?,
+            ante: amount(self.ante_amount)?,
+            currency,
+            date: self.start_date_utc.clone(),
+            max_seats: self.table_size,
+            button: self.dealer_seat,
+            players,
+            actions,
+            board,
+            rake,
+        })
+    }
+}
+
+/// Convert a date to the ISO-8601 UTC format of the specification ("2020-01-19T22:12:38Z"). Dates already in the
+/// format are kept, and PokerStars dates are converted if they are in UTC or GMT ("2020/01/19 22:12:38 UTC"). Dates
+/// in other time zones give None
+fn utc_date(date: &str) -> Option<String> {
+    let shape: String = date.chars().map(|c| if c.is_ascii_digit() { 'd' } else { c }).collect();
+    if shape.starts_with("dddd-dd-ddTdd:dd:dd") && shape.ends_with('Z') {
+        return Some(date.to_string());
+    }
+
+    let mut parts = date.split_whitespace();
+    let (day, time, zone) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() || (zone != "UTC" && zone != "GMT") {
+        return None;
+    }
+    let day: Vec<&str> = day.split('/').collect();
+    let time: Vec<&str> = time.split(':').collect();
+    let number = |p: &&str, len: usize| (1..=len).contains(&p.len()) && p.bytes().all(|b| b.is_ascii_digit());
+    match (day.as_slice(), time.as_slice()) {
+        ([y, m, d], [h, min, sec]) if y.len() == 4 && number(y, 4) && [m, d, h, min, sec].iter().all(|p| number(p, 2)) => {
+            Some(format!("{}-{:0>2}-{:0>2}T{:0>2}:{:0>2}:{:0>2}Z", y, m, d, h, min, sec))
+        }
+        _ => None,
+    }
+}
+
+fn previous(street: Street) -> Street {
+    match street {
+        Street::Preflop | Street::Flop => Street::Preflop,
+        Street::Turn => Street::Flop,
+        Street::River => Street::Turn,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cards::cards;
+    use crate::deck::Deck;
+    use crate::hand_history::{HandHistory, HistoryActionKind};
+    use crate::table::{Action, Table, TableConfig};
+
+    #[test]
+    fn table_round_trip() {
+        let top = cards("Ah Kh 2c As Kd 7d 3s Qc Jh 2d 4d 5s 9c 8h");
+        let mut table = Table::new(TableConfig::no_limit(5, 10).ante(1), 6);
+        table.sit(0, "Alice", 1000).unwrap();
+        table.sit(2, "Bob", 100).unwrap();
+        table.sit(4, "Carol", 300).unwrap();
+        table.start_hand(Deck::stacked(&top, Deck::new()).unwrap()).unwrap();
+        table.act(Action::Raise(30)).unwrap();
+        table.act(Action::Raise(99)).unwrap();
+        table.act(Action::Call).unwrap();
+        table.act(Action::Raise(400)).unwrap();
+        table.act(Action::Call).unwrap();
+
+        let history = HandHistory::from_table(&table, "1", "Test");
+        let json = history.to_ohh();
+        assert!(json.starts_with("{\"ohh\":{\"spec_version\":\"1.4.6\""));
+        assert!(json.contains("\"cards\":[\"Qc\",\"Jh\",\"2d\"]"));
+        assert!(json.contains("\"action\":\"Raise\",\"amount\":370.0"));
+        assert!(!json.contains('\n'));
+
+        assert_eq!(HandHistory::parse_ohh(&json).unwrap(), history);
+    }
+
+    #[test]
+    fn pokerstars_round_trip() {
+        let text = "PokerStars Hand #7:  Hold'em Pot Limit ($0.05/$0.10) - 2020/01/19 17:12:38 ET
+Table 'Alcor' 6-max Seat #1 is the button
+Seat 1: Alice ($10 in chips)
+Seat 2: Bob ($10 in chips)
+Alice: posts small blind $0.05
+Bob: posts big blind $0.10
+*** HOLE CARDS ***
+Dealt to Alice [Ah Kd]
+Alice: raises $0.20 to $0.30
+Bob: calls $0.20
+*** FLOP *** [2c 7d Th]
+Bob: checks
+Alice: bets $0.40
+Bob: folds
+Uncalled bet ($0.40) returned to Alice
+Alice collected $0.57 from pot
+*** SUMMARY ***
+Total pot $0.60 | Rake $0.03
+";
+        let hand = HandHistory::parse_pokerstars(text).unwrap();
+        let json = hand.to_ohh();
+        assert!(json.contains("\"currency\":\"USD\""));
+        assert!(json.contains("\"player_wins\":[{\"player_id\":0,\"win_amount\":0.57}]"));
+
+        // the date is in Eastern Time, which the format cannot hold
+        assert!(!json.contains("start_date_utc"));
+        let parsed = HandHistory::parse_ohh(&json).unwrap();
+        assert_eq!(parsed, HandHistory { date: None, ..hand.clone() });
+
+        let utc = HandHistory::parse_pokerstars(&text.replace("17:12:38 ET", "22:12:38 UTC")).unwrap();
+        assert!(utc.to_ohh().contains("\"start_date_utc\":\"2020-01-19T22:12:38Z\""));
+        let parsed = HandHistory::parse_ohh(&utc.to_ohh()).unwrap();
+        assert_eq!(parsed.date.as_deref(), Some("2020-01-19T22:12:38Z"));
+        assert!(parsed.to_ohh().contains("\"start_date_utc\":\"2020-01-19T22:12:38Z\""));
+        assert_eq!(parsed.actions[7].kind, HistoryActionKind::Uncalled(40));
+
+        // a raise to less than the player's own bet adds nothing
+        let mut inconsistent = hand.clone();
+        inconsistent.actions[2].kind = HistoryActionKind::Raise { by: 20, to: 3 };
+        assert!(inconsistent.to_ohh().contains("\"action\":\"Raise\",\"amount\":0.0"));
+    }
+
+    #[test]
+    fn parse_files() {
+        let text = "PokerStars Hand #7:  Hold'em No Limit (5/10)\nSeat 1: A (100 in chips)\nSeat 2: B (100 in chips)\n\
+            A: posts small blind 5\nB: posts big blind 10\nA: folds\nUncalled bet (5) returned to B\nB collected 10 from pot\n";
+        let json = HandHistory::parse_pokerstars(text).unwrap().to_ohh();
+        let file = format!("{}\n{}\n\n{}\n", json, json, json.replace("\"bet_type\":\"NL\"", "\"bet_type\":\"XX\""));
+
+        let error = HandHistory::parse_ohh_all(&file).unwrap_err();
+        assert_eq!(error.line(), 4);
+        assert_eq!(error.to_string(), "invalid hand history line 4: 'XX'");
+        assert_eq!(HandHistory::parse_ohh_all(&format!("{}\n{}", json, json)).unwrap().len(), 2);
+        assert_eq!(HandHistory::parse_ohh("{\"ohh\": {}}").unwrap_err().line(), 1);
+
+        // a raise that does not reach the bet to call
+        let raise = json.replace("\"action\":\"Fold\",\"amount\":0.0", "\"action\":\"Raise\",\"amount\":1.0");
+        assert_ne!(raise, json);
+        assert_eq!(HandHistory::parse_ohh(&raise).unwrap_err().to_string(), "invalid hand history line 1: 'Raise'");
+    }
+}
@@ -0,0 +1,367 @@
+use crate::cards::{Card, Rank, Suit};
+
+/// The categories of poker hands. Standard high hands are listed from weakest to strongest, followed by the categories
+/// that only lowball evaluators produce. Variants may rank the categories differently (see ```HandEvaluator```)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HandCategory {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+    RoyalFlush,
+    /// An unpaired hand in lowball, described by its highest cards, e.g. "Seven-five low"
+    Low,
+    /// A Badugi hand, made of up to four cards of different suits and ranks
+    Badugi,
+}
+
+impl std::fmt::Display for HandCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            HandCategory::HighCard => "High card",
+            HandCategory::OnePair => "Pair",
+            HandCategory::TwoPair => "Two pair",
+            HandCategory::ThreeOfAKind => "Three of a kind",
+            HandCategory::Straight => "Straight",
+            HandCategory::Flush => "Flush",
+            HandCategory::FullHouse => "Full house",
+            HandCategory::FourOfAKind => "Four of a kind",
+            HandCategory::StraightFlush => "Straight flush",
+            HandCategory::RoyalFlush => "Royal flush",
+            HandCategory::Low => "Low",
+            HandCategory::Badugi => "Badugi",
+        })
+    }
+}
+
+/// The value of a poker hand. Hand ranks are totally ordered: a stronger hand compares greater than a weaker one, and
+/// hands that split the pot compare equal.
+///
+/// A hand rank is made up of its category and the ranks that break ties within the category, in order of significance
+/// (e.g. the rank of the pair followed by the three kickers). Only the ranks that matter are kept, so a straight holds
+/// just its highest card.
+///
+/// Hands are ordered by their strength, which is assigned by the evaluator of each poker variant (e.g. lower hands are
+/// stronger in lowball). Only compare hand ranks produced by the same evaluator.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HandRank {
+    strength: u32,
+    category: HandCategory,
+    // poker values (2..=14, aces high, or 1 for aces played low), padded with 0
+    kickers: [u8; 5],
+}
+
+impl HandRank {
+    /// Get the category of the hand
+    pub fn category(&self) -> HandCategory {
+        self.category
+    }
+
+    /// Get the ranks that break ties within the category, most significant first
+    pub fn kickers(&self) -> Vec<Rank> {
+        self.kickers.iter().take_while(|v| **v > 0).map(|v| rank_of(*v)).collect()
+    }
+
+    /// Describe the hand, e.g. "Two pair, Kings and Sevens"
+    pub fn description(&self) -> String {
+        let k = &self.kickers;
+        match self.category {
+            HandCategory::HighCard => format!("High card, {}", name(k[0])),
+            HandCategory::OnePair => format!("Pair of {}", plural(k[0])),
+            HandCategory::TwoPair => format!("Two pair, {} and {}", plural(k[0]), plural(k[1])),
+            HandCategory::ThreeOfAKind => format!("Three of a kind, {}", plural(k[0])),
+            HandCategory::Straight => format!("Straight, {} high", name(k[0])),
+            HandCategory::Flush => format!("Flush, {} high", name(k[0])),
+            HandCategory::FullHouse => format!("Full house, {} full of {}", plural(k[0]), plural(k[1])),
+            HandCategory::FourOfAKind => format!("Four of a kind, {}", plural(k[0])),
+            HandCategory::StraightFlush => format!("Straight flush, {} high", name(k[0])),
+            HandCategory::RoyalFlush => "Royal flush".to_string(),
+            HandCategory::Low => format!("{}-{} low", name(k[0]), name(k[1]).to_lowercase()),
+            HandCategory::Badugi => match self.kickers().len() {
+                4 => format!("Badugi, {} high", name(k[0])),
+                n => format!("{}-card hand, {} high", ["One", "Two", "Three"][n - 1], name(k[0])),
+            },
+        }
+    }
+
+    /// Get the strength of the hand as an integer. Values compare in the same order as the hand ranks
+    pub fn value(&self) -> u32 {
+        self.strength
+    }
+
+    /// Unpack a standard high hand from the integer returned by ```value()```. Returns None for the values of other
+    /// variants
+    pub fn from_value(value: u32) -> Option<Self> {
+        const CATEGORIES: [HandCategory; 10] = [
+            HandCategory::HighCard, HandCategory::OnePair, HandCategory::TwoPair, HandCategory::ThreeOfAKind,
+            HandCategory::Straight, HandCategory::Flush, HandCategory::FullHouse, HandCategory::FourOfAKind,
+            HandCategory::StraightFlush, HandCategory::RoyalFlush,
+        ];
+        let category = *CATEGORIES.get((value >> 20) as usize)?;
+        let mut kickers = [0; 5];
+        for (i, kicker) in kickers.iter_mut().enumerate() {
+            *kicker = (value >> (16 - 4 * i) & 0xf) as u8;
+        }
+        Some(HandRank {
+            strength: value,
+            category,
+            kickers,
+        })
+    }
+
+    /// Create a standard high hand, whose strength packs the category and kickers into 24 bits
+    pub(crate) fn new(category: HandCategory, ranks: &[u8]) -> Self {
+        let hand = HandRank::with_strength(0, category, ranks);
+        HandRank {
+            strength: hand.packed(category as u32),
+            ..hand
+        }
+    }
+
+    /// Create a hand with a strength assigned by a variant's evaluator
+    pub(crate) fn with_strength(strength: u32, category: HandCategory, ranks: &[u8]) -> Self {
+        let mut kickers = [0; 5];
+        kickers[..ranks.len()].copy_from_slice(ranks);
+        HandRank {
+            strength,
+            category,
+            kickers,
+        }
+    }
+
+    /// Pack the kickers behind the given order of the category, as 4 bits each
+    pub(crate) fn packed(&self, order: u32) -> u32 {
+        self.kickers.iter().fold(order, |value, k| (value << 4) | *k as u32)
+    }
+}
+
+impl std::fmt::Display for HandRank {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.description())
+    }
+}
+
+/// Use this to evaluate poker hands of five to seven cards (e.g. two hole cards and a board in Texas Hold'em). The best
+/// five card hand that can be made from the cards is ranked.
+pub struct PokerEvaluator;
+
+impl PokerEvaluator {
+    /// Evaluate the best five card hand contained in ```cards```. Returns None unless there are 5 to 7 cards, or if any
+    /// of the cards is a joker or has no suit
+    pub fn evaluate(cards: &[Card]) -> Option<HandRank> {
+        if cards.len() < 5 || cards.len() > 7 || cards.iter().any(unplayable) {
+            return None;
+        }
+
+        // bit (value - 2) of a mask is set for each rank present, aces high
+        let mut suits = [0u16; 4];
+        let mut counts = [0u8; 15];
+        for card in cards {
+            let value = poker_value(card.rank());
+            suits[card.suit().to_ordinal() as usize] |= 1 << (value - 2);
+            counts[value as usize] += 1;
+        }
+
+        if let Some(flush) = suits.iter().find(|s| s.count_ones() >= 5) {
+            return Some(rank_flush(*flush));
+        }
+
+        Some(rank_by_counts(&counts, suits.iter().fold(0, |all, s| all | s)))
+    }
+
+    /// Compare two hands, returning the ordering of the first relative to the second (Greater if it wins). Invalid
+    /// hands lose to valid ones
+    pub fn compare(a: &[Card], b: &[Card]) -> std::cmp::Ordering {
+        Self::evaluate(a).cmp(&Self::evaluate(b))
+    }
+}
+
+/// Returns true for cards that poker hands cannot hold: jokers and cards without a suit
+pub(crate) fn unplayable(card: &Card) -> bool {
+    card.is_joker() || *card.suit() == Suit::None
+}
+
+/// Rank a flush from the mask of its values (bit ```value - 2``` set for each value present)
+pub(crate) fn rank_flush(flush: u16) -> HandRank {
+    match straight_high(flush) {
+        Some(14) => HandRank::new(HandCategory::RoyalFlush, &[14]),
+        Some(high) => HandRank::new(HandCategory::StraightFlush, &[high]),
+        None => HandRank::new(HandCategory::Flush, &highest(flush, 5)),
+    }
+}
+
+/// Rank a hand without a flush from the number of cards of each value and the mask of values present
+pub(crate) fn rank_by_counts(counts: &[u8; 15], ranks: u16) -> HandRank {
+    let mut quads = Vec::new();
+    let mut trips = Vec::new();
+    let mut pairs = Vec::new();
+    for value in (2..=14).rev() {
+        match counts[value as usize] {
+            4 => quads.push(value),
+            3 => trips.push(value),
+            2 => pairs.push(value),
+            _ => (),
+        }
+    }
+    let without = |values: &[u8], n: usize| {
+        let mask = values.iter().fold(ranks, |mask, v| mask & !(1 << (v - 2)));
+        highest(mask, n)
+    };
+
+    if let Some(quad) = quads.first() {
+        return HandRank::new(HandCategory::FourOfAKind, &[*quad, without(&[*quad], 1)[0]]);
+    }
+    if let Some(trip) = trips.first() {
+        // a second set of trips plays as the pair of a full house
+        if let Some(pair) = trips.get(1).into_iter().chain(pairs.first()).max() {
+            return HandRank::new(HandCategory::FullHouse, &[*trip, *pair]);
+        }
+    }
+    if let Some(high) = straight_high(ranks) {
+        return HandRank::new(HandCategory::Straight, &[high]);
+    }
+    if let Some(trip) = trips.first() {
+        let kickers = without(&[*trip], 2);
+        return HandRank::new(HandCategory::ThreeOfAKind, &[*trip, kickers[0], kickers[1]]);
+    }
+    if pairs.len() >= 2 {
+        return HandRank::new(HandCategory::TwoPair, &[pairs[0], pairs[1], without(&pairs[..2], 1)[0]]);
+    }
+    if let Some(pair) = pairs.first() {
+        let kickers = without(&[*pair], 3);
+        return HandRank::new(HandCategory::OnePair, &[*pair, kickers[0], kickers[1], kickers[2]]);
+    }
+    HandRank::new(HandCategory::HighCard, &highest(ranks, 5))
+}
+
+/// Get the highest card of the best straight in a mask of values, counting the ace as low as well as high
+fn straight_high(ranks: u16) -> Option<u8> {
+    // shift the values up so the ace can also sit below the two
+    let mask = ((ranks as u32) << 1) | ((ranks as u32 >> 12) & 1);
+    (0..=9).rev().find(|low| mask >> low & 0x1f == 0x1f).map(|low| low as u8 + 5)
+}
+
+/// Get the n highest values in a mask of values
+fn highest(ranks: u16, n: usize) -> Vec<u8> {
+    (2..=14u8).rev().filter(|v| ranks & (1 << (v - 2)) != 0).take(n).collect()
+}
+
+/// The value of a rank in poker (2..=14, aces high)
+pub(crate) fn poker_value(rank: &Rank) -> u8 {
+    match rank {
+        Rank::Ace => 14,
+        _ => rank.to_ordinal() + 1,
+    }
+}
+
+fn rank_of(value: u8) -> Rank {
+    Rank::from_ordinal(if value == 14 { 0 } else { value - 1 })
+}
+
+fn name(value: u8) -> &'static str {
+    if value == 1 {
+        return "Ace";
+    }
+    ["Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine", "Ten", "Jack", "Queen", "King", "Ace"][value as usize - 2]
+}
+
+fn plural(value: u8) -> &'static str {
+    if value == 1 {
+        return "Aces";
+    }
+    ["Twos", "Threes", "Fours", "Fives", "Sixes", "Sevens", "Eights", "Nines", "Tens", "Jacks", "Queens", "Kings", "Aces"][value as usize - 2]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HandCategory, HandRank, PokerEvaluator};
+    use crate::cards::{cards, Card, Rank, Suit};
+    use crate::deck::Deck;
+
+    fn describe(hand: &str) -> String {
+        PokerEvaluator::evaluate(&cards(hand)).unwrap().description()
+    }
+
+    #[test]
+    fn categories_and_descriptions() {
+        assert_eq!(describe("Ah Kh Qh Jh Th 2c 3d"), "Royal flush");
+        assert_eq!(describe("5d 4d 3d 2d Ad Ac"), "Straight flush, Five high");
+        assert_eq!(describe("9s 9h 9d 9c Ks"), "Four of a kind, Nines");
+        assert_eq!(describe("Kc Kd Ks 7c 7d 7h 2c"), "Full house, Kings full of Sevens");
+        assert_eq!(describe("Ac 9c 7c 4c 2c Kd"), "Flush, Ace high");
+        assert_eq!(describe("6c 5d 4h 3s 2c Ac"), "Straight, Six high");
+        assert_eq!(describe("Qc Qd Qs 8c 2d"), "Three of a kind, Queens");
+        assert_eq!(describe("Kc Kd 7s 7c 6d 6h 2c"), "Two pair, Kings and Sevens");
+        assert_eq!(describe("6c 6d As Tc 4d"), "Pair of Sixes");
+        assert_eq!(describe("Ac Jd 9s 6c 3d 2h"), "High card, Ace");
+    }
+
+    #[test]
+    fn kickers() {
+        let hand = PokerEvaluator::evaluate(&cards("Kc Kd 7s 7c 6d 6h Qc")).unwrap();
+
+        assert_eq!(hand.category(), HandCategory::TwoPair);
+        assert_eq!(hand.kickers(), vec![Rank::King, Rank::Seven, Rank::Queen]);
+        assert!(PokerEvaluator::evaluate(&cards("Ac Ad 9s 8c 3d")) > PokerEvaluator::evaluate(&cards("Ac Ad 9s 7c 6d")));
+        assert_eq!(PokerEvaluator::evaluate(&cards("Ac Ad 9s 8c 3d 2c")), PokerEvaluator::evaluate(&cards("Ah As 9d 8h 3s")));
+    }
+
+    #[test]
+    fn packed_values() {
+        let full_house = PokerEvaluator::evaluate(&cards("Kc Kd Ks 7c 7d 7h 2c")).unwrap();
+        let flush = PokerEvaluator::evaluate(&cards("Ac 9c 7c 4c 2c Kd")).unwrap();
+
+        assert_eq!(HandRank::from_value(full_house.value()), Some(full_house));
+        assert!(full_house.value() > flush.value());
+        assert_eq!(HandRank::from_value(10 << 20), None);
+    }
+
+    #[test]
+    fn category_order() {
+        let hands = [
+            "Ac Jd 9s 6c 3d", "2c 2d 3s 4c 5d 8h", "2c 2d 3s 3c 4d", "2c 2d 2s 3c 4d", "5c 4d 3s 2c Ad",
+            "2c 3c 4c 5c 7c", "2c 2d 2s 3c 3d", "2c 2d 2s 2h 3d", "2c 3c 4c 5c 6c", "Tc Jc Qc Kc Ac",
+        ];
+        let ranks: Vec<_> = hands.iter().map(|h| PokerEvaluator::evaluate(&cards(h)).unwrap()).collect();
+
+        assert!(ranks.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(ranks[9].category(), HandCategory::RoyalFlush);
+    }
+
+    #[test]
+    fn invalid_hands() {
+        assert_eq!(PokerEvaluator::evaluate(&cards("Ac Kd Qs Jh")), None);
+        assert_eq!(PokerEvaluator::evaluate(&Deck::new().iter().take(8).cloned().collect::<Vec<_>>()), None);
+
+        let mut hand = cards("Ac Kd Qs Jh");
+        hand.push(Card::joker());
+        assert_eq!(PokerEvaluator::evaluate(&hand), None);
+        assert_eq!(PokerEvaluator::compare(&cards("2c 3d 4h 5s 7c"), &hand), std::cmp::Ordering::Greater);
+
+        let mut hand = cards("Ac Kd Qs Jh");
+        hand.push(Card::from_suit_and_rank(Suit::None, Rank::Ace));
+        assert_eq!(PokerEvaluator::evaluate(&hand), None);
+    }
+
+    #[test]
+    fn five_card_category_frequencies() {
+        let mut counts = [0usize; 10];
+        let clubs: Vec<Card> = Deck::new().iter().filter(|c| *c.suit() == Suit::Clubs || *c.suit() == Suit::Diamonds).cloned().collect();
+        for hand in Deck::from_cards(clubs).combinations(5) {
+            counts[PokerEvaluator::evaluate(&hand).unwrap().category() as usize] += 1;
+        }
+
+        // all 5-card hands from two suits (26 choose 5)
+        assert_eq!(counts.iter().sum::<usize>(), 65_780);
+        assert_eq!(counts[HandCategory::RoyalFlush as usize], 2);
+        assert_eq!(counts[HandCategory::StraightFlush as usize], 18);
+        assert_eq!(counts[HandCategory::FourOfAKind as usize], 0);
+    }
+}
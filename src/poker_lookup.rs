@@ -0,0 +1,220 @@
+use std::sync::OnceLock;
+use crate::card_set::CardSet;
+use crate::poker::{rank_by_counts, rank_flush, HandRank};
+
+/// The mask of the 13 cards of a suit within a ```CardSet```
+const SUIT_MASK: u64 = (1 << 13) - 1;
+
+/// The number of buckets (as a power of 2) used to build the perfect hash of rank multisets
+const BUCKET_BITS: u32 = 15;
+
+/// The size of the rank table (as a power of 2), which holds the 73,775 multisets of 5 to 7 ranks
+const SLOT_BITS: u32 = 17;
+
+/// The tables used by the ```LookupEvaluator```, generated on first use
+struct Tables {
+    /// Packed hand ranks of flushes, indexed by the 13-bit mask of a suit's ranks (0 if the suit is not a flush)
+    flush: Vec<u32>,
+    /// The key of each 13-bit mask of ranks, i.e. the sum of ```5^rank```. Adding up the keys of the four suits gives a
+    /// key that identifies how many cards of each rank a hand holds
+    keys: Vec<u32>,
+    /// The displacement of each bucket of the perfect hash
+    displacements: Vec<u16>,
+    /// Packed hand ranks of hands without a flush, indexed by the perfect hash of their key
+    ranks: Vec<u32>,
+}
+
+impl Tables {
+    fn new() -> Self {
+        let flush = (0..1u32 << 13).map(|mask| {
+            if mask.count_ones() < 5 {
+                0
+            } else {
+                rank_flush(values_mask(mask as u64)).value()
+            }
+        }).collect();
+        let keys = (0..1u32 << 13).map(|mask| (0..13).filter(|r| mask & (1 << r) != 0).map(|r| 5u32.pow(r)).sum()).collect();
+
+        let mut entries = Vec::new();
+        for n in 5..=7 {
+            rank_multisets(&mut [0; 13], 0, n, &mut entries);
+        }
+
+        // hash and displace: the keys of each bucket are placed with the first displacement that maps all of them to
+        // free slots, filling the largest buckets first
+        let mut buckets: Vec<Vec<(u32, u32)>> = vec![Vec::new(); 1 << BUCKET_BITS];
+        for (key, value) in entries {
+            buckets[bucket(key)].push((key, value));
+        }
+        let mut order: Vec<usize> = (0..buckets.len()).collect();
+        order.sort_by_key(|b| std::cmp::Reverse(buckets[*b].len()));
+
+        let mut displacements = vec![0u16; 1 << BUCKET_BITS];
+        let mut ranks = vec![0u32; 1 << SLOT_BITS];
+        let mut used = vec![false; 1 << SLOT_BITS];
+        for b in order.into_iter().take_while(|b| !buckets[*b].is_empty()) {
+            let displacement = (0..=u16::MAX).find(|d| {
+                let mut slots: Vec<usize> = buckets[b].iter().map(|(key, _)| slot(*key, *d)).collect();
+                slots.sort_unstable();
+                slots.dedup();
+                slots.len() == buckets[b].len() && slots.iter().all(|s| !used[*s])
+            }).expect("no displacement found for the poker lookup tables");
+
+            displacements[b] = displacement;
+            for (key, value) in buckets[b].iter() {
+                let s = slot(*key, displacement);
+                used[s] = true;
+                ranks[s] = *value;
+            }
+        }
+
+        Tables {
+            flush,
+            keys,
+            displacements,
+            ranks,
+        }
+    }
+}
+
+/// Collect the key and packed hand rank of every multiset of ```left``` more cards placed on the ranks from ```rank```
+/// upwards, with at most 4 cards per rank
+fn rank_multisets(counts: &mut [u8; 13], rank: usize, left: usize, entries: &mut Vec<(u32, u32)>) {
+    if rank == 13 {
+        if left == 0 {
+            let mut by_value = [0u8; 15];
+            let mut mask = 0u64;
+            let mut key = 0;
+            for (r, count) in counts.iter().enumerate() {
+                by_value[if r == 0 { 14 } else { r + 1 }] = *count;
+                if *count > 0 {
+                    mask |= 1 << r;
+                }
+                key += *count as u32 * 5u32.pow(r as u32);
+            }
+            entries.push((key, rank_by_counts(&by_value, values_mask(mask)).value()));
+        }
+        return;
+    }
+    for count in 0..=left.min(4) {
+        counts[rank] = count as u8;
+        rank_multisets(counts, rank + 1, left - count, entries);
+    }
+    counts[rank] = 0;
+}
+
+#[inline]
+fn bucket(key: u32) -> usize {
+    ((key as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15) >> (64 - BUCKET_BITS)) as usize
+}
+
+#[inline]
+fn slot(key: u32, displacement: u16) -> usize {
+    let h = (key as u64 | (displacement as u64) << 32).wrapping_mul(0xc2b2_ae3d_27d4_eb4f);
+    ((h ^ (h >> 29)).wrapping_mul(0x1656_67b1_9e37_79f9) >> (64 - SLOT_BITS)) as usize
+}
+
+/// Convert a mask of rank ordinals (bit 0 is the ace) into a mask of poker values (bit ```value - 2```, aces high)
+fn values_mask(ordinals: u64) -> u16 {
+    (((ordinals >> 1) & 0xfff) | ((ordinals & 1) << 12)) as u16
+}
+
+fn tables() -> &'static Tables {
+    static TABLES: OnceLock<Tables> = OnceLock::new();
+    TABLES.get_or_init(Tables::new)
+}
+
+/// A table driven poker evaluator for high throughput, e.g. when enumerating boards or running simulations. It ranks
+/// hands of five to seven cards held in a ```CardSet```, and agrees with ```PokerEvaluator``` on every hand.
+///
+/// Flushes are looked up by the ranks of the suit, and other hands by a perfect hash of the number of cards of each
+/// rank. The tables (about 640KB) are generated the first time they are needed, or up front with ```init()```.
+pub struct LookupEvaluator;
+
+impl LookupEvaluator {
+    /// Generate the lookup tables, so that the first evaluation does not pay for it
+    pub fn init() {
+        tables();
+    }
+
+    /// Evaluate the best five card hand in the set. Returns None unless the set holds 5 to 7 cards, or if it holds a
+    /// joker
+    pub fn evaluate(cards: &CardSet) -> Option<HandRank> {
+        if cards.len() < 5 || cards.len() > 7 || cards.bits() >> 52 != 0 {
+            return None;
+        }
+        HandRank::from_value(Self::strength(cards))
+    }
+
+    /// Get the packed hand rank (see ```HandRank::value()```) of a set of 5 to 7 cards without jokers. Stronger hands
+    /// have greater values. This skips the validation done by ```evaluate()```, and the result is unspecified for
+    /// other sets
+    #[inline]
+    pub fn strength(cards: &CardSet) -> u32 {
+        let tables = tables();
+        let bits = cards.bits();
+        let suits = [bits & SUIT_MASK, (bits >> 13) & SUIT_MASK, (bits >> 26) & SUIT_MASK, (bits >> 39) & SUIT_MASK];
+
+        // suits of fewer than five cards have no entry in the flush table
+        for suit in suits.iter() {
+            let flush = tables.flush[*suit as usize];
+            if flush != 0 {
+                return flush;
+            }
+        }
+
+        let key = suits.iter().map(|s| tables.keys[*s as usize]).sum::<u32>();
+        tables.ranks[slot(key, tables.displacements[bucket(key)])]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use super::LookupEvaluator;
+    use crate::card_set::CardSet;
+    use crate::cards::Card;
+    use crate::deck::Deck;
+    use crate::poker::{HandCategory, PokerEvaluator};
+
+    #[test]
+    fn agrees_with_simple_evaluator() {
+        let mut rng = StdRng::seed_from_u64(40);
+        let deck: Vec<Card> = Deck::new().iter().cloned().collect();
+
+        for i in 0..30_000 {
+            let hand: Vec<Card> = deck.choose_multiple(&mut rng, 5 + i % 3).cloned().collect();
+            let set: CardSet = hand.iter().collect();
+            assert_eq!(LookupEvaluator::evaluate(&set), PokerEvaluator::evaluate(&hand), "{}", set);
+        }
+    }
+
+    #[test]
+    fn category_frequencies() {
+        // every 5-card hand from the 20 cards of ranks Ten to Ace
+        let broadway: CardSet = Deck::new().iter().filter(|c| c.rank().to_ordinal() == 0 || c.rank().to_ordinal() >= 9).collect();
+        let mut counts = [0usize; 10];
+        for hand in broadway.combinations(5) {
+            counts[LookupEvaluator::evaluate(&hand).unwrap().category() as usize] += 1;
+        }
+
+        assert_eq!(counts.iter().sum::<usize>(), 15_504);
+        assert_eq!(counts[HandCategory::RoyalFlush as usize], 4);
+        assert_eq!(counts[HandCategory::Straight as usize], 1020);
+        assert_eq!(counts[HandCategory::FourOfAKind as usize], 5 * 16);
+    }
+
+    #[test]
+    fn invalid_sets() {
+        let four: CardSet = Deck::new().iter().take(4).collect();
+        let eight: CardSet = Deck::new().iter().take(8).collect();
+        let mut joker = four;
+        joker.insert(Card::joker());
+
+        assert_eq!(LookupEvaluator::evaluate(&four), None);
+        assert_eq!(LookupEvaluator::evaluate(&eight), None);
+        assert_eq!(LookupEvaluator::evaluate(&joker), None);
+    }
+}
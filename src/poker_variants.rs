@@ -0,0 +1,348 @@
+use crate::cards::{Card, Rank};
+use crate::combinations::Combinations;
+use crate::poker::{poker_value, unplayable, HandCategory, HandRank, PokerEvaluator};
+
+// The strength of each variant's hands is tagged in the top bits, so that they never unpack as standard high hands
+const SHORT_DECK: u32 = 1 << 24;
+const ACE_TO_FIVE: u32 = 2 << 24;
+const DEUCE_TO_SEVEN: u32 = 3 << 24;
+const BADUGI: u32 = 4 << 24;
+
+/// The largest packed category and kickers (see ```HandRank::packed()```), used to invert the order of lowball hands
+const MAX_PACKED: u32 = (1 << 24) - 1;
+
+/// Implemented by the hand evaluators of each poker variant, so that table engines can rank and compare hands without
+/// knowing which variant is being played. The best hand compares greatest, whether it is the highest or the lowest.
+pub trait HandEvaluator {
+    /// Evaluate the best hand that can be made from a player's hole cards and the board (empty in games without
+    /// community cards). Returns None if the cards cannot make a hand in this variant
+    fn evaluate(&self, hole: &[Card], board: &[Card]) -> Option<HandRank>;
+
+    /// Evaluate the best low hand in split pot games. Returns None if the variant has no low half, or if the hand does
+    /// not qualify for it
+    fn evaluate_low(&self, _hole: &[Card], _board: &[Card]) -> Option<HandRank> {
+        None
+    }
+}
+
+/// Texas Hold'em and other games where the best five of all the cards play
+impl HandEvaluator for PokerEvaluator {
+    fn evaluate(&self, hole: &[Card], board: &[Card]) -> Option<HandRank> {
+        PokerEvaluator::evaluate(&[hole, board].concat())
+    }
+}
+
+/// Short deck (6+) Hold'em, played without the Twos to Fives. A flush beats a full house, and the ace plays low in the
+/// A-6-7-8-9 straight.
+pub struct ShortDeckEvaluator;
+
+impl HandEvaluator for ShortDeckEvaluator {
+    fn evaluate(&self, hole: &[Card], board: &[Card]) -> Option<HandRank> {
+        let cards = [hole, board].concat();
+        if cards.iter().any(|c| *c.rank() != Rank::Ace && *c.rank() < Rank::Six) {
+            return None;
+        }
+        let rules = Rules { ace_low: false, straights: true, flushes: true, wheel: Some(9) };
+
+        best_of(&cards, 5, 7, |hand| {
+            let rank = classify(hand, &rules);
+            let order = match rank.category() {
+                HandCategory::Flush => HandCategory::FullHouse as u32,
+                HandCategory::FullHouse => HandCategory::Flush as u32,
+                category => category as u32,
+            };
+            Some(HandRank::with_strength(SHORT_DECK | rank.packed(order), rank.category(), &kickers(&rank)))
+        })
+    }
+}
+
+/// Omaha, where a hand is made of exactly two hole cards and three cards of the board
+pub struct OmahaEvaluator;
+
+impl HandEvaluator for OmahaEvaluator {
+    fn evaluate(&self, hole: &[Card], board: &[Card]) -> Option<HandRank> {
+        best_omaha(hole, board, PokerEvaluator::evaluate)
+    }
+}
+
+/// Omaha Hi-Lo (eight or better), where the pot is split between the best high hand and the best qualifying low hand.
+/// A low hand is made of five unpaired cards of Eight or lower (aces are low), using two hole cards and three cards of
+/// the board.
+pub struct OmahaHiLoEvaluator;
+
+impl HandEvaluator for OmahaHiLoEvaluator {
+    fn evaluate(&self, hole: &[Card], board: &[Card]) -> Option<HandRank> {
+        OmahaEvaluator.evaluate(hole, board)
+    }
+
+    fn evaluate_low(&self, hole: &[Card], board: &[Card]) -> Option<HandRank> {
+        best_omaha(hole, board, |hand| {
+            Some(ace_to_five(hand)).filter(|low| low.category() == HandCategory::Low && low.kickers().iter().all(|r| *r <= Rank::Eight))
+        })
+    }
+}
+
+/// Ace-to-five lowball (e.g. Razz or California lowball), where the lowest hand wins. Aces are low, and straights and
+/// flushes do not count against a hand, so the best hand is 5-4-3-2-A. The best five of five to seven cards play.
+pub struct AceToFiveEvaluator;
+
+impl HandEvaluator for AceToFiveEvaluator {
+    fn evaluate(&self, hole: &[Card], board: &[Card]) -> Option<HandRank> {
+        best_of(&[hole, board].concat(), 5, 7, |hand| Some(ace_to_five(hand)))
+    }
+}
+
+/// Deuce-to-seven lowball (Kansas City lowball), where the lowest hand wins. Aces are always high, and straights and
+/// flushes count against a hand, so the best hand is 7-5-4-3-2 of mixed suits.
+pub struct DeuceToSevenEvaluator;
+
+impl HandEvaluator for DeuceToSevenEvaluator {
+    fn evaluate(&self, hole: &[Card], board: &[Card]) -> Option<HandRank> {
+        let rules = Rules { ace_low: false, straights: true, flushes: true, wheel: None };
+
+        best_of(&[hole, board].concat(), 5, 7, |hand| {
+            let rank = classify(hand, &rules);
+            Some(HandRank::with_strength(DEUCE_TO_SEVEN | (MAX_PACKED - rank.value()), low_category(&rank), &kickers(&rank)))
+        })
+    }
+}
+
+/// Badugi, where the best hand is the largest set of cards of different suits and different ranks (aces are low). A
+/// four card badugi beats any three card hand, and hands of the same size are compared by their highest cards, lowest
+/// first.
+pub struct BadugiEvaluator;
+
+impl HandEvaluator for BadugiEvaluator {
+    fn evaluate(&self, hole: &[Card], board: &[Card]) -> Option<HandRank> {
+        let cards = [hole, board].concat();
+        if cards.is_empty() || cards.len() > 8 || cards.iter().any(unplayable) {
+            return None;
+        }
+
+        let mut best: Option<HandRank> = None;
+        for subset in 1u32..1 << cards.len() {
+            if subset.count_ones() > 4 {
+                continue;
+            }
+            let hand: Vec<&Card> = cards.iter().enumerate().filter(|(i, _)| subset & (1 << i) != 0).map(|(_, c)| c).collect();
+            let mut suits: Vec<_> = hand.iter().map(|c| c.suit().to_ordinal()).collect();
+            let mut values: Vec<u8> = hand.iter().map(|c| c.rank().to_ordinal() + 1).collect();
+            suits.sort_unstable();
+            suits.dedup();
+            values.sort_unstable_by(|a, b| b.cmp(a));
+            values.dedup();
+            if suits.len() < hand.len() || values.len() < hand.len() {
+                continue;
+            }
+
+            let packed = values.iter().chain(std::iter::repeat(&0)).take(4).fold(0, |p, v| (p << 4) | *v as u32);
+            let strength = BADUGI | ((hand.len() as u32) << 16) | (0xffff - packed);
+            let rank = HandRank::with_strength(strength, HandCategory::Badugi, &values);
+            if Some(rank) > best {
+                best = Some(rank);
+            }
+        }
+        best
+    }
+}
+
+/// The rules used to classify a five card hand
+struct Rules {
+    ace_low: bool,
+    straights: bool,
+    flushes: bool,
+    /// The highest card of the straight in which the ace plays low, if any
+    wheel: Option<u8>,
+}
+
+/// Classify exactly five cards as a standard high hand, under the given rules
+fn classify(hand: &[Card], rules: &Rules) -> HandRank {
+    let mut groups: Vec<(u8, u8)> = Vec::new();
+    for card in hand {
+        let value = if rules.ace_low && *card.rank() == Rank::Ace { 1 } else { poker_value(card.rank()) };
+        match groups.iter_mut().find(|(_, v)| *v == value) {
+            Some((count, _)) => *count += 1,
+            None => groups.push((1, value)),
+        }
+    }
+    // by number of cards, then by value
+    groups.sort_unstable_by(|a, b| b.cmp(a));
+    let values: Vec<u8> = groups.iter().map(|(_, v)| *v).collect();
+
+    let flush = rules.flushes && hand.iter().all(|c| c.suit() == hand[0].suit());
+    let straight = if rules.straights && values.len() == 5 {
+        if values[0] - values[4] == 4 {
+            Some(values[0])
+        } else {
+            rules.wheel.filter(|w| values[0] == 14 && values[1] == *w && values[4] == w - 3)
+        }
+    } else {
+        None
+    };
+
+    match (straight, flush) {
+        (Some(14), true) => HandRank::new(HandCategory::RoyalFlush, &[14]),
+        (Some(high), true) => HandRank::new(HandCategory::StraightFlush, &[high]),
+        (None, true) => HandRank::new(HandCategory::Flush, &values),
+        (Some(high), false) => HandRank::new(HandCategory::Straight, &[high]),
+        (None, false) => {
+            let category = match (groups[0].0, groups.get(1).map_or(0, |g| g.0)) {
+                (4, _) => HandCategory::FourOfAKind,
+                (3, 2) => HandCategory::FullHouse,
+                (3, _) => HandCategory::ThreeOfAKind,
+                (2, 2) => HandCategory::TwoPair,
+                (2, _) => HandCategory::OnePair,
+                _ => HandCategory::HighCard,
+            };
+            HandRank::new(category, &values)
+        }
+    }
+}
+
+/// Rank exactly five cards as an ace-to-five low hand
+fn ace_to_five(hand: &[Card]) -> HandRank {
+    let rules = Rules { ace_low: true, straights: false, flushes: false, wheel: None };
+    let rank = classify(hand, &rules);
+    HandRank::with_strength(ACE_TO_FIVE | (MAX_PACKED - rank.value()), low_category(&rank), &kickers(&rank))
+}
+
+/// Unpaired lowball hands are described as lows rather than by their high card
+fn low_category(rank: &HandRank) -> HandCategory {
+    match rank.category() {
+        HandCategory::HighCard => HandCategory::Low,
+        category => category,
+    }
+}
+
+/// Get the kickers of a hand as values, keeping aces that play low
+fn kickers(rank: &HandRank) -> Vec<u8> {
+    (0..5).map(|i| (rank.packed(0) >> (16 - 4 * i) & 0xf) as u8).take_while(|v| *v > 0).collect()
+}
+
+/// Get the best hand ranked by ```rank``` over all five card subsets of ```min``` to ```max``` cards
+fn best_of<F>(cards: &[Card], min: usize, max: usize, rank: F) -> Option<HandRank>
+where
+    F: Fn(&[Card]) -> Option<HandRank>,
+{
+    if cards.len() < min || cards.len() > max || cards.iter().any(unplayable) {
+        return None;
+    }
+    Combinations::new(cards.to_vec(), 5).filter_map(|hand| rank(&hand)).max()
+}
+
+/// Get the best hand ranked by ```rank``` using exactly two hole cards and three cards of the board
+fn best_omaha<F>(hole: &[Card], board: &[Card], rank: F) -> Option<HandRank>
+where
+    F: Fn(&[Card]) -> Option<HandRank>,
+{
+    if hole.len() < 2 || board.len() < 3 || hole.iter().chain(board.iter()).any(unplayable) {
+        return None;
+    }
+    Combinations::new(hole.to_vec(), 2)
+        .flat_map(|pair| Combinations::new(board.to_vec(), 3).map(move |three| [pair.as_slice(), &three].concat()))
+        .filter_map(|hand| rank(&hand))
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AceToFiveEvaluator, BadugiEvaluator, DeuceToSevenEvaluator, HandEvaluator, OmahaEvaluator, OmahaHiLoEvaluator, ShortDeckEvaluator};
+    use crate::cards::{cards, Card, Rank, Suit};
+    use crate::poker::{HandCategory, PokerEvaluator};
+
+    fn describe(evaluator: &dyn HandEvaluator, hole: &str, board: &str) -> String {
+        evaluator.evaluate(&cards(hole), &cards(board)).unwrap().description()
+    }
+
+    #[test]
+    fn omaha_uses_two_hole_cards() {
+        let hole = cards("As Ks Qs Js");
+        let board = cards("2s 3s 4d 5h 9c");
+
+        assert_eq!(describe(&PokerEvaluator, "As Ks", "2s 3s 4d 5h 9c"), "Straight, Five high");
+        assert_eq!(OmahaEvaluator.evaluate(&hole, &board).unwrap().kickers().len(), 5);
+        assert_eq!(describe(&OmahaEvaluator, "As Ks Qs Js", "2s 3s 4d 5h 9c"), "High card, Ace");
+        assert_eq!(describe(&OmahaEvaluator, "As Ks Qs Js", "2s 3s 4s 5h 9c"), "Flush, Ace high");
+        assert_eq!(OmahaEvaluator.evaluate(&hole, &board[..2]), None);
+    }
+
+    #[test]
+    fn omaha_hi_lo() {
+        let (hole, board) = (cards("Ac 2d Kh Ks"), cards("3c 4d 8h Kc Qd"));
+
+        assert_eq!(OmahaHiLoEvaluator.evaluate(&hole, &board).unwrap().description(), "Three of a kind, Kings");
+        assert_eq!(OmahaHiLoEvaluator.evaluate_low(&hole, &board).unwrap().description(), "Eight-four low");
+        assert_eq!(OmahaHiLoEvaluator.evaluate_low(&hole, &cards("3c 9d Th Kc Qd")), None);
+        assert_eq!(OmahaEvaluator.evaluate_low(&hole, &board), None);
+
+        let wheel = OmahaHiLoEvaluator.evaluate_low(&cards("Ac 2d Kh Ks"), &cards("3c 4d 5h Kc Qd")).unwrap();
+        assert_eq!(wheel.description(), "Five-four low");
+        assert!(wheel > OmahaHiLoEvaluator.evaluate_low(&hole, &board).unwrap());
+    }
+
+    #[test]
+    fn short_deck() {
+        let flush = ShortDeckEvaluator.evaluate(&cards("Ah 9h"), &cards("6h 7h Jh Jc Js")).unwrap();
+        let full_house = ShortDeckEvaluator.evaluate(&cards("Ac Ad"), &cards("6h 6s Jh Jc Js")).unwrap();
+
+        assert_eq!(flush.category(), HandCategory::Flush);
+        assert_eq!(full_house.category(), HandCategory::FullHouse);
+        assert!(flush > full_house);
+        assert_eq!(describe(&ShortDeckEvaluator, "Ac 6d", "7h 8s 9c Kd Kh"), "Straight, Nine high");
+        assert_eq!(ShortDeckEvaluator.evaluate(&cards("Ac 2d"), &cards("7h 8s 9c Kd Kh")), None);
+    }
+
+    #[test]
+    fn ace_to_five_lowball() {
+        let wheel = AceToFiveEvaluator.evaluate(&cards("Ac 2c 3c 4c 5c"), &[]).unwrap();
+        let six = AceToFiveEvaluator.evaluate(&cards("Ac 2d 3c 4c 6c"), &[]).unwrap();
+        let pair = AceToFiveEvaluator.evaluate(&cards("Ac Ad 3c 4c 2c"), &[]).unwrap();
+
+        assert_eq!(wheel.description(), "Five-four low");
+        assert_eq!(six.description(), "Six-four low");
+        assert_eq!(pair.description(), "Pair of Aces");
+        assert!(wheel > six && six > pair);
+
+        // razz: the best five of seven cards
+        assert_eq!(describe(&AceToFiveEvaluator, "Kc Kd 7h", "2c 3d 4h 6s"), "Seven-six low");
+    }
+
+    #[test]
+    fn deuce_to_seven_lowball() {
+        let best = DeuceToSevenEvaluator.evaluate(&cards("7c 5d 4c 3c 2c"), &[]).unwrap();
+        let ace_high = DeuceToSevenEvaluator.evaluate(&cards("Ac 2d 3c 4c 5c"), &[]).unwrap();
+        let king_high = DeuceToSevenEvaluator.evaluate(&cards("Kc 2d 3c 4c 5c"), &[]).unwrap();
+        let straight = DeuceToSevenEvaluator.evaluate(&cards("6c 2d 3c 4c 5c"), &[]).unwrap();
+        let flush = DeuceToSevenEvaluator.evaluate(&cards("7c 5c 4c 3c 2c"), &[]).unwrap();
+
+        assert_eq!(best.description(), "Seven-five low");
+        assert_eq!(ace_high.description(), "Ace-five low");
+        assert!(best > king_high && king_high > ace_high && ace_high > straight);
+        assert_eq!(straight.category(), HandCategory::Straight);
+        assert!(straight > flush);
+    }
+
+    #[test]
+    fn badugi() {
+        let badugi = BadugiEvaluator.evaluate(&cards("Ks 2d 3h 4c"), &[]).unwrap();
+        let wheel = BadugiEvaluator.evaluate(&cards("As 2d 3h 4c"), &[]).unwrap();
+        let three = BadugiEvaluator.evaluate(&cards("As 2s 3h 4c"), &[]).unwrap();
+
+        assert_eq!(badugi.description(), "Badugi, King high");
+        assert_eq!(wheel.description(), "Badugi, Four high");
+        assert_eq!(three.description(), "Three-card hand, Four high");
+        assert!(wheel > badugi && badugi > three);
+        assert_eq!(BadugiEvaluator.evaluate(&cards("As Ad Ah Ac"), &[]).unwrap().description(), "One-card hand, Ace high");
+        let unsuited = Card::from_suit_and_rank(Suit::None, Rank::Five);
+        assert_eq!(BadugiEvaluator.evaluate(&[cards("As 2d 3h 4c"), vec![unsuited]].concat(), &[]), None);
+    }
+
+    #[test]
+    fn variant_agnostic_comparison() {
+        let evaluators: Vec<Box<dyn HandEvaluator>> = vec![Box::new(PokerEvaluator), Box::new(DeuceToSevenEvaluator)];
+        let (a, b) = (cards("Ac Kc Qc Jc 9d"), cards("7c 5d 4c 3c 2c"));
+
+        let winners: Vec<bool> = evaluators.iter().map(|e| e.evaluate(&a, &[]) > e.evaluate(&b, &[])).collect();
+        assert_eq!(winners, vec![true, false]);
+    }
+}
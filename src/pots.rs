@@ -0,0 +1,321 @@
+use std::collections::BTreeMap;
+use crate::poker::HandRank;
+
+/// A main or side pot: the chips in it and the seats of the players who can win it
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pot {
+    amount: u64,
+    eligible: Vec<usize>,
+}
+
+impl Pot {
+    /// Get the chips in the pot
+    pub fn amount(&self) -> u64 {
+        self.amount
+    }
+
+    /// Get the seats of the players who can win the pot, in the order odd chips are given out
+    pub fn eligible(&self) -> &[usize] {
+        &self.eligible
+    }
+}
+
+/// The half of a pot that an award was won from
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Share {
+    /// The whole pot, when nobody qualifies for the low half (or the game has none)
+    Whole,
+    /// The high half of a split pot
+    High,
+    /// The low half of a split pot
+    Low,
+}
+
+/// The chips a player won from one of the pots
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Award {
+    /// The index of the pot, 0 being the main pot and then the side pots in order
+    pub pot: usize,
+    pub seat: usize,
+    pub amount: u64,
+    pub share: Share,
+}
+
+/// The outcome of settling the pots of a hand: the uncalled bet returned (if any), the pots and what each player won
+/// from them
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Settlement {
+    uncalled: Option<(usize, u64)>,
+    pots: Vec<Pot>,
+    awards: Vec<Award>,
+}
+
+impl Settlement {
+    /// Get the seat and amount of the bet nobody called, which goes back to the player before the pots are awarded
+    pub fn uncalled(&self) -> Option<(usize, u64)> {
+        self.uncalled
+    }
+
+    /// Get the main pot followed by the side pots
+    pub fn pots(&self) -> &[Pot] {
+        &self.pots
+    }
+
+    /// Get the awards of every pot, in the order of the pots
+    pub fn awards(&self) -> &[Award] {
+        &self.awards
+    }
+
+    /// Get the chips that go to the player in the seat, including the uncalled bet
+    pub fn total(&self, seat: usize) -> u64 {
+        let uncalled = self.uncalled.filter(|(s, _)| *s == seat).map_or(0, |(_, amount)| amount);
+        uncalled + self.awards.iter().filter(|a| a.seat == seat).map(|a| a.amount).sum::<u64>()
+    }
+}
+
+/// Splits the chips put in during a hand into the main pot and side pots, and awards them.
+///
+/// Every player's contribution is tracked by seat. A player still in the hand can only win from each opponent as much
+/// as they put in themselves, so each all-in amount caps a pot, and the chips above it go to the next side pot. Folded
+/// players' chips stay in the pots, but they cannot win them. A bet that nobody called is returned first.
+///
+/// Pots are awarded to the best hands among their eligible players. In split pot games the low half goes to the best
+/// qualifying low hand and the high half (with the odd chip) to the best high hand, and if nobody qualifies for the
+/// low, the high hand scoops. When a pot or half is split evenly, the odd chips go one each to the winners in seat
+/// order from the left of the button.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PotManager {
+    seats: usize,
+    button: usize,
+    // contribution and whether the player folded, by seat
+    players: BTreeMap<usize, (u64, bool)>,
+}
+
+impl PotManager {
+    /// Create an empty pot manager for a table with the given number of seats and the button in the given seat
+    pub fn new(seats: usize, button: usize) -> Self {
+        PotManager {
+            seats: seats.max(1),
+            button,
+            players: BTreeMap::new(),
+        }
+    }
+
+    /// Add chips that the player in the seat put in the pot (antes, blinds, calls, bets and raises)
+    pub fn contribute(&mut self, seat: usize, amount: u64) {
+        self.players.entry(seat).or_insert((0, false)).0 += amount;
+    }
+
+    /// Mark the player in the seat as folded, so that they cannot win any pot
+    pub fn fold(&mut self, seat: usize) {
+        self.players.entry(seat).or_insert((0, false)).1 = true;
+    }
+
+    /// Get the chips the player in the seat put in the pot
+    pub fn contribution(&self, seat: usize) -> u64 {
+        self.players.get(&seat).map_or(0, |(amount, _)| *amount)
+    }
+
+    /// Get all the chips put in the pot
+    pub fn total(&self) -> u64 {
+        self.players.values().map(|(amount, _)| amount).sum()
+    }
+
+    /// Get the seat and amount of the part of the largest contribution that no other player matched
+    pub fn uncalled(&self) -> Option<(usize, u64)> {
+        let mut by_amount: Vec<(usize, u64)> = self.players.iter().map(|(seat, (amount, _))| (*seat, *amount)).collect();
+        by_amount.sort_by_key(|(_, amount)| std::cmp::Reverse(*amount));
+        let (seat, top) = *by_amount.first()?;
+        let second = by_amount.get(1).map_or(0, |(_, amount)| *amount);
+        (top > second).then(|| (seat, top - second))
+    }
+
+    /// Get the main pot followed by the side pots, once the uncalled bet is returned
+    pub fn pots(&self) -> Vec<Pot> {
+        let uncalled = self.uncalled();
+        let contributions: Vec<(usize, u64, bool)> = self.players.iter().map(|(seat, (amount, folded))| {
+            let returned = uncalled.filter(|(s, _)| s == seat).map_or(0, |(_, amount)| amount);
+            (*seat, amount - returned, *folded)
+        }).collect();
+
+        let mut live: Vec<usize> = contributions.iter().filter(|(_, _, folded)| !folded).map(|(seat, _, _)| *seat).collect();
+        live.sort_by_key(|seat| self.position(*seat));
+        let mut levels: Vec<u64> = contributions.iter().filter(|(_, _, folded)| !folded).map(|(_, amount, _)| *amount).collect();
+        levels.sort_unstable();
+        levels.dedup();
+        if levels.is_empty() {
+            levels.push(0);
+        }
+
+        // a pot for each all-in level of the players still in the hand, the last one taking everything above it
+        let mut pots = Vec::new();
+        let mut previous = 0;
+        for (i, level) in levels.iter().enumerate() {
+            let cap = if i + 1 == levels.len() { u64::MAX } else { *level };
+            let amount = contributions.iter().map(|(_, c, _)| (*c).min(cap) - (*c).min(previous)).sum();
+            let eligible = live.iter().cloned().filter(|seat| contributions.iter().any(|(s, c, _)| s == seat && c >= level)).collect();
+            previous = *level;
+            if amount > 0 {
+                pots.push(Pot { amount, eligible });
+            }
+        }
+        pots
+    }
+
+    /// Award the pots to the best high hands, and the low halves to the best low hands (empty unless the game is split
+    /// between high and low). Players still in the hand without a hand rank (e.g. because they mucked) do not win
+    /// anything, unless no eligible player has a rank
+    pub fn settle(&self, high: &[(usize, HandRank)], low: &[(usize, HandRank)]) -> Settlement {
+        let mut awards = Vec::new();
+        let pots = self.pots();
+        for (pot, p) in pots.iter().enumerate() {
+            let high_winners = best(p.eligible(), high);
+            let low_winners = best(p.eligible(), low);
+            let high_winners = if high_winners.is_empty() { p.eligible().to_vec() } else { high_winners };
+
+            if low_winners.is_empty() {
+                split(&mut awards, pot, p.amount, &high_winners, Share::Whole);
+            } else {
+                split(&mut awards, pot, p.amount - p.amount / 2, &high_winners, Share::High);
+                split(&mut awards, pot, p.amount / 2, &low_winners, Share::Low);
+            }
+        }
+
+        Settlement {
+            uncalled: self.uncalled(),
+            pots,
+            awards,
+        }
+    }
+
+    /// The position of the seat clockwise from the left of the button
+    fn position(&self, seat: usize) -> usize {
+        (seat % self.seats + self.seats - self.button % self.seats - 1) % self.seats
+    }
+}
+
+/// The seats with the greatest hand rank among the eligible seats (in their order)
+fn best(eligible: &[usize], ranks: &[(usize, HandRank)]) -> Vec<usize> {
+    let rank = |seat: &usize| ranks.iter().find(|(s, _)| s == seat).map(|(_, rank)| *rank);
+    let top = eligible.iter().filter_map(rank).max();
+    eligible.iter().cloned().filter(|seat| top.is_some() && rank(seat) == top).collect()
+}
+
+/// Split the chips evenly among the winners, giving the odd chips to the first winners
+fn split(awards: &mut Vec<Award>, pot: usize, amount: u64, winners: &[usize], share: Share) {
+    if winners.is_empty() {
+        return;
+    }
+    let each = amount / winners.len() as u64;
+    let odd = (amount % winners.len() as u64) as usize;
+    for (i, seat) in winners.iter().enumerate() {
+        awards.push(Award {
+            pot,
+            seat: *seat,
+            amount: each + u64::from(i < odd),
+            share,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PotManager, Share};
+    use crate::cards::cards;
+    use crate::poker::{HandRank, PokerEvaluator};
+    use crate::poker_variants::{HandEvaluator, OmahaHiLoEvaluator};
+
+    fn rank(hand: &str) -> HandRank {
+        PokerEvaluator::evaluate(&cards(hand)).unwrap()
+    }
+
+    #[test]
+    fn side_pots() {
+        let mut pots = PotManager::new(6, 0);
+        pots.contribute(1, 50);
+        pots.contribute(2, 200);
+        pots.contribute(3, 500);
+        pots.contribute(4, 120);
+        pots.contribute(5, 800);
+        pots.fold(4);
+
+        assert_eq!(pots.total(), 1670);
+        assert_eq!(pots.uncalled(), Some((5, 300)));
+        let amounts: Vec<u64> = pots.pots().iter().map(|p| p.amount()).collect();
+        assert_eq!(amounts, vec![250, 150 + 70 + 150 + 150, 600]);
+        assert_eq!(pots.pots()[1].eligible(), &[2, 3, 5]);
+        assert_eq!(pots.pots()[2].eligible(), &[3, 5]);
+
+        // the short stack has the best hand, then seat 5, then seat 3
+        let high = [
+            (1, rank("As Ad Ac 2h 3h")),
+            (2, rank("Ks Kd 7c 2s 3d")),
+            (3, rank("Qs Qd 7c 2s 3d")),
+            (5, rank("Ks Kd Kc 2d 3c")),
+        ];
+        let settlement = pots.settle(&high, &[]);
+        assert_eq!(settlement.total(1), 250);
+        assert_eq!(settlement.total(5), 520 + 600 + 300);
+        assert_eq!(settlement.total(2) + settlement.total(3) + settlement.total(4), 0);
+        assert_eq!(settlement.awards().iter().map(|a| a.amount).sum::<u64>() + 300, pots.total());
+    }
+
+    #[test]
+    fn odd_chips_by_position() {
+        // the button is seat 3, so seat 1 is before seat 2 but after seat 5
+        let mut pots = PotManager::new(6, 3);
+        for seat in [1, 2, 5].iter() {
+            pots.contribute(*seat, 33);
+        }
+        pots.contribute(4, 2);
+        pots.fold(4);
+
+        let high: Vec<(usize, HandRank)> = [1, 2, 5].iter().map(|s| (*s, rank("As Ks Qs Js Ts"))).collect();
+        let settlement = pots.settle(&high, &[]);
+        let awards: Vec<(usize, u64)> = settlement.awards().iter().map(|a| (a.seat, a.amount)).collect();
+        assert_eq!(awards, vec![(5, 34), (1, 34), (2, 33)]);
+    }
+
+    #[test]
+    fn hi_lo_split() {
+        let board = cards("2c 4d 7h Ks Kd");
+        let hands = [(0, cards("Ac 3c Qs Qh")), (1, cards("As 3d 8c 9c")), (2, cards("Kh Jh Td 9s"))];
+        let high: Vec<(usize, HandRank)> = hands.iter().filter_map(|(s, h)| Some((*s, OmahaHiLoEvaluator.evaluate(h, &board)?))).collect();
+        let low: Vec<(usize, HandRank)> = hands.iter().filter_map(|(s, h)| Some((*s, OmahaHiLoEvaluator.evaluate_low(h, &board)?))).collect();
+        assert_eq!(low.len(), 2);
+
+        let mut pots = PotManager::new(9, 2);
+        for seat in 0..3 {
+            pots.contribute(seat, 101);
+        }
+
+        // seat 2 scoops the high half with the odd chip, and the two 7-4-3-2-A lows quarter the pot
+        let settlement = pots.settle(&high, &low);
+        assert_eq!(settlement.total(2), 152);
+        assert_eq!(settlement.total(0), 76);
+        assert_eq!(settlement.total(1), 75);
+        assert!(settlement.awards().iter().filter(|a| a.seat != 2).all(|a| a.share == Share::Low));
+
+        // without a qualifying low the high hand scoops
+        let settlement = pots.settle(&high, &[]);
+        assert_eq!(settlement.total(2), 303);
+        assert_eq!(settlement.awards()[0].share, Share::Whole);
+    }
+
+    #[test]
+    fn everyone_folds() {
+        let mut pots = PotManager::new(2, 0);
+        pots.contribute(0, 5);
+        pots.contribute(1, 10);
+        pots.fold(0);
+
+        let settlement = pots.settle(&[], &[]);
+        assert_eq!(settlement.uncalled(), Some((1, 5)));
+        assert_eq!(settlement.pots().len(), 1);
+        assert_eq!(settlement.total(1), 15);
+    }
+}
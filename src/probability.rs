@@ -0,0 +1,174 @@
+use crate::card_type::CardType;
+
+/// Calculates exact odds of drawing cards without replacement (the hypergeometric distribution), based on the
+/// composition of the cards that remain rather than an infinite-deck approximation.
+///
+/// The odds are described by the number of cards remaining (the population) and how many of those match some
+/// condition (the successes), e.g. the cards that would bust a blackjack hand.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DrawOdds {
+    population: usize,
+    successes: usize,
+}
+
+impl DrawOdds {
+    /// Describe a population of cards of which ```successes``` match. ```successes``` is capped to ```population```
+    pub fn new(population: usize, successes: usize) -> Self {
+        DrawOdds {
+            population,
+            successes: successes.min(population),
+        }
+    }
+
+    /// Count the cards (e.g. of a ```CardSet```, or ```Deck::iter().copied()```) that match a predicate
+    pub fn from_cards<C, I, P>(cards: I, predicate: P) -> Self
+    where
+        C: CardType,
+        I: IntoIterator<Item = C>,
+        P: Fn(&C) -> bool,
+    {
+        let mut population = 0;
+        let mut successes = 0;
+        for card in cards {
+            population += 1;
+            if predicate(&card) {
+                successes += 1;
+            }
+        }
+        DrawOdds::new(population, successes)
+    }
+
+    /// Get the number of cards remaining
+    pub fn population(&self) -> usize {
+        self.population
+    }
+
+    /// Get the number of remaining cards that match
+    pub fn successes(&self) -> usize {
+        self.successes
+    }
+
+    /// The probability of drawing exactly ```k``` matching cards in ```draws``` draws
+    pub fn exactly(&self, draws: usize, k: usize) -> f64 {
+        let (n, s) = (self.population, self.successes);
+        if draws > n || k > draws || k > s || draws - k > n - s {
+            return 0.0;
+        }
+        (ln_choose(s, k) + ln_choose(n - s, draws - k) - ln_choose(n, draws)).exp()
+    }
+
+    /// The probability of drawing at least ```k``` matching cards in ```draws``` draws. Like every other probability,
+    /// this is 0 if there are fewer cards remaining than draws
+    pub fn at_least(&self, draws: usize, k: usize) -> f64 {
+        if draws > self.population {
+            return 0.0;
+        }
+        if k == 0 {
+            return 1.0;
+        }
+        (1.0 - self.at_most(draws, k - 1)).clamp(0.0, 1.0)
+    }
+
+    /// The probability of drawing at most ```k``` matching cards in ```draws``` draws
+    pub fn at_most(&self, draws: usize, k: usize) -> f64 {
+        (0..=k.min(draws)).map(|i| self.exactly(draws, i)).sum::<f64>().min(1.0)
+    }
+
+    /// The probability of drawing each possible number of matching cards (0 to ```draws```) in ```draws``` draws
+    pub fn distribution(&self, draws: usize) -> Vec<f64> {
+        (0..=draws).map(|k| self.exactly(draws, k)).collect()
+    }
+
+    /// The expected number of matching cards in ```draws``` draws
+    pub fn expected(&self, draws: usize) -> f64 {
+        if self.population == 0 {
+            return 0.0;
+        }
+        draws as f64 * self.successes as f64 / self.population as f64
+    }
+
+    /// Get the probability distribution of the next card drawn, grouped by an outcome derived from each card (e.g. its
+    /// blackjack value). Outcomes are listed in the order they are first encountered
+    pub fn next_card<C, I, K, F>(cards: I, outcome: F) -> Vec<(K, f64)>
+    where
+        C: CardType,
+        I: IntoIterator<Item = C>,
+        K: PartialEq,
+        F: Fn(&C) -> K,
+    {
+        let mut counts: Vec<(K, usize)> = Vec::new();
+        let mut total = 0;
+
+        for card in cards {
+            let key = outcome(&card);
+            match counts.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((key, 1)),
+            }
+            total += 1;
+        }
+
+        counts.into_iter().map(|(key, count)| (key, count as f64 / total as f64)).collect()
+    }
+}
+
+/// The natural logarithm of the binomial coefficient ```n choose k```
+fn ln_choose(n: usize, k: usize) -> f64 {
+    if k > n {
+        return f64::NEG_INFINITY;
+    }
+    let k = k.min(n - k);
+    (0..k).map(|i| ((n - i) as f64).ln() - ((i + 1) as f64).ln()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DrawOdds;
+    use crate::card_set::CardSet;
+    use crate::cards::Rank;
+    use crate::deck::Deck;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-12
+    }
+
+    #[test]
+    fn hypergeometric() {
+        let aces = DrawOdds::from_cards(Deck::new().iter().copied(), |c| *c.rank() == Rank::Ace);
+
+        assert_eq!(aces, DrawOdds::new(52, 4));
+        assert!(close(aces.at_least(2, 1), 1.0 - 1128.0 / 1326.0));
+        assert!(close(aces.exactly(2, 2), 6.0 / 1326.0));
+        assert!(close(aces.distribution(5).iter().sum::<f64>(), 1.0));
+        assert!(close(aces.expected(13), 1.0));
+        assert_eq!(aces.exactly(5, 5), 0.0);
+        assert_eq!(aces.at_least(53, 0), 0.0);
+        assert_eq!(aces.at_least(5, 0), 1.0);
+        for k in 0..=5 {
+            assert_eq!(aces.at_least(53, k), 0.0);
+            assert_eq!(aces.at_most(53, k), 0.0);
+            assert_eq!(aces.exactly(53, k), 0.0);
+        }
+    }
+
+    #[test]
+    fn remaining_cards() {
+        let mut remaining = CardSet::full();
+        for card in Deck::new().iter().filter(|c| *c.rank() >= Rank::Ten) {
+            remaining.remove(card);
+        }
+        let bust = DrawOdds::from_cards(remaining, |c| *c.rank() >= Rank::Six);
+
+        assert!(close(bust.at_least(1, 1), 16.0 / 36.0));
+    }
+
+    #[test]
+    fn next_card_distribution() {
+        let deck = Deck::new();
+        let outcomes = DrawOdds::next_card(deck.iter().copied(), |c| (c.rank().to_ordinal() + 1).min(10));
+
+        assert_eq!(outcomes.len(), 10);
+        assert_eq!(outcomes[0], (1, 4.0 / 52.0));
+        assert_eq!(outcomes[9], (10, 16.0 / 52.0));
+    }
+}
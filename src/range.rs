@@ -0,0 +1,263 @@
+use crate::card_set::CardSet;
+use crate::cards::{Card, Rank, Suit};
+use crate::poker::poker_value;
+
+/// The error returned when a range string cannot be parsed, holding the offending part of the range
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseRangeError {
+    token: String,
+}
+
+impl std::fmt::Display for ParseRangeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid range '{}'", self.token)
+    }
+}
+
+impl std::error::Error for ParseRangeError {}
+
+/// A range of Hold'em starting hands, i.e. a set of two card combos with a weight (the fraction of the time the combo
+/// is played, from 0 to 1) each.
+///
+/// Ranges are parsed from the standard comma separated notation:
+///
+/// * ```AA```, ```AKs```, ```AKo``` and ```AK``` for a pair, the suited or offsuit combos of two ranks, or both
+/// * ```TT+``` for TT and every higher pair, and ```A2s+``` or ```KTo+``` to raise the second card up to the first
+/// * ```TT-77``` and ```A5s-A2s``` for the hands in between
+/// * ```KhQh``` for a specific combo
+/// * a ```:weight``` suffix (e.g. ```AKo:0.5```) to play the hands part of the time
+///
+/// A combo listed more than once takes the weight of its last occurrence.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Range {
+    // sorted by the bits of the combos
+    combos: Vec<(CardSet, f64)>,
+}
+
+impl Range {
+    /// Create an empty range
+    pub fn new() -> Self {
+        Range::default()
+    }
+
+    /// Parse a range from the standard notation
+    pub fn parse(range: &str) -> Result<Self, ParseRangeError> {
+        let mut combos = std::collections::BTreeMap::new();
+        for token in range.split(',').map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            let error = || ParseRangeError { token: token.to_string() };
+            let (hands, weight) = match token.split_once(':') {
+                Some((hands, weight)) => (hands.trim(), weight.trim().parse::<f64>().map_err(|_| error())?),
+                None => (token, 1.0),
+            };
+            if !(0.0..=1.0).contains(&weight) {
+                return Err(error());
+            }
+            for combo in expand(hands).ok_or_else(error)? {
+                combos.insert(combo.bits(), weight);
+            }
+        }
+        Ok(Range {
+            combos: combos.into_iter().map(|(bits, weight)| (CardSet::from_bits(bits), weight)).collect(),
+        })
+    }
+
+    /// Add a combo to the range, replacing its weight if it is already in the range
+    pub fn add(&mut self, combo: [Card; 2], weight: f64) {
+        let set: CardSet = combo.iter().collect();
+        match self.combos.binary_search_by_key(&set.bits(), |(c, _)| c.bits()) {
+            Ok(i) => self.combos[i].1 = weight,
+            Err(i) => self.combos.insert(i, (set, weight)),
+        }
+    }
+
+    /// Iterate over the combos in the range and their weights
+    pub fn combos(&self) -> impl Iterator<Item = ([Card; 2], f64)> + '_ {
+        self.combos.iter().map(|(set, weight)| {
+            let mut cards = set.iter();
+            ([cards.next().unwrap_or_default(), cards.next().unwrap_or_default()], *weight)
+        })
+    }
+
+    /// Get the weight of a combo, or 0 if it is not in the range
+    pub fn weight(&self, combo: [Card; 2]) -> f64 {
+        let set: CardSet = combo.iter().collect();
+        self.combos.iter().find(|(c, _)| *c == set).map_or(0.0, |(_, w)| *w)
+    }
+
+    /// Remove the combos that hold any of the known cards (e.g. the board or the hero's hole cards)
+    pub fn without(&self, known: &CardSet) -> Range {
+        Range {
+            combos: self.combos.iter().filter(|(c, _)| c.is_disjoint(known)).cloned().collect(),
+        }
+    }
+
+    /// Get the number of combos in the range
+    pub fn len(&self) -> usize {
+        self.combos.len()
+    }
+
+    /// Returns true if the range holds no combos
+    pub fn is_empty(&self) -> bool {
+        self.combos.is_empty()
+    }
+
+    /// Get the number of combos in the range, counted by their weights
+    pub fn total_weight(&self) -> f64 {
+        self.combos.iter().map(|(_, w)| w).sum()
+    }
+
+    pub(crate) fn sets(&self) -> &[(CardSet, f64)] {
+        &self.combos
+    }
+}
+
+impl std::str::FromStr for Range {
+    type Err = ParseRangeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Range::parse(s)
+    }
+}
+
+/// The shape of a group of starting hands, e.g. "AKs". ```high``` and ```low``` are poker values (aces high)
+#[derive(Copy, Clone, PartialEq)]
+struct HandClass {
+    high: u8,
+    low: u8,
+    suited: Option<bool>,
+}
+
+impl HandClass {
+    fn parse(class: &str) -> Option<Self> {
+        let chars: Vec<char> = class.chars().collect();
+        if chars.len() < 2 || chars.len() > 3 {
+            return None;
+        }
+        let a = poker_value(&Rank::from_char(chars[0])?);
+        let b = poker_value(&Rank::from_char(chars[1])?);
+        let suited = match chars.get(2).map(|c| c.to_ascii_lowercase()) {
+            None => None,
+            Some('s') if a != b => Some(true),
+            Some('o') if a != b => Some(false),
+            _ => return None,
+        };
+        Some(HandClass { high: a.max(b), low: a.min(b), suited })
+    }
+
+    fn is_pair(&self) -> bool {
+        self.high == self.low
+    }
+
+    fn combos(&self) -> Vec<CardSet> {
+        let mut combos = Vec::new();
+        for s1 in 0..4 {
+            for s2 in 0..4 {
+                let valid = match (self.is_pair(), self.suited) {
+                    (true, _) => s1 < s2,
+                    (false, None) => true,
+                    (false, Some(suited)) => (s1 == s2) == suited,
+                };
+                if valid {
+                    combos.push([card(self.high, s1), card(self.low, s2)].iter().collect());
+                }
+            }
+        }
+        combos
+    }
+}
+
+fn card(value: u8, suit: u8) -> Card {
+    Card::from_suit_and_rank(Suit::from_ordinal(suit), Rank::from_ordinal(if value == 14 { 0 } else { value - 1 }))
+}
+
+/// Expand a single entry of a range (without its weight) into its combos
+fn expand(hands: &str) -> Option<Vec<CardSet>> {
+    // a specific combo, e.g. KhQh
+    if hands.len() == 4 && hands.is_char_boundary(2) {
+        if let (Ok(a), Ok(b)) = (hands[..2].parse::<Card>(), hands[2..].parse::<Card>()) {
+            return if a != b && !a.is_joker() && !b.is_joker() { Some(vec![[a, b].iter().collect()]) } else { None };
+        }
+    }
+
+    let classes: Vec<HandClass> = if let Some((first, last)) = hands.split_once('-') {
+        let (first, last) = (HandClass::parse(first.trim())?, HandClass::parse(last.trim())?);
+        if first.is_pair() && last.is_pair() {
+            let (top, bottom) = (first.high.max(last.high), first.high.min(last.high));
+            (bottom..=top).map(|v| HandClass { high: v, low: v, suited: None }).collect()
+        } else if !first.is_pair() && !last.is_pair() && first.high == last.high && first.suited == last.suited {
+            let (top, bottom) = (first.low.max(last.low), first.low.min(last.low));
+            (bottom..=top).map(|v| HandClass { low: v, ..first }).collect()
+        } else {
+            return None;
+        }
+    } else if let Some(base) = hands.strip_suffix('+') {
+        let base = HandClass::parse(base)?;
+        if base.is_pair() {
+            (base.high..=14).map(|v| HandClass { high: v, low: v, suited: None }).collect()
+        } else {
+            (base.low..base.high).map(|v| HandClass { low: v, ..base }).collect()
+        }
+    } else {
+        vec![HandClass::parse(hands)?]
+    };
+
+    Some(classes.iter().flat_map(|c| c.combos()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Range;
+    use crate::card_set::CardSet;
+    use crate::cards::Card;
+
+    fn card(card: &str) -> Card {
+        card.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_notation() {
+        assert_eq!(Range::parse("AA").unwrap().len(), 6);
+        assert_eq!(Range::parse("AKs").unwrap().len(), 4);
+        assert_eq!(Range::parse("AKo").unwrap().len(), 12);
+        assert_eq!(Range::parse("KA").unwrap().len(), 16);
+        assert_eq!(Range::parse("TT+").unwrap().len(), 30);
+        assert_eq!(Range::parse("A2s+").unwrap().len(), 48);
+        assert_eq!(Range::parse("KTo+").unwrap().len(), 36);
+        assert_eq!(Range::parse("TT-77").unwrap().len(), 24);
+        assert_eq!(Range::parse("A5s-A2s").unwrap().len(), 16);
+        assert_eq!(Range::parse("KhQh").unwrap().len(), 1);
+
+        let range: Range = "AKs, TT+, A5s-A2s, 72o, KhQh".parse().unwrap();
+        assert_eq!(range.len(), 4 + 30 + 16 + 12 + 1);
+        assert_eq!(range.weight([card("Kh"), card("Qh")]), 1.0);
+        assert_eq!(range.weight([card("Kh"), card("Qs")]), 0.0);
+        assert_eq!(range.weight([card("2c"), card("7d")]), 1.0);
+    }
+
+    #[test]
+    fn weights() {
+        let range = Range::parse("AKo:0.5, AK, QQ:0.25").unwrap();
+
+        assert_eq!(range.len(), 22);
+        assert_eq!(range.weight([card("Ac"), card("Kd")]), 1.0);
+        assert_eq!(range.total_weight(), 16.0 + 1.5);
+    }
+
+    #[test]
+    fn card_removal() {
+        let known: CardSet = [card("Ah"), card("Kd")].iter().collect();
+        let range = Range::parse("AA, AK").unwrap().without(&known);
+
+        assert_eq!(range.len(), 3 + 9);
+        assert!(range.combos().all(|(c, _)| !known.contains(&c[0]) && !known.contains(&c[1])));
+    }
+
+    #[test]
+    fn invalid_ranges() {
+        for invalid in ["AAs", "A", "AKx", "TT-AKs", "AKs-QJs", "AhAh", "AK:2", "AK:x", "ZZ"].iter() {
+            assert!(Range::parse(invalid).is_err(), "{}", invalid);
+        }
+        assert_eq!(Range::parse("AKs, 1Q").unwrap_err().to_string(), "invalid range '1Q'");
+        assert!(Range::parse("").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,299 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+use crate::card_type::CardType;
+use crate::deck::Deck;
+
+/// The number of trials run with the same random number generator. Trials are split into batches so that every batch
+/// can be seeded from the master seed independently of the thread that runs it.
+const BATCH_SIZE: usize = 1024;
+
+/// Implemented by simulation states that can shuffle their remaining cards before each trial
+pub trait Shuffle {
+    /// Shuffle the remaining cards using the supplied random number generator
+    fn shuffle_with<R: Rng + ?Sized>(&mut self, rng: &mut R);
+}
+
+impl<C: CardType> Shuffle for Deck<C> {
+    fn shuffle_with<R: Rng + ?Sized>(&mut self, rng: &mut R) {
+        Deck::shuffle_with(self, rng);
+    }
+}
+
+/// Running statistics (count, mean and variance) of the results of a simulation
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct Estimate {
+    trials: u64,
+    mean: f64,
+    m2: f64,
+}
+
+impl Estimate {
+    /// Create an estimate without any results
+    pub fn new() -> Self {
+        Estimate::default()
+    }
+
+    /// Add the result of a trial
+    pub fn add(&mut self, value: f64) {
+        self.trials += 1;
+        let delta = value - self.mean;
+        self.mean += delta / self.trials as f64;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    /// Combine the results of another estimate into this one
+    pub fn merge(&mut self, other: &Estimate) {
+        if other.trials == 0 {
+            return;
+        }
+        let trials = self.trials + other.trials;
+        let delta = other.mean - self.mean;
+
+        self.mean += delta * other.trials as f64 / trials as f64;
+        self.m2 += other.m2 + delta * delta * self.trials as f64 * other.trials as f64 / trials as f64;
+        self.trials = trials;
+    }
+
+    /// Get the number of trials
+    pub fn trials(&self) -> u64 {
+        self.trials
+    }
+
+    /// Get the mean result
+    pub fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// Get the sample variance of the results
+    pub fn variance(&self) -> f64 {
+        if self.trials < 2 {
+            return 0.0;
+        }
+        self.m2 / (self.trials - 1) as f64
+    }
+
+    /// Get the sample standard deviation of the results
+    pub fn std_dev(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    /// Get the standard error of the mean
+    pub fn std_error(&self) -> f64 {
+        if self.trials == 0 {
+            return 0.0;
+        }
+        self.std_dev() / (self.trials as f64).sqrt()
+    }
+
+    /// Get the confidence interval of the mean at the given confidence level (e.g. 0.95), using the normal approximation
+    pub fn confidence_interval(&self, level: f64) -> (f64, f64) {
+        let z = inverse_normal(0.5 + level.clamp(0.0, 0.999_999) / 2.0);
+        let margin = z * self.std_error();
+        (self.mean - margin, self.mean + margin)
+    }
+}
+
+/// A seeded Monte Carlo runner. Each trial clones the starting state, shuffles it and plays it out with a user supplied
+/// closure, which returns the result of the trial (e.g. 1.0 for a win and 0.0 for a loss, or the amount won).
+///
+/// Results are reproducible from the master seed, and do not depend on the number of threads used. With the ```rayon```
+/// feature enabled, ```par_run()``` runs the trials on the rayon thread pool and produces the same results as ```run()```.
+#[derive(Debug, Copy, Clone)]
+pub struct MonteCarlo {
+    trials: usize,
+    seed: u64,
+    threads: usize,
+}
+
+impl MonteCarlo {
+    /// Create a runner for the given number of trials, using seed 0 and a single thread
+    pub fn new(trials: usize) -> Self {
+        MonteCarlo {
+            trials,
+            seed: 0,
+            threads: 1,
+        }
+    }
+
+    /// Set the master seed
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Set the number of threads to run the trials on
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Run the simulation. The closure receives a shuffled copy of ```state``` and the random number generator of the
+    /// trial, to make any further random decisions reproducible as well
+    pub fn run<S, F>(&self, state: &S, trial: F) -> Estimate
+    where
+        S: Shuffle + Clone + Sync,
+        F: Fn(&mut S, &mut StdRng) -> f64 + Sync,
+    {
+        let batches = self.batches();
+        let next = AtomicUsize::new(0);
+        let worker = || {
+            let mut results = Vec::new();
+            loop {
+                let batch = next.fetch_add(1, Ordering::Relaxed);
+                if batch >= batches {
+                    return results;
+                }
+                results.push((batch, self.run_batch(batch, state, &trial)));
+            }
+        };
+
+        let mut results: Vec<(usize, Estimate)> = if self.threads == 1 {
+            worker()
+        } else {
+            std::thread::scope(|scope| {
+                let handles: Vec<_> = (0..self.threads).map(|_| scope.spawn(worker)).collect();
+                handles.into_iter().flat_map(|h| h.join().expect("simulation thread panicked")).collect()
+            })
+        };
+
+        // merge in batch order, so that rounding does not depend on thread scheduling
+        results.sort_by_key(|(batch, _)| *batch);
+        let mut estimate = Estimate::new();
+        for (_, batch) in results.iter() {
+            estimate.merge(batch);
+        }
+        estimate
+    }
+
+    /// Run the simulation on the rayon thread pool. The results are identical to those of ```run()```
+    #[cfg(feature = "rayon")]
+    pub fn par_run<S, F>(&self, state: &S, trial: F) -> Estimate
+    where
+        S: Shuffle + Clone + Sync,
+        F: Fn(&mut S, &mut StdRng) -> f64 + Sync,
+    {
+        use rayon::prelude::*;
+
+        let results: Vec<Estimate> = (0..self.batches())
+            .into_par_iter()
+            .map(|batch| self.run_batch(batch, state, &trial))
+            .collect();
+
+        let mut estimate = Estimate::new();
+        for batch in results.iter() {
+            estimate.merge(batch);
+        }
+        estimate
+    }
+
+    fn batches(&self) -> usize {
+        self.trials.div_ceil(BATCH_SIZE)
+    }
+
+    fn run_batch<S, F>(&self, batch: usize, state: &S, trial: &F) -> Estimate
+    where
+        S: Shuffle + Clone,
+        F: Fn(&mut S, &mut StdRng) -> f64,
+    {
+        let mut rng = StdRng::seed_from_u64(derive_seed(self.seed, batch as u64));
+        let mut estimate = Estimate::new();
+        let trials = BATCH_SIZE.min(self.trials - batch * BATCH_SIZE);
+
+        for _ in 0..trials {
+            let mut state = state.clone();
+            state.shuffle_with(&mut rng);
+            estimate.add(trial(&mut state, &mut rng));
+        }
+        estimate
+    }
+}
+
+/// Derive an independent seed for a stream of work (e.g. a thread, a batch of trials or a table) from a master seed,
+/// using the SplitMix64 finalizer. The same master seed and stream always produce the same seed
+pub fn derive_seed(seed: u64, stream: u64) -> u64 {
+    let mut z = seed.wrapping_add(stream.wrapping_add(1).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// The inverse of the standard normal cumulative distribution function (Acklam's approximation)
+fn inverse_normal(p: f64) -> f64 {
+    const A: [f64; 6] = [-3.969683028665376e+01, 2.209460984245205e+02, -2.759285104469687e+02, 1.38357751867269e+02, -3.066479806614716e+01, 2.506628277459239e+00];
+    const B: [f64; 5] = [-5.447609879822406e+01, 1.615858368580409e+02, -1.556989798598866e+02, 6.680131188771972e+01, -1.328068155288572e+01];
+    const C: [f64; 6] = [-7.784894002430293e-03, -3.223964580411365e-01, -2.400758277161838e+00, -2.549732539343734e+00, 4.374664141464968e+00, 2.938163982698783e+00];
+    const D: [f64; 4] = [7.784695709041462e-03, 3.224671290700398e-01, 2.445134137142996e+00, 3.754408661907416e+00];
+    const LOW: f64 = 0.02425;
+
+    if p <= LOW {
+        let q = (-2.0 * p.ln()).sqrt();
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5]) / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    } else if p < 1.0 - LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -inverse_normal(1.0 - p)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{derive_seed, inverse_normal, Estimate, MonteCarlo};
+    use crate::cards::Rank;
+    use crate::deck::Deck;
+
+    #[test]
+    fn estimate_statistics() {
+        let mut a = Estimate::new();
+        let mut b = Estimate::new();
+        let mut all = Estimate::new();
+        for (i, x) in [1.0, 2.0, 3.0, 4.0, 10.0].iter().enumerate() {
+            if i < 2 { a.add(*x) } else { b.add(*x) }
+            all.add(*x);
+        }
+        a.merge(&b);
+
+        assert_eq!(a.trials(), 5);
+        assert!((a.mean() - 4.0).abs() < 1e-12);
+        assert!((a.variance() - all.variance()).abs() < 1e-12);
+        assert!((all.variance() - 12.5).abs() < 1e-12);
+        assert!((inverse_normal(0.975) - 1.959964).abs() < 1e-6);
+    }
+
+    #[test]
+    fn seeded_and_thread_independent() {
+        let deck = Deck::new();
+        let top_is_ace = |deck: &mut Deck, _: &mut _| if *deck.draw().unwrap().rank() == Rank::Ace { 1.0 } else { 0.0 };
+
+        let single = MonteCarlo::new(5_000).seed(9).run(&deck, top_is_ace);
+        let threaded = MonteCarlo::new(5_000).seed(9).threads(4).run(&deck, top_is_ace);
+        let other = MonteCarlo::new(5_000).seed(10).run(&deck, top_is_ace);
+
+        assert_eq!(single, threaded);
+        assert_ne!(single, other);
+        assert_eq!(single.trials(), 5_000);
+
+        let (low, high) = single.confidence_interval(0.999);
+        assert!(low < 1.0 / 13.0 && 1.0 / 13.0 < high);
+    }
+
+    #[test]
+    fn derived_seeds() {
+        assert_eq!(derive_seed(1, 2), derive_seed(1, 2));
+        assert_ne!(derive_seed(1, 2), derive_seed(1, 3));
+        assert_ne!(derive_seed(1, 2), derive_seed(2, 2));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_run_matches_sequential() {
+        let deck = Deck::new();
+        let top_is_ace = |deck: &mut Deck, _: &mut _| if *deck.draw().unwrap().rank() == Rank::Ace { 1.0 } else { 0.0 };
+
+        let sequential = MonteCarlo::new(3_000).seed(5).run(&deck, top_is_ace);
+        let parallel = MonteCarlo::new(3_000).seed(5).par_run(&deck, top_is_ace);
+        assert_eq!(sequential, parallel);
+    }
+}
@@ -0,0 +1,845 @@
+use rand::Rng;
+use crate::cards::Card;
+use crate::deck::Deck;
+use crate::poker::{HandRank, PokerEvaluator};
+use crate::pots::PotManager;
+
+/// The maximum number of seats at a table
+pub const MAX_SEATS: usize = 10;
+
+/// The number of bets allowed on each street of a fixed limit game (a bet and three raises)
+const FIXED_LIMIT_CAP: u32 = 4;
+
+/// The betting structure of a Hold'em game
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BettingStructure {
+    /// Bets and raises up to the player's whole stack
+    NoLimit,
+    /// Bets and raises up to the size of the pot
+    PotLimit,
+    /// Bets and raises of a fixed size: the big blind preflop and on the flop, twice that on the turn and river
+    FixedLimit,
+}
+
+/// The stakes and betting structure of a table
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TableConfig {
+    pub structure: BettingStructure,
+    pub small_blind: u64,
+    pub big_blind: u64,
+    pub ante: u64,
+}
+
+impl TableConfig {
+    /// Create the config of a no limit game with the given blinds
+    pub fn no_limit(small_blind: u64, big_blind: u64) -> Self {
+        TableConfig {
+            structure: BettingStructure::NoLimit,
+            small_blind,
+            big_blind,
+            ante: 0,
+        }
+    }
+
+    /// Create the config of a pot limit game with the given blinds
+    pub fn pot_limit(small_blind: u64, big_blind: u64) -> Self {
+        TableConfig {
+            structure: BettingStructure::PotLimit,
+            ..TableConfig::no_limit(small_blind, big_blind)
+        }
+    }
+
+    /// Create the config of a fixed limit game with the given blinds. The small bet is the big blind
+    pub fn fixed_limit(small_blind: u64, big_blind: u64) -> Self {
+        TableConfig {
+            structure: BettingStructure::FixedLimit,
+            ..TableConfig::no_limit(small_blind, big_blind)
+        }
+    }
+
+    /// Set the ante every player posts at the start of a hand
+    pub fn ante(mut self, ante: u64) -> Self {
+        self.ante = ante;
+        self
+    }
+}
+
+/// The betting rounds of a Hold'em hand
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Street {
+    Preflop,
+    Flop,
+    Turn,
+    River,
+}
+
+/// An action of the player whose turn it is. Bets are the amount put in on the street, raises are the total the
+/// player raises to on the street (e.g. ```Raise(300)``` over a bet of 100 adds 300 minus what the player already bet)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Action {
+    Fold,
+    Check,
+    Call,
+    Bet(u64),
+    Raise(u64),
+}
+
+/// The actions open to the player whose turn it is. A player who cannot cover the full amount may call, bet or raise
+/// all-in for less, so the ranges include the all-in amount.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct LegalActions {
+    /// The player may fold (only when facing a bet)
+    pub fold: bool,
+    /// The player may check
+    pub check: bool,
+    /// The chips the player adds to call, if facing a bet
+    pub call: Option<u64>,
+    /// The smallest and largest bet, if nobody has bet on the street
+    pub bet: Option<(u64, u64)>,
+    /// The smallest and largest total to raise to, if the player may raise
+    pub raise: Option<(u64, u64)>,
+}
+
+impl LegalActions {
+    /// Returns true if the action is legal
+    pub fn allows(&self, action: Action) -> bool {
+        let within = |range: Option<(u64, u64)>, amount| range.is_some_and(|(min, max)| (min..=max).contains(&amount));
+        match action {
+            Action::Fold => self.fold,
+            Action::Check => self.check,
+            Action::Call => self.call.is_some(),
+            Action::Bet(amount) => within(self.bet, amount),
+            Action::Raise(amount) => within(self.raise, amount),
+        }
+    }
+}
+
+/// The events of a hand, in the order they happened. Seats are the indices of the table's seats.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TableEvent {
+    HandStarted { button: usize },
+    Ante { seat: usize, amount: u64 },
+    SmallBlind { seat: usize, amount: u64 },
+    BigBlind { seat: usize, amount: u64 },
+    HoleCards { seat: usize, cards: [Card; 2] },
+    /// A player acted, adding ```amount``` chips
+    Action { seat: usize, action: Action, amount: u64, all_in: bool },
+    /// The cards dealt to the board at the start of a street
+    Board { street: Street, cards: Vec<Card> },
+    /// The part of a bet nobody called was returned to the player
+    UncalledBet { seat: usize, amount: u64 },
+    Showdown { seat: usize, cards: [Card; 2], rank: HandRank },
+    /// A player won chips from a pot (0 is the main pot, then the side pots)
+    Won { seat: usize, amount: u64, pot: usize },
+}
+
+/// Describes why a table operation failed
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TableError {
+    /// There is no seat with the index
+    SeatOutOfRange(usize),
+    /// A player already sits in the seat
+    SeatTaken(usize),
+    /// Nobody sits in the seat
+    SeatEmpty(usize),
+    /// The operation must wait until the current hand is over
+    HandInProgress,
+    /// No hand is being played
+    NoHandInProgress,
+    /// Fewer than two seated players have chips
+    NotEnoughPlayers,
+    /// The deck holds too few cards for the hand, or holds jokers
+    InvalidDeck,
+    /// The action is not legal for the player whose turn it is
+    IllegalAction(Action),
+    /// The big blind of the stakes is zero
+    InvalidBlinds,
+}
+
+impl std::fmt::Display for TableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableError::SeatOutOfRange(seat) => write!(f, "there is no seat {}", seat),
+            TableError::SeatTaken(seat) => write!(f, "seat {} is taken", seat),
+            TableError::SeatEmpty(seat) => write!(f, "seat {} is empty", seat),
+            TableError::HandInProgress => write!(f, "a hand is in progress"),
+            TableError::NoHandInProgress => write!(f, "no hand is in progress"),
+            TableError::NotEnoughPlayers => write!(f, "a hand needs at least two players with chips"),
+            TableError::InvalidDeck => write!(f, "the deck cannot be used for a hand of Hold'em"),
+            TableError::IllegalAction(action) => write!(f, "{:?} is not a legal action", action),
+            TableError::InvalidBlinds => write!(f, "the big blind must be positive"),
+        }
+    }
+}
+
+impl std::error::Error for TableError {}
+
+#[derive(Debug, Clone)]
+struct Seat {
+    name: String,
+    stack: u64,
+}
+
+/// A player dealt into the current hand
+#[derive(Debug, Clone)]
+struct Player {
+    seat: usize,
+    hole: [Card; 2],
+    stack: u64,
+    /// The chips put in on the current street
+    street_bet: u64,
+    folded: bool,
+    all_in: bool,
+    /// The player acted since the last full bet or raise, so a short all-in raise does not let them raise again
+    acted: bool,
+}
+
+/// The state of a hand. Players are held in dealing order, starting left of the button, so the button is last.
+#[derive(Debug, Clone)]
+struct Hand {
+    config: TableConfig,
+    deck: Deck,
+    players: Vec<Player>,
+    pots: PotManager,
+    board: Vec<Card>,
+    street: Street,
+    /// The highest bet on the current street
+    current_bet: u64,
+    /// The size of the last full bet or raise on the street, which the next raise must at least match
+    min_raise: u64,
+    /// The number of bets and raises on the street (the big blind counts as the first bet preflop)
+    bets: u32,
+    to_act: Option<usize>,
+    finished: bool,
+    events: Vec<TableEvent>,
+}
+
+impl Hand {
+    fn pot(&self) -> u64 {
+        self.pots.total()
+    }
+
+    /// The size of a bet in a fixed limit game, and the minimum bet otherwise
+    fn bet_size(&self) -> u64 {
+        match (self.config.structure, self.street) {
+            (BettingStructure::FixedLimit, Street::Turn | Street::River) => 2 * self.config.big_blind,
+            _ => self.config.big_blind,
+        }
+    }
+
+    /// Move up to ```amount``` chips of the player into the pot, returning the chips moved
+    fn put(&mut self, player: usize, amount: u64, street_bet: bool) -> u64 {
+        let p = &mut self.players[player];
+        let amount = amount.min(p.stack);
+        p.stack -= amount;
+        if street_bet {
+            p.street_bet += amount;
+        }
+        p.all_in = p.stack == 0;
+        self.pots.contribute(p.seat, amount);
+        amount
+    }
+
+    fn deal(&mut self, count: usize) -> Vec<Card> {
+        (0..count).filter_map(|_| self.deck.draw()).collect()
+    }
+
+    fn legal_actions(&self, player: usize) -> LegalActions {
+        let p = &self.players[player];
+        let to_call = self.current_bet.saturating_sub(p.street_bet);
+        let mut legal = LegalActions {
+            fold: to_call > 0,
+            check: to_call == 0,
+            call: (to_call > 0).then(|| to_call.min(p.stack)),
+            bet: None,
+            raise: None,
+        };
+
+        // betting is closed to players who only face a short all-in since they acted, and pointless if nobody is left
+        // to call
+        let others_can_act = self.players.iter().enumerate().any(|(i, q)| i != player && !q.folded && !q.all_in);
+        let capped = self.config.structure == BettingStructure::FixedLimit && self.bets >= FIXED_LIMIT_CAP;
+        if others_can_act && p.stack > to_call && !p.acted && !capped {
+            let all_in = p.street_bet + p.stack;
+            let min = self.current_bet + self.min_raise.max(self.bet_size());
+            let max = match self.config.structure {
+                BettingStructure::NoLimit => all_in,
+                BettingStructure::PotLimit => self.current_bet + self.pot() + to_call,
+                BettingStructure::FixedLimit => self.current_bet + self.bet_size(),
+            };
+            let range = (min.min(all_in), max.max(min).min(all_in));
+            if self.current_bet == 0 {
+                legal.bet = Some(range);
+            } else {
+                legal.raise = Some(range);
+            }
+        }
+        legal
+    }
+
+    fn act(&mut self, action: Action) -> Result<(), TableError> {
+        let i = self.to_act.ok_or(TableError::NoHandInProgress)?;
+        let legal = self.legal_actions(i);
+        if !legal.allows(action) {
+            return Err(TableError::IllegalAction(action));
+        }
+
+        let before = self.players[i].stack;
+        match action {
+            Action::Fold => {
+                self.players[i].folded = true;
+                self.pots.fold(self.players[i].seat);
+            }
+            Action::Check => {}
+            Action::Call => {
+                self.put(i, legal.call.unwrap_or(0), true);
+            }
+            Action::Bet(amount) | Action::Raise(amount) => {
+                self.put(i, amount - self.players[i].street_bet, true);
+                let increment = amount - self.current_bet;
+                // a bet always opens the betting, while a raise short of the minimum (all-in) does not reopen it
+                if self.current_bet == 0 || increment >= self.min_raise {
+                    self.min_raise = self.min_raise.max(increment);
+                    for (j, p) in self.players.iter_mut().enumerate() {
+                        p.acted &= j == i;
+                    }
+                }
+                self.current_bet = amount;
+                self.bets += 1;
+            }
+        }
+
+        let p = &mut self.players[i];
+        p.acted = true;
+        self.events.push(TableEvent::Action {
+            seat: p.seat,
+            action,
+            amount: before - p.stack,
+            all_in: p.all_in,
+        });
+        self.advance(i + 1);
+        Ok(())
+    }
+
+    /// Give the turn to the next player from ```from``` who still has to act, or end the street
+    fn advance(&mut self, from: usize) {
+        self.to_act = None;
+        if self.players.iter().filter(|p| !p.folded).count() == 1 {
+            return self.finish();
+        }
+
+        let able: Vec<&Player> = self.players.iter().filter(|p| !p.folded && !p.all_in).collect();
+        if able.len() > 1 || able.first().is_some_and(|p| p.street_bet < self.current_bet) {
+            let n = self.players.len();
+            self.to_act = (0..n).map(|k| (from + k) % n).find(|i| {
+                let p = &self.players[*i];
+                !p.folded && !p.all_in && (!p.acted || p.street_bet < self.current_bet)
+            });
+        }
+        if self.to_act.is_none() {
+            self.end_street();
+        }
+    }
+
+    fn end_street(&mut self) {
+        for p in self.players.iter_mut() {
+            p.street_bet = 0;
+            p.acted = false;
+        }
+        let (street, count) = match self.street {
+            Street::Preflop => (Street::Flop, 3),
+            Street::Flop => (Street::Turn, 1),
+            Street::Turn => (Street::River, 1),
+            Street::River => return self.finish(),
+        };
+
+        self.deal(1);
+        let cards = self.deal(count);
+        self.board.extend(cards.iter());
+        self.events.push(TableEvent::Board { street, cards });
+        self.street = street;
+        self.current_bet = 0;
+        self.min_raise = self.bet_size();
+        self.bets = 0;
+        self.advance(0);
+    }
+
+    /// Show down the remaining hands, then return the uncalled bet and award the pots
+    fn finish(&mut self) {
+        self.to_act = None;
+        self.finished = true;
+
+        let live: Vec<&Player> = self.players.iter().filter(|p| !p.folded).collect();
+        let mut ranks = Vec::new();
+        if live.len() > 1 {
+            for p in live {
+                let cards: Vec<Card> = p.hole.iter().chain(self.board.iter()).cloned().collect();
+                if let Some(rank) = PokerEvaluator::evaluate(&cards) {
+                    ranks.push((p.seat, p.hole, rank));
+                }
+            }
+        }
+
+        let settlement = self.pots.settle(&ranks.iter().map(|(seat, _, rank)| (*seat, *rank)).collect::<Vec<_>>(), &[]);
+        if let Some((seat, amount)) = settlement.uncalled() {
+            self.events.push(TableEvent::UncalledBet { seat, amount });
+        }
+        for (seat, cards, rank) in ranks {
+            self.events.push(TableEvent::Showdown { seat, cards, rank });
+        }
+        for award in settlement.awards() {
+            self.events.push(TableEvent::Won { seat: award.seat, amount: award.amount, pot: award.pot });
+        }
+        for p in self.players.iter_mut() {
+            p.stack += settlement.total(p.seat);
+        }
+    }
+}
+
+/// A Hold'em table with up to ```MAX_SEATS``` seats that runs hands one action at a time.
+///
+/// Players sit down with a stack, and each hand is started with a deck (shuffled by the caller, e.g. with
+/// ```FairShuffle```, or with ```start_hand_with()```). The button moves to the next player with chips for every hand,
+/// antes and blinds are posted, and the player returned by ```to_act()``` acts with ```act()```, choosing from
+/// ```legal_actions()```. Streets are dealt once their betting is complete, and when at most one player is left who
+/// can bet, the board is run out to the showdown. The events of the hand are recorded in ```events()```.
+///
+/// Heads-up, the button posts the small blind and acts first before the flop and last after it.
+#[derive(Debug, Clone)]
+pub struct Table {
+    config: TableConfig,
+    seats: Vec<Option<Seat>>,
+    button: Option<usize>,
+    // the button placed with set_button(), used as is by the next hand
+    next_button: Option<usize>,
+    hand: Option<Hand>,
+}
+
+impl Table {
+    /// Create a table with the given number of seats. Panics unless there are 2 to ```MAX_SEATS``` seats and the big
+    /// blind is positive
+    pub fn new(config: TableConfig, seats: usize) -> Self {
+        assert!((2..=MAX_SEATS).contains(&seats), "a table has 2 to {} seats", MAX_SEATS);
+        assert!(config.big_blind > 0, "the big blind must be positive");
+        Table {
+            config,
+            seats: vec![None; seats],
+            button: None,
+            next_button: None,
+            hand: None,
+        }
+    }
+
+    /// Get the stakes and betting structure of the table
+    pub fn config(&self) -> &TableConfig {
+        &self.config
+    }
+
+    /// Change the stakes or betting structure for the following hands. The big blind must be positive
+    pub fn set_config(&mut self, config: TableConfig) -> Result<(), TableError> {
+        if config.big_blind == 0 {
+            return Err(TableError::InvalidBlinds);
+        }
+        if self.in_hand() {
+            return Err(TableError::HandInProgress);
+        }
+        self.config = config;
+        Ok(())
+    }
+
+    /// Get the number of seats
+    pub fn seats(&self) -> usize {
+        self.seats.len()
+    }
+
+    /// Seat a player with a stack of chips
+    pub fn sit(&mut self, seat: usize, name: &str, stack: u64) -> Result<(), TableError> {
+        match self.seats.get_mut(seat) {
+            None => Err(TableError::SeatOutOfRange(seat)),
+            Some(Some(_)) => Err(TableError::SeatTaken(seat)),
+            Some(empty) => {
+                *empty = Some(Seat { name: name.to_string(), stack });
+                Ok(())
+            }
+        }
+    }
+
+    /// Remove a player from the table, returning their stack. Players cannot leave during a hand they are dealt in
+    pub fn leave(&mut self, seat: usize) -> Result<u64, TableError> {
+        if self.player(seat).is_some() {
+            return Err(TableError::HandInProgress);
+        }
+        match self.seats.get_mut(seat) {
+            None => Err(TableError::SeatOutOfRange(seat)),
+            Some(taken) => taken.take().map(|s| s.stack).ok_or(TableError::SeatEmpty(seat)),
+        }
+    }
+
+    /// Get the name of the player in the seat
+    pub fn name(&self, seat: usize) -> Option<&str> {
+        self.seats.get(seat)?.as_ref().map(|s| s.name.as_str())
+    }
+
+    /// Get the chips in front of the player in the seat, i.e. their stack minus what they put in the current hand
+    pub fn stack(&self, seat: usize) -> Option<u64> {
+        match self.player(seat) {
+            Some(p) => Some(p.stack),
+            None => self.seats.get(seat)?.as_ref().map(|s| s.stack),
+        }
+    }
+
+    /// Get the seat of the button, once a hand was started
+    pub fn button(&self) -> Option<usize> {
+        self.button
+    }
+
+    /// Place the button in the seat for the next hand, instead of moving it on from the last hand. The seat may be
+    /// empty (a dead button)
+    pub fn set_button(&mut self, seat: usize) -> Result<(), TableError> {
+        if seat >= self.seats.len() {
+            return Err(TableError::SeatOutOfRange(seat));
+        }
+        if self.in_hand() {
+            return Err(TableError::HandInProgress);
+        }
+        self.next_button = Some(seat);
+        Ok(())
+    }
+
+    /// Start a hand dealt from the top of the deck, which must hold at least two cards per player plus eight for the
+    /// board and burn cards, and no jokers
+    pub fn start_hand(&mut self, deck: Deck) -> Result<(), TableError> {
+        if self.in_hand() {
+            return Err(TableError::HandInProgress);
+        }
+        let active: Vec<usize> = (0..self.seats.len())
+            .filter(|s| self.seats[*s].as_ref().is_some_and(|seat| seat.stack > 0))
+            .collect();
+        if active.len() < 2 {
+            return Err(TableError::NotEnoughPlayers);
+        }
+        if deck.len() < 2 * active.len() + 8 || deck.iter().any(|c| c.is_joker()) {
+            return Err(TableError::InvalidDeck);
+        }
+
+        let n = self.seats.len();
+        let button = match (self.next_button.take(), self.button) {
+            (Some(button), _) => button,
+            (None, None) => active[0],
+            (None, Some(button)) => (1..=n).map(|k| (button + k) % n).find(|s| active.contains(s)).unwrap_or(active[0]),
+        };
+        self.button = Some(button);
+        let mut order = active;
+        order.sort_by_key(|s| (s + n - button - 1) % n);
+
+        let mut hand = Hand {
+            config: self.config,
+            deck,
+            players: order.iter().map(|s| Player {
+                seat: *s,
+                hole: [Card::default(); 2],
+                stack: self.seats[*s].as_ref().map_or(0, |seat| seat.stack),
+                street_bet: 0,
+                folded: false,
+                all_in: false,
+                acted: false,
+            }).collect(),
+            pots: PotManager::new(n, button),
+            board: Vec::new(),
+            street: Street::Preflop,
+            current_bet: self.config.big_blind,
+            min_raise: self.config.big_blind,
+            bets: 1,
+            to_act: None,
+            finished: false,
+            events: vec![TableEvent::HandStarted { button }],
+        };
+
+        let count = hand.players.len();
+        if self.config.ante > 0 {
+            for i in 0..count {
+                let amount = hand.put(i, self.config.ante, false);
+                hand.events.push(TableEvent::Ante { seat: hand.players[i].seat, amount });
+            }
+        }
+        let (small, big) = if count == 2 { (1, 0) } else { (0, 1) };
+        let amount = hand.put(small, self.config.small_blind, true);
+        hand.events.push(TableEvent::SmallBlind { seat: hand.players[small].seat, amount });
+        let amount = hand.put(big, self.config.big_blind, true);
+        hand.events.push(TableEvent::BigBlind { seat: hand.players[big].seat, amount });
+
+        let cards = hand.deal(2 * count);
+        for i in 0..count {
+            let p = &mut hand.players[i];
+            p.hole = [cards[i], cards[i + count]];
+            hand.events.push(TableEvent::HoleCards { seat: p.seat, cards: p.hole });
+        }
+
+        hand.advance(big + 1);
+        self.hand = Some(hand);
+        self.settle();
+        Ok(())
+    }
+
+    /// Shuffle a standard deck with the random number generator and start a hand with it
+    pub fn start_hand_with<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Result<(), TableError> {
+        let mut deck = Deck::new();
+        deck.shuffle_with(rng);
+        self.start_hand(deck)
+    }
+
+    /// Returns true while a hand is being played
+    pub fn in_hand(&self) -> bool {
+        self.hand.as_ref().is_some_and(|h| !h.finished)
+    }
+
+    /// Get the street of the current (or last) hand
+    pub fn street(&self) -> Option<Street> {
+        self.hand.as_ref().map(|h| h.street)
+    }
+
+    /// Get the board of the current (or last) hand
+    pub fn board(&self) -> &[Card] {
+        self.hand.as_ref().map_or(&[], |h| &h.board)
+    }
+
+    /// Get the chips put in the pot during the current hand, including the bets of the current street
+    pub fn pot(&self) -> u64 {
+        self.hand.as_ref().filter(|h| !h.finished).map_or(0, |h| h.pot())
+    }
+
+    /// Get the hole cards of the player in the seat, if they were dealt into the current (or last) hand
+    pub fn hole_cards(&self, seat: usize) -> Option<[Card; 2]> {
+        self.hand.as_ref()?.players.iter().find(|p| p.seat == seat).map(|p| p.hole)
+    }
+
+    /// Get the seat of the player whose turn it is
+    pub fn to_act(&self) -> Option<usize> {
+        let hand = self.hand.as_ref()?;
+        hand.to_act.map(|i| hand.players[i].seat)
+    }
+
+    /// Get the actions open to the player whose turn it is
+    pub fn legal_actions(&self) -> Option<LegalActions> {
+        let hand = self.hand.as_ref()?;
+        hand.to_act.map(|i| hand.legal_actions(i))
+    }
+
+    /// Perform an action for the player whose turn it is. Once the action completes the betting of a street, the next
+    /// street is dealt, and once the hand is over the pots are awarded and the stacks updated
+    pub fn act(&mut self, action: Action) -> Result<(), TableError> {
+        self.hand.as_mut().ok_or(TableError::NoHandInProgress)?.act(action)?;
+        self.settle();
+        Ok(())
+    }
+
+    /// Get the events of the current (or last) hand
+    pub fn events(&self) -> &[TableEvent] {
+        self.hand.as_ref().map_or(&[], |h| &h.events)
+    }
+
+    /// Get the player of the seat in the current hand, if it is not over
+    fn player(&self, seat: usize) -> Option<&Player> {
+        self.hand.as_ref().filter(|h| !h.finished)?.players.iter().find(|p| p.seat == seat)
+    }
+
+    /// Copy the stacks back to the seats once the hand is over
+    fn settle(&mut self) {
+        if let Some(hand) = self.hand.as_ref().filter(|h| h.finished) {
+            for p in hand.players.iter() {
+                if let Some(seat) = self.seats[p.seat].as_mut() {
+                    seat.stack = p.stack;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Action, Street, Table, TableConfig, TableError, TableEvent};
+    use crate::cards::cards;
+    use crate::deck::Deck;
+
+    fn table(config: TableConfig, stacks: &[u64]) -> Table {
+        let mut table = Table::new(config, 6);
+        for (seat, stack) in stacks.iter().enumerate() {
+            table.sit(seat, &format!("Player {}", seat + 1), *stack).unwrap();
+        }
+        table
+    }
+
+    #[test]
+    fn blinds_and_button() {
+        let mut table = table(TableConfig::no_limit(5, 10).ante(1), &[1000, 1000, 1000]);
+        table.start_hand(Deck::new()).unwrap();
+
+        assert_eq!(table.button(), Some(0));
+        assert_eq!(table.pot(), 3 + 5 + 10);
+        assert_eq!(table.stack(1), Some(994));
+        assert_eq!(table.stack(2), Some(989));
+        assert_eq!(table.to_act(), Some(0));
+        assert_eq!(table.start_hand(Deck::new()), Err(TableError::HandInProgress));
+        assert_eq!(table.set_config(TableConfig::no_limit(0, 0)), Err(TableError::InvalidBlinds));
+
+        // folding to the big blind ends the hand
+        table.act(Action::Fold).unwrap();
+        table.act(Action::Fold).unwrap();
+        assert!(!table.in_hand());
+        assert_eq!(table.stack(2), Some(1000 + 1 + 1 + 5));
+        assert_eq!(table.events().last(), Some(&TableEvent::Won { seat: 2, amount: 13, pot: 0 }));
+
+        table.start_hand(Deck::new()).unwrap();
+        assert_eq!(table.button(), Some(1));
+        assert_eq!(table.to_act(), Some(1));
+    }
+
+    #[test]
+    fn heads_up_order() {
+        let mut table = table(TableConfig::no_limit(5, 10), &[1000, 1000]);
+        table.start_hand(Deck::new()).unwrap();
+
+        // the button posts the small blind and acts first preflop
+        assert_eq!(table.stack(0), Some(995));
+        assert_eq!(table.to_act(), Some(0));
+        table.act(Action::Call).unwrap();
+        assert_eq!(table.to_act(), Some(1));
+        table.act(Action::Check).unwrap();
+
+        // and last after the flop
+        assert_eq!(table.street(), Some(Street::Flop));
+        assert_eq!(table.board().len(), 3);
+        assert_eq!(table.to_act(), Some(1));
+    }
+
+    #[test]
+    fn no_limit_raises() {
+        let mut table = table(TableConfig::no_limit(5, 10), &[1000, 1000, 1000]);
+        table.start_hand(Deck::new()).unwrap();
+
+        let legal = table.legal_actions().unwrap();
+        assert!(legal.fold && !legal.check);
+        assert_eq!(legal.call, Some(10));
+        assert_eq!(legal.bet, None);
+        assert_eq!(legal.raise, Some((20, 1000)));
+
+        table.act(Action::Raise(35)).unwrap();
+        assert_eq!(table.legal_actions().unwrap().raise, Some((60, 1000)));
+        assert_eq!(table.act(Action::Raise(50)), Err(TableError::IllegalAction(Action::Raise(50))));
+        assert_eq!(table.act(Action::Check), Err(TableError::IllegalAction(Action::Check)));
+        table.act(Action::Call).unwrap();
+
+        // the big blind has the option to raise
+        assert_eq!(table.to_act(), Some(2));
+        assert_eq!(table.legal_actions().unwrap().call, Some(25));
+        table.act(Action::Call).unwrap();
+
+        assert_eq!(table.street(), Some(Street::Flop));
+        assert_eq!(table.pot(), 105);
+        assert_eq!(table.to_act(), Some(1));
+        let legal = table.legal_actions().unwrap();
+        assert!(legal.check && !legal.fold);
+        assert_eq!(legal.bet, Some((10, 965)));
+    }
+
+    #[test]
+    fn short_all_in_does_not_reopen_betting() {
+        let mut table = table(TableConfig::no_limit(5, 10), &[130, 1000, 1000, 1000]);
+        table.start_hand(Deck::new()).unwrap();
+
+        // seat 3 is under the gun, and the button is all-in for a raise of 30, short of a full raise of 90
+        table.act(Action::Raise(100)).unwrap();
+        assert_eq!(table.legal_actions().unwrap().raise, Some((130, 130)));
+        table.act(Action::Raise(130)).unwrap();
+        table.act(Action::Fold).unwrap();
+        assert_eq!(table.legal_actions().unwrap().raise, Some((220, 1000)));
+        table.act(Action::Call).unwrap();
+
+        assert_eq!(table.to_act(), Some(3));
+        let legal = table.legal_actions().unwrap();
+        assert_eq!(legal.call, Some(30));
+        assert_eq!(legal.raise, None);
+    }
+
+    #[test]
+    fn pot_and_fixed_limits() {
+        let mut table = table(TableConfig::pot_limit(5, 10), &[1000, 1000, 1000]);
+        table.start_hand(Deck::new()).unwrap();
+        // a pot sized raise calls 10 and raises the 25 in the pot
+        assert_eq!(table.legal_actions().unwrap().raise, Some((20, 35)));
+
+        let mut table = table_fixed();
+        table.start_hand(Deck::new()).unwrap();
+        assert_eq!(table.legal_actions().unwrap().raise, Some((20, 20)));
+        table.act(Action::Raise(20)).unwrap();
+        table.act(Action::Raise(30)).unwrap();
+        table.act(Action::Raise(40)).unwrap();
+        // the bet and three raises cap the betting
+        assert_eq!(table.legal_actions().unwrap().raise, None);
+        table.act(Action::Call).unwrap();
+        table.act(Action::Call).unwrap();
+
+        table.act(Action::Check).unwrap();
+        table.act(Action::Check).unwrap();
+        table.act(Action::Check).unwrap();
+        assert_eq!(table.street(), Some(Street::Turn));
+        assert_eq!(table.legal_actions().unwrap().bet, Some((20, 20)));
+    }
+
+    fn table_fixed() -> Table {
+        table(TableConfig::fixed_limit(5, 10), &[1000, 1000, 1000])
+    }
+
+    #[test]
+    fn all_in_side_pots() {
+        // dealt one card at a time from the left of the button: seats 1, 2, 0
+        let top = cards("Ah Kh 2c As Kd 7d 3s Qc Jh 2d 4d 5s 9c 8h");
+        let mut table = table(TableConfig::no_limit(5, 10), &[1000, 100, 300]);
+        table.start_hand(Deck::stacked(&top, Deck::new()).unwrap()).unwrap();
+
+        assert_eq!(table.hole_cards(1), Some([top[0], top[3]]));
+        table.act(Action::Raise(1000)).unwrap();
+        table.act(Action::Call).unwrap();
+        table.act(Action::Call).unwrap();
+
+        // everyone is all-in, so the board is run out
+        assert!(!table.in_hand());
+        assert_eq!(table.board(), &cards("Qc Jh 2d 5s 8h")[..]);
+        let events = table.events();
+        assert!(events.contains(&TableEvent::UncalledBet { seat: 0, amount: 700 }));
+        assert!(events.contains(&TableEvent::Won { seat: 1, amount: 300, pot: 0 }));
+        assert!(events.contains(&TableEvent::Won { seat: 2, amount: 400, pot: 1 }));
+        assert_eq!(table.stack(0), Some(700));
+        assert_eq!(table.stack(1), Some(300));
+        assert_eq!(table.stack(2), Some(400));
+
+        table.start_hand(Deck::new()).unwrap();
+        assert_eq!(table.button(), Some(1));
+    }
+
+    #[test]
+    fn split_pot_odd_chip() {
+        // both players left play the royal flush on the board
+        let top = cards("2c 4c 2h 3s 5c 3h 9d As Ks Qs 8d Js 7c Ts");
+        let mut table = table(TableConfig::no_limit(5, 10).ante(1), &[1000, 1000, 1000]);
+        table.start_hand(Deck::stacked(&top, Deck::new()).unwrap()).unwrap();
+
+        table.act(Action::Raise(25)).unwrap();
+        table.act(Action::Call).unwrap();
+        table.act(Action::Fold).unwrap();
+        for _ in 0..6 {
+            table.act(Action::Check).unwrap();
+        }
+
+        // a pot of 63 chips: the odd chip goes to the first winner left of the button
+        assert!(!table.in_hand());
+        assert_eq!(table.stack(1), Some(1000 - 26 + 32));
+        assert_eq!(table.stack(0), Some(1000 - 26 + 31));
+        assert_eq!(table.stack(2), Some(989));
+    }
+}
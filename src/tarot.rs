@@ -0,0 +1,264 @@
+use crate::card_type::CardType;
+use crate::cards::Suit;
+
+/// Represents the rank of a suited Tarot card: Ace to Ten followed by the four court cards Jack (Valet),
+/// Knight (Cavalier), Queen (Dame) and King (Roi)
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TarotRank {
+    Ace,
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Knight,
+    Queen,
+    King,
+}
+
+impl std::fmt::Display for TarotRank {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TarotRank::Ace => write!(f, "A"),
+            TarotRank::Ten => write!(f, "T"),
+            TarotRank::Jack => write!(f, "J"),
+            TarotRank::Knight => write!(f, "C"),
+            TarotRank::Queen => write!(f, "Q"),
+            TarotRank::King => write!(f, "K"),
+            _ => write!(f, "{}", self.to_ordinal() + 1),
+        }
+    }
+}
+
+impl TarotRank {
+    /// Convert a TarotRank variant to its ordinal value
+    pub fn to_ordinal(&self) -> u8 {
+        match self {
+            TarotRank::Ace => 0,
+            TarotRank::Two => 1,
+            TarotRank::Three => 2,
+            TarotRank::Four => 3,
+            TarotRank::Five => 4,
+            TarotRank::Six => 5,
+            TarotRank::Seven => 6,
+            TarotRank::Eight => 7,
+            TarotRank::Nine => 8,
+            TarotRank::Ten => 9,
+            TarotRank::Jack => 10,
+            TarotRank::Knight => 11,
+            TarotRank::Queen => 12,
+            TarotRank::King => 13,
+        }
+    }
+
+    /// Convert an ordinal value to a ```TarotRank``` variant. Returns ```None``` for values outside of 0..13
+    pub fn from_ordinal(rank: u8) -> Option<Self> {
+        match rank {
+            0 => Some(TarotRank::Ace),
+            1 => Some(TarotRank::Two),
+            2 => Some(TarotRank::Three),
+            3 => Some(TarotRank::Four),
+            4 => Some(TarotRank::Five),
+            5 => Some(TarotRank::Six),
+            6 => Some(TarotRank::Seven),
+            7 => Some(TarotRank::Eight),
+            8 => Some(TarotRank::Nine),
+            9 => Some(TarotRank::Ten),
+            10 => Some(TarotRank::Jack),
+            11 => Some(TarotRank::Knight),
+            12 => Some(TarotRank::Queen),
+            13 => Some(TarotRank::King),
+            _ => None,
+        }
+    }
+}
+
+/// Represents a card of the 78-card French Tarot deck: 56 suited cards (using the French suits), the 21 numbered
+/// trumps (1..=21) and the Excuse.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TarotCard {
+    Suited(Suit, TarotRank),
+    Trump(u8),
+    Excuse,
+}
+
+impl std::fmt::Display for TarotCard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TarotCard::Suited(suit, rank) => write!(f, "{}{}", rank, suit),
+            TarotCard::Trump(n) => write!(f, "T{}", n),
+            TarotCard::Excuse => write!(f, "EX"),
+        }
+    }
+}
+
+impl TarotCard {
+    /// Construct a trump card. Returns ```None``` unless the number is in the range 1..=21
+    pub fn trump(number: u8) -> Option<Self> {
+        match number {
+            1..=21 => Some(TarotCard::Trump(number)),
+            _ => None,
+        }
+    }
+
+    /// Returns true for the trumps (but not the Excuse)
+    pub fn is_trump(&self) -> bool {
+        matches!(self, TarotCard::Trump(_))
+    }
+
+    /// Returns true for the three oudlers (bouts): the Petit (trump 1), trump 21 and the Excuse
+    pub fn is_oudler(&self) -> bool {
+        matches!(self, TarotCard::Trump(1) | TarotCard::Trump(21) | TarotCard::Excuse)
+    }
+
+    /// Get the card points of this card, counted in half points so that the value stays integral.
+    /// Oudlers and Kings are worth 4.5 points, Queens 3.5, Knights 2.5, Jacks 1.5 and all other cards 0.5.
+    pub fn half_points(&self) -> u8 {
+        match self {
+            _ if self.is_oudler() => 9,
+            TarotCard::Suited(_, TarotRank::King) => 9,
+            TarotCard::Suited(_, TarotRank::Queen) => 7,
+            TarotCard::Suited(_, TarotRank::Knight) => 5,
+            TarotCard::Suited(_, TarotRank::Jack) => 3,
+            _ => 1,
+        }
+    }
+
+    /// Get the card points of this card
+    pub fn points(&self) -> f32 {
+        self.half_points() as f32 / 2.0
+    }
+}
+
+impl CardType for TarotCard {
+    const NUM_FACES: usize = 78;
+
+    /// Suited cards are indexed by suit and rank (```suit * 14 + rank```), followed by trumps 1 to 21 and the Excuse.
+    /// Cards that are not in the deck (suited cards without a suit, and trumps outside of 1..=21) map to 78
+    fn to_index(&self) -> usize {
+        match self {
+            TarotCard::Suited(suit, rank) if *suit != Suit::None => suit.to_ordinal() as usize * 14 + rank.to_ordinal() as usize,
+            TarotCard::Trump(n @ 1..=21) => 55 + *n as usize,
+            TarotCard::Excuse => 77,
+            _ => Self::NUM_FACES,
+        }
+    }
+
+    fn from_index(index: usize) -> Option<Self> {
+        match index {
+            0..=55 => Some(TarotCard::Suited(
+                Suit::from_ordinal((index / 14) as u8),
+                TarotRank::from_ordinal((index % 14) as u8)?,
+            )),
+            56..=76 => TarotCard::trump((index - 55) as u8),
+            77 => Some(TarotCard::Excuse),
+            _ => None,
+        }
+    }
+}
+
+/// Use this to score the cards taken by a player (or team) in a game of French Tarot
+pub struct TarotEvaluator;
+
+impl TarotEvaluator {
+    /// The total number of card points in a Tarot deck
+    pub const TOTAL_POINTS: f32 = 91.0;
+
+    /// Count the oudlers contained in a set of cards
+    pub fn oudlers(cards: &[TarotCard]) -> usize {
+        cards.iter().filter(|c| c.is_oudler()).count()
+    }
+
+    /// Calculate the card points contained in a set of cards
+    pub fn card_points(cards: &[TarotCard]) -> f32 {
+        cards.iter().map(|c| c.half_points() as u32).sum::<u32>() as f32 / 2.0
+    }
+
+    /// The number of card points the taker needs to make their contract, based on the number of oudlers taken
+    pub fn points_required(oudlers: usize) -> f32 {
+        match oudlers {
+            0 => 56.0,
+            1 => 51.0,
+            2 => 41.0,
+            _ => 36.0,
+        }
+    }
+
+    /// Calculate by how many points the taker made (positive or zero) or missed (negative) their contract
+    pub fn contract_margin(taken: &[TarotCard]) -> f32 {
+        Self::card_points(taken) - Self::points_required(Self::oudlers(taken))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TarotCard, TarotEvaluator, TarotRank};
+    use crate::card_type::CardType;
+    use crate::cards::Suit;
+    use crate::deck::Deck;
+    use crate::deck_spec::DeckSpec;
+
+    #[test]
+    fn tarot_standard_deck() {
+        let deck = TarotCard::standard_deck();
+
+        assert_eq!(deck.len(), 78);
+        assert_eq!(deck.iter().filter(|c| c.is_trump()).count(), 21);
+        assert_eq!(deck[11], TarotCard::Suited(Suit::Clubs, TarotRank::Knight));
+        assert_eq!(deck[56], TarotCard::Trump(1));
+        assert_eq!(deck[77], TarotCard::Excuse);
+        for (i, card) in deck.iter().enumerate() {
+            assert_eq!(card.to_index(), i);
+        }
+    }
+
+    #[test]
+    fn tarot_scoring() {
+        let deck = TarotCard::standard_deck();
+
+        assert_eq!(TarotEvaluator::oudlers(&deck), 3);
+        assert_eq!(TarotEvaluator::card_points(&deck), TarotEvaluator::TOTAL_POINTS);
+
+        let taken = [TarotCard::Excuse, TarotCard::Trump(21), TarotCard::Suited(Suit::Hearts, TarotRank::King), TarotCard::Trump(5)];
+        assert_eq!(TarotEvaluator::card_points(&taken), 14.0);
+        assert_eq!(TarotEvaluator::points_required(TarotEvaluator::oudlers(&taken)), 41.0);
+        assert_eq!(TarotEvaluator::contract_margin(&taken), -27.0);
+    }
+
+    #[test]
+    fn trump_bounds() {
+        assert_eq!(TarotCard::trump(0), None);
+        assert_eq!(TarotCard::trump(22), None);
+        assert_eq!(TarotCard::trump(21).unwrap().to_string(), "T21");
+
+        // cards that are not in the deck do not pass for real ones
+        let invalid = [TarotCard::Trump(0), TarotCard::Trump(30), TarotCard::Suited(Suit::None, TarotRank::Ace)];
+        let mut deck = Deck::from_cards(TarotCard::standard_deck());
+        let fingerprint = deck.fingerprint();
+        for card in invalid.iter() {
+            assert_eq!(card.to_index(), TarotCard::NUM_FACES);
+            deck.add(*card);
+        }
+        assert_eq!(deck.fingerprint(), None);
+        let report = deck.validate(&DeckSpec::default());
+        assert_eq!(report.foreign, vec![(TarotCard::Trump(0), 1), (TarotCard::Trump(30), 1), (TarotCard::Suited(Suit::None, TarotRank::Ace), 1)]);
+        assert!(report.missing.is_empty() && report.duplicates.is_empty());
+        deck.draw_top();
+        deck.draw_top();
+        deck.draw_top();
+        assert_eq!(deck.fingerprint(), fingerprint);
+        for (i, card) in invalid.iter().enumerate() {
+            let mut swapped = Deck::from_cards(TarotCard::standard_deck());
+            swapped.draw_top();
+            swapped.add(*card);
+            assert!(!swapped.validate(&DeckSpec::default()).is_valid(), "{}", i);
+        }
+    }
+}
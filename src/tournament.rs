@@ -0,0 +1,758 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use rand::Rng;
+use rand::seq::SliceRandom;
+use crate::deck::Deck;
+use crate::icm::{IcmCalculator, IcmError};
+use crate::table::{Action, BettingStructure, Table, TableConfig, TableError, MAX_SEATS};
+
+/// A source of the time elapsed since some fixed instant, which drives the blind levels of a ```Tournament```
+pub trait Clock {
+    /// Get the time elapsed since the clock's epoch
+    fn now(&self) -> Duration;
+}
+
+/// A clock following the wall clock from the moment it was created
+#[derive(Debug, Copy, Clone)]
+pub struct SystemClock(Instant);
+
+impl SystemClock {
+    /// Create a clock starting now
+    pub fn new() -> Self {
+        SystemClock(Instant::now())
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        SystemClock::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.0.elapsed()
+    }
+}
+
+/// A clock that only moves when told to, for tests and simulations. Clones share the same time, so a tournament can
+/// be given a clone while the caller keeps advancing the original.
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock(Arc<AtomicU64>);
+
+impl ManualClock {
+    /// Create a clock standing at 0
+    pub fn new() -> Self {
+        ManualClock::default()
+    }
+
+    /// Move the clock forward, with millisecond precision
+    pub fn advance(&self, duration: Duration) {
+        self.0.fetch_add(duration.as_millis() as u64, Ordering::SeqCst);
+    }
+
+    /// Set the clock to the given time, with millisecond precision
+    pub fn set(&self, time: Duration) {
+        self.0.store(time.as_millis() as u64, Ordering::SeqCst);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        Duration::from_millis(self.0.load(Ordering::SeqCst))
+    }
+}
+
+/// How long a blind level lasts
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LevelLength {
+    /// The level ends once the time has passed. The next level starts with the following hand
+    Time(Duration),
+    /// The level ends once the number of hands was started at any one table
+    Hands(u32),
+}
+
+/// The blinds and ante of a level of a tournament
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlindLevel {
+    pub small_blind: u64,
+    pub big_blind: u64,
+    pub ante: u64,
+    pub length: LevelLength,
+}
+
+/// The blind levels of a tournament, played in order. The last level lasts until the tournament is over.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlindSchedule {
+    structure: BettingStructure,
+    levels: Vec<BlindLevel>,
+}
+
+impl BlindSchedule {
+    /// Create an empty schedule for the betting structure
+    pub fn new(structure: BettingStructure) -> Self {
+        BlindSchedule {
+            structure,
+            levels: Vec::new(),
+        }
+    }
+
+    /// Add a level to the end of the schedule. Panics unless the big blind is positive
+    pub fn level(mut self, small_blind: u64, big_blind: u64, ante: u64, length: LevelLength) -> Self {
+        assert!(big_blind > 0, "the big blind must be positive");
+        self.levels.push(BlindLevel {
+            small_blind,
+            big_blind,
+            ante,
+            length,
+        });
+        self
+    }
+
+    /// Get the betting structure
+    pub fn structure(&self) -> BettingStructure {
+        self.structure
+    }
+
+    /// Get the levels
+    pub fn levels(&self) -> &[BlindLevel] {
+        &self.levels
+    }
+
+    /// Get the table configuration of the level, the last level standing in for any level past the end. Panics if
+    /// the schedule is empty
+    pub fn config(&self, level: usize) -> TableConfig {
+        let level = self.levels[level.min(self.levels.len() - 1)];
+        TableConfig {
+            structure: self.structure,
+            small_blind: level.small_blind,
+            big_blind: level.big_blind,
+            ante: level.ante,
+        }
+    }
+}
+
+/// The prizes of a tournament by finishing place, first place first
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PayoutStructure {
+    prizes: Vec<u64>,
+}
+
+impl PayoutStructure {
+    /// Create a payout structure from the prizes, first place first
+    pub fn new(prizes: Vec<u64>) -> Self {
+        PayoutStructure { prizes }
+    }
+
+    /// Split a prize pool by the percentages, first place first. Amounts are rounded down, and the chips left over go
+    /// to first place
+    pub fn from_percentages(pool: u64, percentages: &[f64]) -> Self {
+        let mut prizes: Vec<u64> = percentages.iter().map(|p| (pool as f64 * p / 100.0).floor() as u64).collect();
+        let paid: u64 = prizes.iter().sum();
+        if let Some(first) = prizes.first_mut() {
+            *first += pool.saturating_sub(paid);
+        }
+        PayoutStructure { prizes }
+    }
+
+    /// Get the prizes, first place first
+    pub fn prizes(&self) -> &[u64] {
+        &self.prizes
+    }
+
+    /// Get the prize for a finishing place, counted from 1. Places outside of the money win 0
+    pub fn prize(&self, place: usize) -> u64 {
+        place.checked_sub(1).and_then(|i| self.prizes.get(i)).copied().unwrap_or(0)
+    }
+
+    /// Get the sum of the prizes
+    pub fn total(&self) -> u64 {
+        self.prizes.iter().sum()
+    }
+}
+
+/// A player's finishing place in a tournament and their prize
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Standing {
+    pub player: usize,
+    pub place: usize,
+    pub prize: u64,
+}
+
+/// Describes why a tournament operation failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TournamentError {
+    /// Players can only register before the tournament starts, and it can only start once
+    AlreadyStarted,
+    /// The tournament has not started
+    NotStarted,
+    /// The tournament is over
+    Finished,
+    /// A player with the name is already registered
+    DuplicateName(String),
+    /// The tournament needs at least two players
+    NotEnoughPlayers,
+    /// There is no table with the index
+    TableOutOfRange(usize),
+    /// The operation failed at the table
+    Table(TableError),
+}
+
+impl std::fmt::Display for TournamentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TournamentError::AlreadyStarted => write!(f, "the tournament has already started"),
+            TournamentError::NotStarted => write!(f, "the tournament has not started"),
+            TournamentError::Finished => write!(f, "the tournament is over"),
+            TournamentError::DuplicateName(name) => write!(f, "'{}' is already registered", name),
+            TournamentError::NotEnoughPlayers => write!(f, "a tournament needs at least two players"),
+            TournamentError::TableOutOfRange(table) => write!(f, "there is no table {}", table),
+            TournamentError::Table(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for TournamentError {}
+
+impl From<TableError> for TournamentError {
+    fn from(e: TableError) -> Self {
+        TournamentError::Table(e)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Entrant {
+    name: String,
+    // the table and seat, while the player is in the tournament
+    seat: Option<(usize, usize)>,
+    place: Option<usize>,
+}
+
+/// A multi-table Hold'em tournament. Players register, ```start()``` seats them at random across as few tables as
+/// possible, and each table plays its hands through ```start_hand()``` and ```act()``` (the tables can be played
+/// independently of each other). Whenever a hand is over:
+///
+/// * Players without chips are eliminated. Players busting in the same hand finish in the order of their stacks at
+///   the start of the hand, the bigger stack finishing higher.
+/// * The table is broken if the players left fit at the other tables, its players moving to the shortest tables.
+/// * Otherwise, while the table holds two or more players more than the shortest table, the player who would be big
+///   blind next moves there.
+///
+/// The blinds follow the ```BlindSchedule```, the level being checked at the start of every hand against the clock
+/// (see ```ManualClock``` to control time) and the number of hands played.
+#[derive(Debug, Clone)]
+pub struct Tournament<C: Clock = SystemClock> {
+    schedule: BlindSchedule,
+    payouts: PayoutStructure,
+    starting_stack: u64,
+    table_size: usize,
+    clock: C,
+    entrants: Vec<Entrant>,
+    tables: Vec<Table>,
+    started: bool,
+    level: usize,
+    level_start: Duration,
+    // the hands started at each table in the current level
+    level_hands: Vec<u32>,
+    // the stacks of each table by seat at the start of its current (or last) hand
+    hand_stacks: Vec<Vec<u64>>,
+    remaining: usize,
+}
+
+impl<C: Clock> Tournament<C> {
+    /// Create a tournament. Panics if the schedule has no levels or the tables do not have 2 to ```MAX_SEATS```
+    /// seats
+    pub fn new(schedule: BlindSchedule, payouts: PayoutStructure, starting_stack: u64, table_size: usize, clock: C) -> Self {
+        assert!(!schedule.levels().is_empty(), "the blind schedule has no levels");
+        assert!((2..=MAX_SEATS).contains(&table_size), "a table has 2 to {} seats", MAX_SEATS);
+        Tournament {
+            schedule,
+            payouts,
+            starting_stack,
+            table_size,
+            clock,
+            entrants: Vec::new(),
+            tables: Vec::new(),
+            started: false,
+            level: 0,
+            level_start: Duration::ZERO,
+            level_hands: Vec::new(),
+            hand_stacks: Vec::new(),
+            remaining: 0,
+        }
+    }
+
+    /// Register a player, returning their index
+    pub fn register(&mut self, name: &str) -> Result<usize, TournamentError> {
+        if self.started {
+            return Err(TournamentError::AlreadyStarted);
+        }
+        if self.entrants.iter().any(|e| e.name == name) {
+            return Err(TournamentError::DuplicateName(name.to_string()));
+        }
+        self.entrants.push(Entrant {
+            name: name.to_string(),
+            seat: None,
+            place: None,
+        });
+        Ok(self.entrants.len() - 1)
+    }
+
+    /// Start the tournament, seating the players at random and starting the clock of the first level
+    pub fn start<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Result<(), TournamentError> {
+        if self.started {
+            return Err(TournamentError::AlreadyStarted);
+        }
+        if self.entrants.len() < 2 {
+            return Err(TournamentError::NotEnoughPlayers);
+        }
+        let count = self.entrants.len().div_ceil(self.table_size);
+        self.tables = vec![Table::new(self.schedule.config(0), self.table_size); count];
+        self.level_hands = vec![0; count];
+        self.hand_stacks = vec![vec![0; self.table_size]; count];
+        let mut order: Vec<usize> = (0..self.entrants.len()).collect();
+        order.shuffle(rng);
+        for (i, player) in order.into_iter().enumerate() {
+            let (table, seat) = (i % count, i / count);
+            self.tables[table].sit(seat, &self.entrants[player].name, self.starting_stack)?;
+            self.entrants[player].seat = Some((table, seat));
+        }
+        self.started = true;
+        self.remaining = self.entrants.len();
+        self.level_start = self.clock.now();
+        Ok(())
+    }
+
+    /// Get the number of registered players
+    pub fn players(&self) -> usize {
+        self.entrants.len()
+    }
+
+    /// Get the number of players still in the tournament
+    pub fn remaining(&self) -> usize {
+        if self.started { self.remaining } else { self.entrants.len() }
+    }
+
+    /// Returns true once a single player is left
+    pub fn is_finished(&self) -> bool {
+        self.started && self.remaining == 1
+    }
+
+    /// Get the name of the player
+    pub fn name(&self, player: usize) -> Option<&str> {
+        self.entrants.get(player).map(|e| e.name.as_str())
+    }
+
+    /// Get the table and seat of the player, while they are in the tournament
+    pub fn seat(&self, player: usize) -> Option<(usize, usize)> {
+        self.entrants.get(player)?.seat
+    }
+
+    /// Get the chips of the player, including any they have in the pot of a hand being played
+    pub fn chips(&self, player: usize) -> u64 {
+        match self.seat(player) {
+            Some((table, seat)) if self.tables[table].in_hand() => self.hand_stacks[table][seat],
+            Some((table, seat)) => self.tables[table].stack(seat).unwrap_or(0),
+            None if self.started => 0,
+            None => self.starting_stack,
+        }
+    }
+
+    /// Get the finishing place of the player, once they are eliminated or have won
+    pub fn place(&self, player: usize) -> Option<usize> {
+        self.entrants.get(player)?.place
+    }
+
+    /// Get the players who have finished, winner first once there is one, with their prizes
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = self
+            .entrants
+            .iter()
+            .enumerate()
+            .filter_map(|(player, e)| {
+                e.place.map(|place| Standing {
+                    player,
+                    place,
+                    prize: self.payouts.prize(place),
+                })
+            })
+            .collect();
+        standings.sort_by_key(|s| s.place);
+        standings
+    }
+
+    /// Get the tables, including broken tables, which are left empty
+    pub fn tables(&self) -> &[Table] {
+        &self.tables
+    }
+
+    /// Get the table with the index
+    pub fn table(&self, table: usize) -> Option<&Table> {
+        self.tables.get(table)
+    }
+
+    /// Get the blind schedule
+    pub fn schedule(&self) -> &BlindSchedule {
+        &self.schedule
+    }
+
+    /// Get the payout structure
+    pub fn payouts(&self) -> &PayoutStructure {
+        &self.payouts
+    }
+
+    /// Get the index of the current blind level. Hands started from now on are played at this level
+    pub fn level(&self) -> usize {
+        self.current_level().0
+    }
+
+    /// Get the blinds and ante of the current level
+    pub fn blinds(&self) -> &BlindLevel {
+        &self.schedule.levels()[self.level().min(self.schedule.levels().len() - 1)]
+    }
+
+    /// Get the time left in the current level, if it is a timed level and not the last
+    pub fn time_to_next_level(&self) -> Option<Duration> {
+        let (level, start) = self.current_level();
+        if !self.started || level + 1 >= self.schedule.levels().len() {
+            return None;
+        }
+        match self.schedule.levels()[level].length {
+            LevelLength::Time(length) => Some((start + length).saturating_sub(self.clock.now())),
+            LevelLength::Hands(_) => None,
+        }
+    }
+
+    /// Start a hand at the table, dealt from the top of the deck, with the blinds of the current level
+    pub fn start_hand(&mut self, table: usize, deck: Deck) -> Result<(), TournamentError> {
+        if !self.started {
+            return Err(TournamentError::NotStarted);
+        }
+        if self.is_finished() {
+            return Err(TournamentError::Finished);
+        }
+        if table >= self.tables.len() {
+            return Err(TournamentError::TableOutOfRange(table));
+        }
+        if self.tables[table].in_hand() {
+            return Err(TableError::HandInProgress.into());
+        }
+        let (level, start) = self.current_level();
+        if level != self.level {
+            self.level = level;
+            self.level_start = start;
+            self.level_hands.iter_mut().for_each(|h| *h = 0);
+        }
+        self.tables[table].set_config(self.schedule.config(level))?;
+        for seat in 0..self.table_size {
+            self.hand_stacks[table][seat] = self.tables[table].stack(seat).unwrap_or(0);
+        }
+        self.tables[table].start_hand(deck)?;
+        self.level_hands[table] += 1;
+        Ok(())
+    }
+
+    /// Shuffle a standard deck with the random number generator and start a hand at the table with it
+    pub fn start_hand_with<R: Rng + ?Sized>(&mut self, table: usize, rng: &mut R) -> Result<(), TournamentError> {
+        let mut deck = Deck::new();
+        deck.shuffle_with(rng);
+        self.start_hand(table, deck)
+    }
+
+    /// Perform an action for the player whose turn it is at the table. Once the hand is over, busted players are
+    /// eliminated and the tables are broken or balanced
+    pub fn act(&mut self, table: usize, action: Action) -> Result<(), TournamentError> {
+        self.tables.get_mut(table).ok_or(TournamentError::TableOutOfRange(table))?.act(action)?;
+        if !self.tables[table].in_hand() {
+            self.eliminate(table);
+            if !self.is_finished() {
+                self.rebalance(table);
+            }
+        }
+        Ok(())
+    }
+
+    /// Split the remaining prizes between the players left by their prize equity under the calculator's model,
+    /// rounded down to whole units, the units left over going to the players who lost the most to rounding. Returns
+    /// each remaining player with their share, or the reason the calculator could not be run on the stacks
+    pub fn icm_chop(&self, calculator: &IcmCalculator) -> Result<Vec<(usize, u64)>, IcmError> {
+        let players: Vec<usize> = (0..self.entrants.len()).filter(|p| self.entrants[*p].place.is_none()).collect();
+        let stacks: Vec<u64> = players.iter().map(|p| self.chips(*p)).collect();
+        let prizes: Vec<u64> = (1..=players.len()).map(|place| self.payouts.prize(place)).collect();
+        let equities = calculator.calculate(&stacks, &prizes)?.equities().to_vec();
+        let mut shares: Vec<u64> = equities.iter().map(|e| e.floor() as u64).collect();
+        let mut by_remainder: Vec<usize> = (0..players.len()).collect();
+        by_remainder.sort_by(|a, b| (equities[*b] - equities[*b].floor()).total_cmp(&(equities[*a] - equities[*a].floor())));
+        let left = prizes.iter().sum::<u64>().saturating_sub(shares.iter().sum());
+        for i in by_remainder.into_iter().cycle().take(left as usize) {
+            shares[i] += 1;
+        }
+        Ok(players.into_iter().zip(shares).collect())
+    }
+
+    /// The current level and the time it started, moving on from the stored level for the time and hands played
+    fn current_level(&self) -> (usize, Duration) {
+        let (mut level, mut start) = (self.level, self.level_start);
+        if !self.started {
+            return (level, start);
+        }
+        let now = self.clock.now();
+        let hands = self.level_hands.iter().copied().max().unwrap_or(0);
+        while level + 1 < self.schedule.levels().len() {
+            match self.schedule.levels()[level].length {
+                LevelLength::Time(length) if now >= start + length => start += length,
+                LevelLength::Hands(count) if level == self.level && hands >= count => start = now,
+                _ => break,
+            }
+            level += 1;
+        }
+        (level, start)
+    }
+
+    /// Eliminate the players at the table who have no chips left after the hand
+    fn eliminate(&mut self, table: usize) {
+        let mut busted: Vec<usize> = (0..self.table_size)
+            .filter(|seat| self.tables[table].stack(*seat) == Some(0))
+            .collect();
+        busted.sort_by_key(|seat| self.hand_stacks[table][*seat]);
+        for seat in busted {
+            let player = self.player_at(table, seat);
+            self.tables[table].leave(seat).expect("the hand is over");
+            self.entrants[player].seat = None;
+            self.entrants[player].place = Some(self.remaining);
+            self.remaining -= 1;
+        }
+        if self.remaining == 1 {
+            if let Some(winner) = self.entrants.iter_mut().find(|e| e.place.is_none()) {
+                winner.place = Some(1);
+            }
+        }
+    }
+
+    /// Break the table if its players fit elsewhere, or else move players from it to the shortest tables
+    fn rebalance(&mut self, table: usize) {
+        let open: Vec<usize> = (0..self.tables.len()).filter(|t| *t != table && self.occupied(*t) > 0).collect();
+        if open.is_empty() {
+            return;
+        }
+        if self.remaining <= open.len() * self.table_size {
+            for seat in 0..self.table_size {
+                if self.tables[table].name(seat).is_some() {
+                    self.move_player(table, seat, &open);
+                }
+            }
+            return;
+        }
+        loop {
+            let shortest = open.iter().map(|t| self.occupied(*t)).min().unwrap_or(0);
+            if self.occupied(table) < shortest + 2 {
+                break;
+            }
+            let seat = self.next_big_blind(table);
+            self.move_player(table, seat, &open);
+        }
+    }
+
+    /// Move the player in the seat to the first empty seat of the shortest of the tables
+    fn move_player(&mut self, from: usize, seat: usize, tables: &[usize]) {
+        let to = *tables.iter().min_by_key(|t| self.occupied(**t)).expect("there are tables to move to");
+        let empty = (0..self.table_size).find(|s| self.tables[to].name(*s).is_none()).expect("the table has room");
+        let player = self.player_at(from, seat);
+        let stack = self.tables[from].leave(seat).expect("the hand is over");
+        self.tables[to].sit(empty, &self.entrants[player].name, stack).expect("the seat is empty");
+        // a player joining a hand in progress is not dealt in, so the stack stands for the rest of the hand
+        self.hand_stacks[to][empty] = stack;
+        self.entrants[player].seat = Some((to, empty));
+    }
+
+    /// The seat of the player who would post the big blind in the next hand at the table
+    fn next_big_blind(&self, table: usize) -> usize {
+        let after = self.tables[table].button().map_or(0, |b| b + 1);
+        let seats: Vec<usize> = (0..self.table_size)
+            .map(|i| (after + i) % self.table_size)
+            .filter(|s| self.tables[table].name(*s).is_some())
+            .collect();
+        seats[2 % seats.len()]
+    }
+
+    fn occupied(&self, table: usize) -> usize {
+        (0..self.table_size).filter(|s| self.tables[table].name(*s).is_some()).count()
+    }
+
+    fn player_at(&self, table: usize, seat: usize) -> usize {
+        self.entrants.iter().position(|e| e.seat == Some((table, seat))).expect("the seat is taken")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use super::{BlindSchedule, LevelLength, ManualClock, PayoutStructure, Tournament, TournamentError};
+    use crate::icm::{IcmCalculator, IcmModel};
+    use crate::table::{Action, BettingStructure};
+
+    fn minutes(m: u64) -> LevelLength {
+        LevelLength::Time(Duration::from_secs(60 * m))
+    }
+
+    fn tournament(players: usize, table_size: usize, schedule: BlindSchedule, clock: &ManualClock) -> Tournament<ManualClock> {
+        let payouts = PayoutStructure::from_percentages(100 * players as u64, &[50.0, 30.0, 20.0]);
+        let mut tournament = Tournament::new(schedule, payouts, 1000, table_size, clock.clone());
+        for i in 0..players {
+            tournament.register(&format!("player{}", i)).unwrap();
+        }
+        tournament
+    }
+
+    // Everybody goes all in, or calls an all in
+    fn play_hand(tournament: &mut Tournament<ManualClock>, table: usize) {
+        while let Some(legal) = tournament.table(table).unwrap().legal_actions() {
+            let action = match (legal.raise, legal.bet, legal.call) {
+                (Some((_, max)), _, _) => Action::Raise(max),
+                (_, Some((_, max)), _) => Action::Bet(max),
+                (_, _, Some(_)) => Action::Call,
+                _ => Action::Check,
+            };
+            tournament.act(table, action).unwrap();
+        }
+    }
+
+    #[test]
+    fn levels_by_time() {
+        let clock = ManualClock::new();
+        let schedule = BlindSchedule::new(BettingStructure::NoLimit)
+            .level(10, 20, 0, minutes(10))
+            .level(20, 40, 0, minutes(10))
+            .level(50, 100, 10, minutes(10));
+        let mut t = tournament(4, 9, schedule, &clock);
+        assert_eq!(t.time_to_next_level(), None);
+        let mut rng = StdRng::seed_from_u64(1);
+        t.start(&mut rng).unwrap();
+        assert_eq!(t.register("late"), Err(TournamentError::AlreadyStarted));
+        assert_eq!(t.level(), 0);
+        assert_eq!(t.time_to_next_level(), Some(Duration::from_secs(600)));
+        clock.advance(Duration::from_secs(660));
+        assert_eq!(t.level(), 1);
+        assert_eq!(t.blinds().big_blind, 40);
+        assert_eq!(t.time_to_next_level(), Some(Duration::from_secs(540)));
+        t.start_hand_with(0, &mut rng).unwrap();
+        assert_eq!(t.table(0).unwrap().config().big_blind, 40);
+        // levels keep to the schedule however long the hands take, and the last level never ends
+        clock.advance(Duration::from_secs(3600));
+        assert_eq!(t.level(), 2);
+        assert_eq!(t.time_to_next_level(), None);
+        assert_eq!(t.table(0).unwrap().config().big_blind, 40);
+    }
+
+    #[test]
+    fn levels_by_hands() {
+        let clock = ManualClock::new();
+        let schedule = BlindSchedule::new(BettingStructure::NoLimit)
+            .level(1, 2, 0, LevelLength::Hands(2))
+            .level(2, 4, 1, LevelLength::Hands(2))
+            .level(5, 10, 1, LevelLength::Hands(2));
+        let mut t = tournament(2, 2, schedule, &clock);
+        let mut rng = StdRng::seed_from_u64(2);
+        t.start(&mut rng).unwrap();
+        let mut blinds = Vec::new();
+        for _ in 0..5 {
+            t.start_hand_with(0, &mut rng).unwrap();
+            blinds.push(t.table(0).unwrap().config().big_blind);
+            // fold the hands, keeping both players in
+            while t.table(0).unwrap().in_hand() {
+                t.act(0, Action::Fold).unwrap();
+            }
+        }
+        assert_eq!(blinds, vec![2, 2, 4, 4, 10]);
+        assert_eq!(t.level(), 2);
+    }
+
+    #[test]
+    fn balancing_and_finishing_order() {
+        let clock = ManualClock::new();
+        let schedule = BlindSchedule::new(BettingStructure::NoLimit).level(25, 50, 0, LevelLength::Hands(10));
+        let mut t = tournament(20, 9, schedule, &clock);
+        let mut rng = StdRng::seed_from_u64(3);
+        t.start(&mut rng).unwrap();
+        let counts = |t: &Tournament<ManualClock>| (0..3).map(|i| (0..9).filter(|s| t.table(i).unwrap().name(*s).is_some()).count()).collect::<Vec<_>>();
+        assert_eq!(counts(&t), vec![7, 7, 6]);
+        let total = |t: &Tournament<ManualClock>| (0..t.players()).map(|p| t.chips(p)).sum::<u64>();
+        while !t.is_finished() {
+            for table in 0..3 {
+                if t.is_finished() || t.start_hand_with(table, &mut rng).is_err() {
+                    continue;
+                }
+                play_hand(&mut t, table);
+                assert_eq!(total(&t), 20_000);
+                let seats: Vec<_> = (0..t.players()).filter_map(|p| t.seat(p)).collect();
+                assert_eq!(seats.len(), t.remaining());
+                for (table, seat) in seats {
+                    assert!(t.table(table).unwrap().name(seat).is_some());
+                }
+            }
+        }
+        assert_eq!(counts(&t).iter().filter(|c| **c > 0).count(), 1);
+        let standings = t.standings();
+        assert_eq!(standings.iter().map(|s| s.place).collect::<Vec<_>>(), (1..=20).collect::<Vec<_>>());
+        assert_eq!(standings.iter().map(|s| s.prize).collect::<Vec<_>>()[..4], [1000, 600, 400, 0]);
+        assert_eq!(t.chips(standings[0].player), 20_000);
+        assert_eq!(t.start_hand_with(0, &mut rng), Err(TournamentError::Finished));
+    }
+
+    #[test]
+    fn moving_during_a_hand() {
+        let clock = ManualClock::new();
+        let schedule = BlindSchedule::new(BettingStructure::NoLimit).level(25, 50, 0, LevelLength::Hands(10));
+        let mut t = tournament(4, 3, schedule, &clock);
+        let mut rng = StdRng::seed_from_u64(6);
+        t.start(&mut rng).unwrap();
+        // table 0 stays in its hand while table 1 plays until somebody busts and the table breaks
+        t.start_hand_with(0, &mut rng).unwrap();
+        while t.remaining() == 4 {
+            t.start_hand_with(1, &mut rng).unwrap();
+            play_hand(&mut t, 1);
+        }
+        assert!(t.table(0).unwrap().in_hand());
+        assert_eq!(t.remaining(), 3);
+        assert!((0..4).filter_map(|p| t.seat(p)).all(|(table, _)| table == 0));
+        assert_eq!((0..4).map(|p| t.chips(p)).sum::<u64>(), 4000);
+        let chop = t.icm_chop(&IcmCalculator::new()).unwrap();
+        assert!(chop.iter().all(|(player, share)| t.chips(*player) > 0 && *share > 0));
+    }
+
+    #[test]
+    fn payouts_and_icm_chop() {
+        let payouts = PayoutStructure::from_percentages(1001, &[50.0, 30.0, 20.0]);
+        assert_eq!(payouts.prizes(), &[501, 300, 200]);
+        assert_eq!(payouts.prize(4), 0);
+        assert_eq!(payouts.total(), 1001);
+
+        let clock = ManualClock::new();
+        let schedule = BlindSchedule::new(BettingStructure::NoLimit).level(10, 20, 0, minutes(20));
+        let mut t = tournament(3, 6, schedule, &clock);
+        t.start(&mut StdRng::seed_from_u64(4)).unwrap();
+        for calculator in [IcmCalculator::new(), IcmCalculator::new().model(IcmModel::MalmuthWeitzman)] {
+            let chop = t.icm_chop(&calculator).unwrap();
+            assert_eq!(chop.iter().map(|(player, _)| *player).collect::<Vec<_>>(), vec![0, 1, 2]);
+            assert!(chop.iter().all(|(_, share)| *share == 100));
+        }
+
+        // after a hand the stacks differ, and the whole of the prizes is still handed out
+        t.start_hand_with(0, &mut StdRng::seed_from_u64(5)).unwrap();
+        t.act(0, Action::Raise(60)).unwrap();
+        while t.table(0).unwrap().in_hand() {
+            t.act(0, Action::Fold).unwrap();
+        }
+        let chop = t.icm_chop(&IcmCalculator::new()).unwrap();
+        assert_eq!(chop.iter().map(|(_, share)| share).sum::<u64>(), 300);
+        assert!(chop.iter().any(|(_, share)| *share > 100));
+    }
+}
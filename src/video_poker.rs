@@ -0,0 +1,575 @@
+use rand::Rng;
+use crate::card_set::CardSet;
+use crate::cards::{Card, Rank};
+use crate::deck::Deck;
+use crate::poker::{HandCategory, HandRank};
+use crate::poker_lookup::LookupEvaluator;
+
+/// The mask of the 13 cards of a suit within a ```CardSet```
+const SUIT_MASK: u64 = (1 << 13) - 1;
+
+/// The bits of the four deuces within a ```CardSet```
+const DEUCES: u64 = 1 << 1 | 1 << 14 | 1 << 27 | 1 << 40;
+
+/// The bit of the joker within a ```CardSet```
+const JOKER: u64 = 1 << 52;
+
+/// The mask of poker values (bit ```value - 2```) of each straight, from the wheel up to the royal
+const STRAIGHTS: [u16; 10] = [
+    0x100f, 0x1f, 0x3e, 0x7c, 0xf8, 0x1f0, 0x3e0, 0x7c0, 0xf80, 0x1f00,
+];
+
+/// A video poker game, which sets the deck, the wild cards and the hands that pay
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VideoPokerGame {
+    /// No wild cards, paying from a pair of jacks
+    JacksOrBetter,
+    /// The four deuces are wild, paying from three of a kind
+    DeucesWild,
+    /// A 53-card deck whose joker is wild, paying from a pair of kings
+    JokerPoker,
+    /// Jacks or Better with bonuses for four aces, twos, threes and fours, and for their kickers
+    DoubleDoubleBonus,
+}
+
+impl VideoPokerGame {
+    /// Get an unshuffled deck for the game
+    pub fn deck(&self) -> Deck {
+        let mut deck = Deck::new();
+        if *self == VideoPokerGame::JokerPoker {
+            deck.add(Card::joker());
+        }
+        deck
+    }
+
+    /// Returns true if the card is wild in the game
+    pub fn is_wild(&self, card: &Card) -> bool {
+        match self {
+            VideoPokerGame::DeucesWild => *card.rank() == Rank::Two,
+            VideoPokerGame::JokerPoker => card.is_joker(),
+            _ => false,
+        }
+    }
+
+    /// Classify a five card hand. Returns None unless there are five different cards, or if a card is a joker in a
+    /// game without one
+    pub fn classify(&self, cards: &[Card]) -> Option<VideoPokerHand> {
+        let set: CardSet = cards.iter().collect();
+        if cards.len() != 5 || set.len() != 5 || set.bits() & !self.cards().bits() != 0 {
+            return None;
+        }
+        Some(self.classify_set(&set))
+    }
+
+    /// All of the cards of the game's deck
+    fn cards(&self) -> CardSet {
+        let full = CardSet::full();
+        match self {
+            VideoPokerGame::JokerPoker => CardSet::from_bits(full.bits() | JOKER),
+            _ => full,
+        }
+    }
+
+    /// The lowest poker value of a pair that pays, if any
+    fn high_pair(&self) -> Option<u8> {
+        match self {
+            VideoPokerGame::JacksOrBetter | VideoPokerGame::DoubleDoubleBonus => Some(11),
+            VideoPokerGame::JokerPoker => Some(13),
+            VideoPokerGame::DeucesWild => None,
+        }
+    }
+
+    /// Classify a set of five cards of the game's deck
+    fn classify_set(&self, cards: &CardSet) -> VideoPokerHand {
+        let wild_bits = match self {
+            VideoPokerGame::DeucesWild => DEUCES,
+            VideoPokerGame::JokerPoker => JOKER,
+            _ => 0,
+        };
+        let wilds = (cards.bits() & wild_bits).count_ones() as u8;
+        if wilds == 0 {
+            self.classify_natural(cards)
+        } else {
+            self.classify_wild(cards.bits() & !wild_bits, wilds)
+        }
+    }
+
+    /// Classify a hand without wild cards from its poker hand rank
+    fn classify_natural(&self, cards: &CardSet) -> VideoPokerHand {
+        let value = LookupEvaluator::strength(cards);
+        let kicker = |i: u32| (value >> (16 - 4 * i) & 0xf) as u8;
+        match HandRank::from_value(value).map(|r| r.category()) {
+            Some(HandCategory::RoyalFlush) => VideoPokerHand::RoyalFlush,
+            Some(HandCategory::StraightFlush) => VideoPokerHand::StraightFlush,
+            Some(HandCategory::FourOfAKind) if *self == VideoPokerGame::DoubleDoubleBonus => {
+                match (kicker(0), kicker(1)) {
+                    (14, 2..=4) => VideoPokerHand::FourAcesWithLowKicker,
+                    (14, _) => VideoPokerHand::FourAces,
+                    (2..=4, 2..=4) | (2..=4, 14) => VideoPokerHand::FourLowWithKicker,
+                    (2..=4, _) => VideoPokerHand::FourLow,
+                    _ => VideoPokerHand::FourOfAKind,
+                }
+            }
+            Some(HandCategory::FourOfAKind) => VideoPokerHand::FourOfAKind,
+            Some(HandCategory::FullHouse) => VideoPokerHand::FullHouse,
+            Some(HandCategory::Flush) => VideoPokerHand::Flush,
+            Some(HandCategory::Straight) => VideoPokerHand::Straight,
+            Some(HandCategory::ThreeOfAKind) => VideoPokerHand::ThreeOfAKind,
+            Some(HandCategory::TwoPair) => VideoPokerHand::TwoPair,
+            Some(HandCategory::OnePair) if self.high_pair().is_some_and(|low| kicker(0) >= low) => VideoPokerHand::HighPair,
+            _ => VideoPokerHand::Nothing,
+        }
+    }
+
+    /// Classify a hand from the bits of its natural cards and the number of wild cards, each wild card standing in for
+    /// whichever card makes the best hand
+    fn classify_wild(&self, naturals: u64, wilds: u8) -> VideoPokerHand {
+        if *self == VideoPokerGame::DeucesWild && wilds == 4 {
+            return VideoPokerHand::FourDeuces;
+        }
+        let suits = [naturals & SUIT_MASK, naturals >> 13 & SUIT_MASK, naturals >> 26 & SUIT_MASK, naturals >> 39 & SUIT_MASK];
+        // the number of natural cards of each poker value, and the mask of values present (bit value - 2)
+        let mut counts = [0u8; 15];
+        let mut values = 0u16;
+        for suit in suits.iter() {
+            for ordinal in 0..13 {
+                if suit & (1 << ordinal) != 0 {
+                    let value = if ordinal == 0 { 14 } else { ordinal + 1 };
+                    counts[value] += 1;
+                    values |= 1 << (value - 2);
+                }
+            }
+        }
+        let most = counts.iter().copied().max().unwrap_or(0) + wilds;
+        let pairs = counts.iter().filter(|c| **c == 2).count();
+        let distinct = counts.iter().all(|c| *c <= 1);
+        let flush = suits.iter().filter(|s| **s != 0).count() <= 1;
+        let straight = distinct && STRAIGHTS.iter().any(|s| values & !s == 0);
+        let royal = distinct && values & !STRAIGHTS[9] == 0;
+
+        if flush && royal {
+            VideoPokerHand::WildRoyalFlush
+        } else if most >= 5 {
+            VideoPokerHand::FiveOfAKind
+        } else if flush && straight {
+            VideoPokerHand::StraightFlush
+        } else if most == 4 {
+            VideoPokerHand::FourOfAKind
+        } else if pairs == 2 {
+            VideoPokerHand::FullHouse
+        } else if flush {
+            VideoPokerHand::Flush
+        } else if straight {
+            VideoPokerHand::Straight
+        } else if most == 3 {
+            VideoPokerHand::ThreeOfAKind
+        } else if self.high_pair().is_some_and(|low| (low..=14).any(|v| counts[v as usize] > 0)) {
+            VideoPokerHand::HighPair
+        } else {
+            VideoPokerHand::Nothing
+        }
+    }
+}
+
+/// The hands that video poker games pay for, from the strongest down. Each game only makes some of them
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VideoPokerHand {
+    /// A royal flush without wild cards
+    RoyalFlush,
+    /// The four deuces of Deuces Wild
+    FourDeuces,
+    /// A royal flush made with wild cards
+    WildRoyalFlush,
+    FiveOfAKind,
+    StraightFlush,
+    /// Four aces with a two, three or four (Double Double Bonus)
+    FourAcesWithLowKicker,
+    /// Four twos, threes or fours with an ace, two, three or four (Double Double Bonus)
+    FourLowWithKicker,
+    /// Four aces (Double Double Bonus)
+    FourAces,
+    /// Four twos, threes or fours (Double Double Bonus)
+    FourLow,
+    FourOfAKind,
+    FullHouse,
+    Flush,
+    Straight,
+    ThreeOfAKind,
+    TwoPair,
+    /// The lowest paying pair: jacks or better, or kings or better in Joker Poker
+    HighPair,
+    Nothing,
+}
+
+impl std::fmt::Display for VideoPokerHand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            VideoPokerHand::RoyalFlush => "Royal flush",
+            VideoPokerHand::FourDeuces => "Four deuces",
+            VideoPokerHand::WildRoyalFlush => "Wild royal flush",
+            VideoPokerHand::FiveOfAKind => "Five of a kind",
+            VideoPokerHand::StraightFlush => "Straight flush",
+            VideoPokerHand::FourAcesWithLowKicker => "Four aces with 2-4",
+            VideoPokerHand::FourLowWithKicker => "Four 2-4 with A-4",
+            VideoPokerHand::FourAces => "Four aces",
+            VideoPokerHand::FourLow => "Four 2-4",
+            VideoPokerHand::FourOfAKind => "Four of a kind",
+            VideoPokerHand::FullHouse => "Full house",
+            VideoPokerHand::Flush => "Flush",
+            VideoPokerHand::Straight => "Straight",
+            VideoPokerHand::ThreeOfAKind => "Three of a kind",
+            VideoPokerHand::TwoPair => "Two pair",
+            VideoPokerHand::HighPair => "High pair",
+            VideoPokerHand::Nothing => "Nothing",
+        })
+    }
+}
+
+/// The expected return of holding some of the cards of a hand, per unit bet
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct HoldValue {
+    /// The cards held, by position in the hand
+    pub hold: [bool; 5],
+    pub expected: f64,
+}
+
+/// What a video poker game pays for each hand, per unit bet. The royal flush is usually paid at the rate for a
+/// maximum bet (e.g. 4000 for 5 coins, i.e. 800 per unit).
+///
+/// The paytable also solves hands: ```analyze()``` works out the exact expected return of all 32 ways to hold the
+/// cards, drawing every possible replacement from the rest of the deck.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Paytable {
+    game: VideoPokerGame,
+    pays: Vec<(VideoPokerHand, u32)>,
+}
+
+impl Paytable {
+    /// Create a paytable for the game that pays nothing
+    pub fn new(game: VideoPokerGame) -> Self {
+        Paytable {
+            game,
+            pays: Vec::new(),
+        }
+    }
+
+    /// Create the usual full pay table of the game: 9/6 Jacks or Better (99.54%), full pay Deuces Wild (100.76%),
+    /// Kings or Better Joker Poker (100.65%) and 9/6 Double Double Bonus (98.98%)
+    pub fn full_pay(game: VideoPokerGame) -> Self {
+        use VideoPokerHand::*;
+        let pays: &[(VideoPokerHand, u32)] = match game {
+            VideoPokerGame::JacksOrBetter => &[
+                (RoyalFlush, 800), (StraightFlush, 50), (FourOfAKind, 25), (FullHouse, 9), (Flush, 6), (Straight, 4),
+                (ThreeOfAKind, 3), (TwoPair, 2), (HighPair, 1),
+            ],
+            VideoPokerGame::DeucesWild => &[
+                (RoyalFlush, 800), (FourDeuces, 200), (WildRoyalFlush, 25), (FiveOfAKind, 15), (StraightFlush, 9),
+                (FourOfAKind, 5), (FullHouse, 3), (Flush, 2), (Straight, 2), (ThreeOfAKind, 1),
+            ],
+            VideoPokerGame::JokerPoker => &[
+                (RoyalFlush, 800), (FiveOfAKind, 200), (WildRoyalFlush, 100), (StraightFlush, 50), (FourOfAKind, 20),
+                (FullHouse, 7), (Flush, 5), (Straight, 3), (ThreeOfAKind, 2), (TwoPair, 1), (HighPair, 1),
+            ],
+            VideoPokerGame::DoubleDoubleBonus => &[
+                (RoyalFlush, 800), (FourAcesWithLowKicker, 400), (FourLowWithKicker, 160), (FourAces, 160),
+                (FourLow, 80), (FourOfAKind, 50), (StraightFlush, 50), (FullHouse, 9), (Flush, 6), (Straight, 4),
+                (ThreeOfAKind, 3), (TwoPair, 1), (HighPair, 1),
+            ],
+        };
+        pays.iter().fold(Paytable::new(game), |table, (hand, pays)| table.pay(*hand, *pays))
+    }
+
+    /// Set what a hand pays
+    pub fn pay(mut self, hand: VideoPokerHand, pays: u32) -> Self {
+        self.pays.retain(|(h, _)| *h != hand);
+        self.pays.push((hand, pays));
+        self.pays.sort();
+        self
+    }
+
+    /// Get the game
+    pub fn game(&self) -> VideoPokerGame {
+        self.game
+    }
+
+    /// Get the hands that pay and what they pay, strongest first
+    pub fn entries(&self) -> &[(VideoPokerHand, u32)] {
+        &self.pays
+    }
+
+    /// Get what a hand pays
+    pub fn pays(&self, hand: VideoPokerHand) -> u32 {
+        self.pays.iter().find(|(h, _)| *h == hand).map_or(0, |(_, pays)| *pays)
+    }
+
+    /// Classify a five card hand and get what it pays. Returns None for hands the game cannot deal
+    pub fn evaluate(&self, cards: &[Card]) -> Option<(VideoPokerHand, u32)> {
+        let hand = self.game.classify(cards)?;
+        Some((hand, self.pays(hand)))
+    }
+
+    /// Get the exact expected return of holding the cards of the hand, replacing the others from the rest of the deck.
+    /// Returns None for hands the game cannot deal
+    pub fn expected_value(&self, cards: &[Card], hold: [bool; 5]) -> Option<f64> {
+        self.game.classify(cards)?;
+        let pays = self.pays_by_hand();
+        let rest = CardSet::from_bits(self.game.cards().bits() & !cards.iter().collect::<CardSet>().bits());
+        Some(self.hold_value(cards, hold, &rest, &pays))
+    }
+
+    /// Get the exact expected return of all 32 ways to hold the cards of the hand, best first (holding fewer cards
+    /// first among equal returns). Returns None for hands the game cannot deal
+    pub fn analyze(&self, cards: &[Card]) -> Option<Vec<HoldValue>> {
+        self.game.classify(cards)?;
+        let pays = self.pays_by_hand();
+        let rest = CardSet::from_bits(self.game.cards().bits() & !cards.iter().collect::<CardSet>().bits());
+        let mut values: Vec<HoldValue> = (0..32u8)
+            .map(|mask| {
+                let hold = [0, 1, 2, 3, 4].map(|i| mask & (1 << i) != 0);
+                HoldValue {
+                    hold,
+                    expected: self.hold_value(cards, hold, &rest, &pays),
+                }
+            })
+            .collect();
+        values.sort_by(|a, b| {
+            let held = |v: &HoldValue| v.hold.iter().filter(|h| **h).count();
+            b.expected.total_cmp(&a.expected).then(held(a).cmp(&held(b)))
+        });
+        Some(values)
+    }
+
+    /// Get the hold with the best expected return (see ```analyze()```)
+    pub fn best_hold(&self, cards: &[Card]) -> Option<HoldValue> {
+        self.analyze(cards).map(|values| values[0])
+    }
+
+    /// What each hand pays, indexed by the hand
+    fn pays_by_hand(&self) -> [u32; 17] {
+        let mut pays = [0; 17];
+        for (hand, pay) in self.pays.iter() {
+            pays[*hand as usize] = *pay;
+        }
+        pays
+    }
+
+    /// The average pay over every draw to the held cards from the rest of the deck
+    fn hold_value(&self, cards: &[Card], hold: [bool; 5], rest: &CardSet, pays: &[u32; 17]) -> f64 {
+        let held: CardSet = cards.iter().zip(hold.iter()).filter(|(_, h)| **h).map(|(c, _)| c).collect();
+        let (mut total, mut draws) = (0u64, 0u64);
+        for draw in rest.combinations(5 - held.len()) {
+            let hand = CardSet::from_bits(held.bits() | draw.bits());
+            total += pays[self.game.classify_set(&hand) as usize] as u64;
+            draws += 1;
+        }
+        total as f64 / draws as f64
+    }
+}
+
+/// Describes why a video poker operation failed
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VideoPokerError {
+    /// The deck holds fewer than ten cards, duplicates, or cards the game does not use
+    InvalidDeck,
+    /// A hand was dealt and must be drawn to first
+    DrawPending,
+    /// No hand was dealt to draw to
+    NotDealt,
+}
+
+impl std::fmt::Display for VideoPokerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VideoPokerError::InvalidDeck => write!(f, "the deck cannot be used for the game"),
+            VideoPokerError::DrawPending => write!(f, "the hand must be drawn to first"),
+            VideoPokerError::NotDealt => write!(f, "no hand was dealt"),
+        }
+    }
+}
+
+impl std::error::Error for VideoPokerError {}
+
+/// The final hand of a round of video poker and what it pays, per unit bet
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DrawOutcome {
+    pub cards: [Card; 5],
+    pub hand: VideoPokerHand,
+    pub pays: u32,
+}
+
+/// A video poker machine. Each round, ```deal()``` deals five cards, and ```draw()``` replaces the cards that are not
+/// held from the same deck and pays the final hand by the paytable.
+#[derive(Debug, Clone)]
+pub struct VideoPoker {
+    paytable: Paytable,
+    deck: Deck,
+    hand: Option<[Card; 5]>,
+}
+
+impl VideoPoker {
+    /// Create a machine with the paytable
+    pub fn new(paytable: Paytable) -> Self {
+        VideoPoker {
+            paytable,
+            deck: Deck::new_empty(),
+            hand: None,
+        }
+    }
+
+    /// Get the paytable
+    pub fn paytable(&self) -> &Paytable {
+        &self.paytable
+    }
+
+    /// Deal a hand from the top of the deck, which must hold at least ten different cards of the game
+    pub fn deal(&mut self, deck: Deck) -> Result<[Card; 5], VideoPokerError> {
+        if self.hand.is_some() {
+            return Err(VideoPokerError::DrawPending);
+        }
+        let cards: CardSet = deck.iter().collect();
+        if deck.len() < 10 || cards.len() != deck.len() || cards.bits() & !self.paytable.game.cards().bits() != 0 {
+            return Err(VideoPokerError::InvalidDeck);
+        }
+        self.deck = deck;
+        let mut hand = [Card::default(); 5];
+        for card in hand.iter_mut() {
+            *card = self.deck.draw().expect("the deck holds ten cards");
+        }
+        self.hand = Some(hand);
+        Ok(hand)
+    }
+
+    /// Shuffle the game's deck with the random number generator and deal a hand from it
+    pub fn deal_with<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Result<[Card; 5], VideoPokerError> {
+        let mut deck = self.paytable.game.deck();
+        deck.shuffle_with(rng);
+        self.deal(deck)
+    }
+
+    /// Get the hand waiting to be drawn to
+    pub fn hand(&self) -> Option<[Card; 5]> {
+        self.hand
+    }
+
+    /// Replace the cards that are not held, ending the round
+    pub fn draw(&mut self, hold: [bool; 5]) -> Result<DrawOutcome, VideoPokerError> {
+        let mut cards = self.hand.take().ok_or(VideoPokerError::NotDealt)?;
+        for (card, held) in cards.iter_mut().zip(hold.iter()) {
+            if !held {
+                *card = self.deck.draw().expect("the deck holds ten cards");
+            }
+        }
+        let (hand, pays) = self.paytable.evaluate(&cards).expect("the deck was checked");
+        Ok(DrawOutcome { cards, hand, pays })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Paytable, VideoPoker, VideoPokerError, VideoPokerGame, VideoPokerHand};
+    use crate::cards::{cards, Card, Rank, Suit};
+    use crate::deck::Deck;
+    use crate::deck_builder::DeckBuilder;
+
+    #[test]
+    fn classify() {
+        let cases = [
+            (VideoPokerGame::JacksOrBetter, "Jh Jd 3c 5s 9h", VideoPokerHand::HighPair),
+            (VideoPokerGame::JacksOrBetter, "Th Td 3c 5s 9h", VideoPokerHand::Nothing),
+            (VideoPokerGame::JacksOrBetter, "Ah 2d 3c 4s 5h", VideoPokerHand::Straight),
+            (VideoPokerGame::JacksOrBetter, "Ts Js Qs Ks As", VideoPokerHand::RoyalFlush),
+            (VideoPokerGame::DeucesWild, "2c 2d 2h 2s 5h", VideoPokerHand::FourDeuces),
+            (VideoPokerGame::DeucesWild, "2c Th Jh Qh Kh", VideoPokerHand::WildRoyalFlush),
+            (VideoPokerGame::DeucesWild, "2c 2d 7s 7h 7d", VideoPokerHand::FiveOfAKind),
+            (VideoPokerGame::DeucesWild, "2c Ah 3d 4s 5c", VideoPokerHand::Straight),
+            (VideoPokerGame::DeucesWild, "2c 9h 9d 4s 4c", VideoPokerHand::FullHouse),
+            (VideoPokerGame::DeucesWild, "2c 2h 5h 7h 9h", VideoPokerHand::StraightFlush),
+            (VideoPokerGame::DeucesWild, "2c Ah Kd 7s 9c", VideoPokerHand::Nothing),
+            (VideoPokerGame::JokerPoker, "JOKER Ah Ad Ac As", VideoPokerHand::FiveOfAKind),
+            (VideoPokerGame::JokerPoker, "JOKER Kh 3d 5c 9s", VideoPokerHand::HighPair),
+            (VideoPokerGame::JokerPoker, "JOKER Qh 3d 5c 9s", VideoPokerHand::Nothing),
+            (VideoPokerGame::JokerPoker, "Kh Kd 3c 3s 9h", VideoPokerHand::TwoPair),
+            (VideoPokerGame::DoubleDoubleBonus, "Ah Ad Ac As 3c", VideoPokerHand::FourAcesWithLowKicker),
+            (VideoPokerGame::DoubleDoubleBonus, "Ah Ad Ac As 9c", VideoPokerHand::FourAces),
+            (VideoPokerGame::DoubleDoubleBonus, "3h 3d 3c 3s Ac", VideoPokerHand::FourLowWithKicker),
+            (VideoPokerGame::DoubleDoubleBonus, "3h 3d 3c 3s Kc", VideoPokerHand::FourLow),
+            (VideoPokerGame::DoubleDoubleBonus, "9h 9d 9c 9s 3c", VideoPokerHand::FourOfAKind),
+        ];
+        for (game, hand, expected) in cases.iter() {
+            assert_eq!(game.classify(&cards(hand)), Some(*expected), "{} in {:?}", hand, game);
+        }
+        assert_eq!(VideoPokerGame::JacksOrBetter.classify(&cards("JOKER Ah Ad Ac As")), None);
+        assert_eq!(VideoPokerGame::JacksOrBetter.classify(&cards("Ah Ah Ad Ac As")), None);
+        assert_eq!(VideoPokerGame::JacksOrBetter.classify(&cards("Ah Ad Ac As")), None);
+        // a card without a suit is not a joker
+        let mut hand = cards("Ah Kd Qs Jc");
+        hand.insert(0, Card::from_suit_and_rank(Suit::None, Rank::Ace));
+        assert_eq!(VideoPokerGame::JokerPoker.classify(&hand), None);
+    }
+
+    #[test]
+    fn paytables() {
+        let table = Paytable::full_pay(VideoPokerGame::DoubleDoubleBonus);
+        assert_eq!(table.evaluate(&cards("Ah Ad Ac As 2c")), Some((VideoPokerHand::FourAcesWithLowKicker, 400)));
+        assert_eq!(table.pays(VideoPokerHand::TwoPair), 1);
+        assert_eq!(table.entries()[0], (VideoPokerHand::RoyalFlush, 800));
+        // 8/5 Jacks or Better
+        let table = Paytable::full_pay(VideoPokerGame::JacksOrBetter)
+            .pay(VideoPokerHand::FullHouse, 8)
+            .pay(VideoPokerHand::Flush, 5);
+        assert_eq!(table.evaluate(&cards("Kh Kd Kc 3s 3h")), Some((VideoPokerHand::FullHouse, 8)));
+        assert_eq!(table.entries().len(), 9);
+        assert_eq!(Paytable::full_pay(VideoPokerGame::DeucesWild).pays(VideoPokerHand::TwoPair), 0);
+    }
+
+    #[test]
+    fn expected_values() {
+        let table = Paytable::full_pay(VideoPokerGame::JacksOrBetter);
+        // the ten of hearts makes a royal, 8 hearts a flush, 3 tens a straight and 12 cards a high pair
+        let hand = cards("Ah Kh Qh Jh 2c");
+        let four_to_royal = [true, true, true, true, false];
+        assert_eq!(table.expected_value(&hand, four_to_royal), Some(872.0 / 47.0));
+        assert_eq!(table.expected_value(&cards("Ah Kh Qh Jh Th"), [true; 5]), Some(800.0));
+
+        // breaking a pair of jacks for four to the royal, with a jack gone from the deck
+        let best = table.best_hold(&cards("Ah Kh Qh Jh Jc")).unwrap();
+        assert_eq!(best.hold, four_to_royal);
+        assert_eq!(best.expected, 871.0 / 47.0);
+
+        // drawing to four deuces cannot lose them, so holding just the deuces is as good as holding all
+        let table = Paytable::full_pay(VideoPokerGame::DeucesWild);
+        let values = table.analyze(&cards("2c 2d 2h 2s 5h")).unwrap();
+        assert_eq!(values.len(), 32);
+        assert_eq!(values[0].hold, [true, true, true, true, false]);
+        assert_eq!(values[0].expected, 200.0);
+        assert_eq!(values[1].hold, [true; 5]);
+
+        // the last ace makes five of a kind, and the other 47 cards four of a kind
+        let table = Paytable::full_pay(VideoPokerGame::JokerPoker);
+        let ev = table.expected_value(&cards("JOKER Ah Ad Ac 7s"), [true, true, true, true, false]).unwrap();
+        assert_eq!(ev, (200.0 + 47.0 * 20.0) / 48.0);
+    }
+
+    #[test]
+    fn hold_and_draw() {
+        let mut machine = VideoPoker::new(Paytable::full_pay(VideoPokerGame::JokerPoker));
+        assert_eq!(machine.draw([true; 5]), Err(VideoPokerError::NotDealt));
+        assert_eq!(machine.deal(Deck::from_cards(cards("Ah Kh"))), Err(VideoPokerError::InvalidDeck));
+
+        let deck = Deck::stacked(&cards("Ks JOKER 3d Kd 8c 5h Kc 9s"), DeckBuilder::new().jokers(1).build().unwrap()).unwrap();
+        assert_eq!(machine.deal(deck.clone()).unwrap().to_vec(), cards("Ks JOKER 3d Kd 8c"));
+        assert_eq!(machine.deal(deck), Err(VideoPokerError::DrawPending));
+        let outcome = machine.draw([true, true, false, true, false]).unwrap();
+        assert_eq!(outcome.cards.to_vec(), cards("Ks JOKER 5h Kd Kc"));
+        assert_eq!((outcome.hand, outcome.pays), (VideoPokerHand::FourOfAKind, 20));
+        assert_eq!(machine.hand(), None);
+
+        let mut jacks = VideoPoker::new(Paytable::full_pay(VideoPokerGame::JacksOrBetter));
+        let mut deck = VideoPokerGame::JokerPoker.deck();
+        deck.shuffle();
+        assert_eq!(jacks.deal(deck), Err(VideoPokerError::InvalidDeck));
+    }
+}
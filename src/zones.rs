@@ -0,0 +1,378 @@
+use rand::Rng;
+use crate::card_type::CardType;
+use crate::cards::Card;
+use crate::deck::Deck;
+
+/// Identifies a zone within a ```Zones``` collection
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ZoneId(usize);
+
+/// A marker for the state of a ```Zones``` collection, created by ```Zones::snapshot()```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    depth: usize,
+    serial: u64,
+}
+
+/// A single undo step, made up of one or more operations
+#[derive(Debug, Clone)]
+struct Step<C: CardType> {
+    serial: u64,
+    operations: Vec<Operation<C>>,
+}
+
+/// A single reversible operation on the zones
+#[derive(Debug, Clone)]
+enum Operation<C: CardType> {
+    /// A card was moved from a position of one zone to a position of another (or the same) zone
+    Move { from: ZoneId, from_position: usize, to: ZoneId, to_position: usize },
+    /// The top ```count``` cards of a zone were moved to its bottom
+    Cut { zone: ZoneId, count: usize },
+    /// A zone was reordered, e.g. by shuffling
+    Reorder { zone: ZoneId, before: Vec<C>, after: Vec<C> },
+}
+
+/// A collection of decks (zones) such as a stock, a waste pile, foundations and tableau columns, where every operation is
+/// journaled so that it can be undone and redone.
+///
+/// Rather than copying the zones, each step on the undo stack stores the operations needed to reverse it, so moving a
+/// card costs the same no matter how large the zones are. Snapshots are markers into the undo stack, which makes
+/// taking a snapshot free and restoring one proportional to the number of steps taken since.
+///
+/// Each mutating method is a single undo step. Use ```transaction()``` to combine several of them into one step.
+/// Undoing or redoing a step makes the compensating moves through the zones' decks, so decks with a history (see
+/// ```Deck::enable_history()```) record them like any other move and their records still account for every card.
+#[derive(Debug, Clone)]
+pub struct Zones<C: CardType = Card> {
+    zones: Vec<Deck<C>>,
+    undo: Vec<Step<C>>,
+    redo: Vec<Step<C>>,
+    pending: Option<Vec<Operation<C>>>,
+    next_serial: u64,
+}
+
+impl<C: CardType> Default for Zones<C> {
+    fn default() -> Self {
+        Zones {
+            zones: Vec::new(),
+            undo: Vec::new(),
+            redo: Vec::new(),
+            pending: None,
+            next_serial: 1,
+        }
+    }
+}
+
+impl<C: CardType> Zones<C> {
+    /// Create an empty collection of zones
+    pub fn new() -> Self {
+        Zones::default()
+    }
+
+    /// Add a zone to the collection. Adding a zone is not an undoable operation
+    pub fn add_zone(&mut self, deck: Deck<C>) -> ZoneId {
+        self.zones.push(deck);
+        ZoneId(self.zones.len() - 1)
+    }
+
+    /// Get a zone
+    pub fn zone(&self, id: ZoneId) -> &Deck<C> {
+        &self.zones[id.0]
+    }
+
+    /// Get the number of zones
+    pub fn len(&self) -> usize {
+        self.zones.len()
+    }
+
+    /// Returns true if there are no zones
+    pub fn is_empty(&self) -> bool {
+        self.zones.is_empty()
+    }
+
+    /// Move the top card of one zone to the top of another. Returns the card moved, or ```None``` if ```from``` is empty
+    /// or either zone is not in the collection
+    pub fn move_top(&mut self, from: ZoneId, to: ZoneId) -> Option<C> {
+        let position = self.zones.get(from.0)?.len().checked_sub(1)?;
+        self.move_nth(from, position, to)
+    }
+
+    /// Move the card at the nth position of one zone (counted from the bottom) to the top of another. Returns the card
+    /// moved, or ```None```, without changing anything, if there is no such card or either zone is not in the collection
+    pub fn move_nth(&mut self, from: ZoneId, n: usize, to: ZoneId) -> Option<C> {
+        if to.0 >= self.zones.len() {
+            return None;
+        }
+        let card = self.zones.get_mut(from.0)?.draw_nth(n)?;
+        self.zones[to.0].put_on_top(card);
+
+        let to_position = self.zone(to).len() - 1;
+        self.push(Operation::Move { from, from_position: n, to, to_position });
+        Some(card)
+    }
+
+    /// Move the top ```count``` cards of one zone onto another, keeping their order (e.g. moving a run of cards between
+    /// tableau columns). Returns false, without moving anything, if ```from``` holds fewer cards
+    pub fn move_run(&mut self, from: ZoneId, count: usize, to: ZoneId) -> bool {
+        let len = self.zone(from).len();
+        if count > len {
+            return false;
+        }
+        self.transaction(|zones| {
+            for _ in 0..count {
+                zones.move_nth(from, len - count, to);
+            }
+        });
+        true
+    }
+
+    /// Cut a zone, moving its top ```count``` cards to the bottom
+    pub fn cut(&mut self, zone: ZoneId, count: usize) {
+        let count = count.min(self.zone(zone).len());
+        self.zones[zone.0].cut(count);
+        self.push(Operation::Cut { zone, count });
+    }
+
+    /// Shuffle a zone using the supplied random number generator
+    pub fn shuffle_with<R: Rng + ?Sized>(&mut self, zone: ZoneId, rng: &mut R) {
+        let before = self.zone(zone).iter().cloned().collect();
+        self.zones[zone.0].shuffle_with(rng);
+        let after = self.zone(zone).iter().cloned().collect();
+        self.push(Operation::Reorder { zone, before, after });
+    }
+
+    /// Run several operations as a single undo step
+    pub fn transaction<R, F: FnOnce(&mut Self) -> R>(&mut self, f: F) -> R {
+        if self.pending.is_some() {
+            return f(self);
+        }
+
+        self.pending = Some(Vec::new());
+        let result = f(self);
+        if let Some(operations) = self.pending.take() {
+            if !operations.is_empty() {
+                self.push_step(operations);
+            }
+        }
+        result
+    }
+
+    /// Undo the last step. Returns false if there is nothing to undo
+    pub fn undo(&mut self) -> bool {
+        match self.undo.pop() {
+            Some(step) => {
+                for operation in step.operations.iter().rev() {
+                    self.reverse(operation);
+                }
+                self.redo.push(step);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Redo the last undone step. Returns false if there is nothing to redo
+    pub fn redo(&mut self) -> bool {
+        match self.redo.pop() {
+            Some(step) => {
+                for operation in step.operations.iter() {
+                    self.apply(operation);
+                }
+                self.undo.push(step);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Returns true if there is a step that can be undone
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Returns true if there is a step that can be redone
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Take a snapshot of the current state
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            depth: self.undo.len(),
+            serial: self.undo.last().map(|step| step.serial).unwrap_or(0),
+        }
+    }
+
+    /// Restore the state of a snapshot by undoing all steps taken since. The undone steps can be redone. Returns false,
+    /// without changing anything, if the snapshot is not in the undo history (e.g. because its steps were undone)
+    pub fn restore(&mut self, snapshot: Snapshot) -> bool {
+        let reachable = match snapshot.depth {
+            0 => true,
+            depth => self.undo.get(depth - 1).map(|step| step.serial) == Some(snapshot.serial),
+        };
+        if !reachable {
+            return false;
+        }
+        while self.undo.len() > snapshot.depth {
+            self.undo();
+        }
+        true
+    }
+
+    /// Forget all undo and redo steps
+    pub fn clear_history(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    fn push(&mut self, operation: Operation<C>) {
+        match self.pending.as_mut() {
+            Some(operations) => operations.push(operation),
+            None => self.push_step(vec![operation]),
+        }
+    }
+
+    fn push_step(&mut self, operations: Vec<Operation<C>>) {
+        self.undo.push(Step {
+            serial: self.next_serial,
+            operations,
+        });
+        self.next_serial += 1;
+        self.redo.clear();
+    }
+
+    fn apply(&mut self, operation: &Operation<C>) {
+        match operation {
+            Operation::Move { from, from_position, to, to_position } => {
+                if let Some(card) = self.zones[from.0].draw_nth(*from_position) {
+                    self.zones[to.0].insert_nth(*to_position, card);
+                }
+            },
+            Operation::Cut { zone, count } => self.zones[zone.0].cut(*count),
+            Operation::Reorder { zone, after, .. } => self.zones[zone.0].reorder(after.clone()),
+        }
+    }
+
+    fn reverse(&mut self, operation: &Operation<C>) {
+        match operation {
+            Operation::Move { from, from_position, to, to_position } => {
+                if let Some(card) = self.zones[to.0].draw_nth(*to_position) {
+                    self.zones[from.0].insert_nth(*from_position, card);
+                }
+            },
+            Operation::Cut { zone, count } => {
+                let len = self.zone(*zone).len();
+                self.zones[zone.0].cut(len - count);
+            },
+            Operation::Reorder { zone, before, .. } => self.zones[zone.0].reorder(before.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Zones;
+    use crate::cards::{Card, Rank, Suit};
+    use crate::deck::Deck;
+    use crate::history::DeckEvent;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn undo_redo_moves() {
+        let mut zones = Zones::new();
+        let stock = zones.add_zone(Deck::new());
+        let waste = zones.add_zone(Deck::new_empty());
+
+        let king = zones.move_top(stock, waste).unwrap();
+        assert_eq!(king, Card::from_suit_and_rank(Suit::Spades, Rank::King));
+        zones.move_nth(stock, 0, waste);
+        assert_eq!(zones.zone(waste).len(), 2);
+
+        assert!(zones.undo());
+        assert_eq!(zones.zone(stock).peek_bottom(), Some(&Card::new()));
+        assert!(zones.undo());
+        assert!(!zones.undo());
+        assert!(zones.zone(stock).iter().eq(Deck::new().iter()));
+        assert!(zones.zone(waste).is_empty());
+
+        assert!(zones.redo());
+        assert_eq!(zones.zone(waste).peek_top(), Some(&king));
+        assert!(zones.can_redo());
+
+        zones.cut(stock, 5);
+        assert!(!zones.can_redo());
+
+        // a zone of a larger collection
+        let mut other = Zones::new();
+        let other_stock = other.add_zone(Deck::new());
+        other.add_zone(Deck::new_empty());
+        let foreign = other.add_zone(Deck::new_empty());
+        assert_eq!(zones.move_top(stock, foreign), None);
+        assert_eq!(zones.move_nth(foreign, 0, stock), None);
+        assert_eq!(zones.zone(stock).len(), 51);
+        assert!(other.move_top(other_stock, foreign).is_some());
+    }
+
+    #[test]
+    fn undo_redo_is_recorded() {
+        let mut stock = Deck::new();
+        let mut waste = Deck::new_empty();
+        stock.enable_history();
+        waste.enable_history();
+        let mut zones = Zones::new();
+        let stock = zones.add_zone(stock);
+        let waste = zones.add_zone(waste);
+
+        let king = zones.move_top(stock, waste).unwrap();
+        zones.cut(stock, 10);
+        assert!(zones.undo());
+        assert!(zones.undo());
+        let events = |zones: &Zones, zone| zones.zone(zone).history().unwrap().records().iter().map(|r| r.event.clone()).collect::<Vec<_>>();
+        assert_eq!(events(&zones, stock), vec![
+            DeckEvent::Draw { card: king, position: 51 },
+            DeckEvent::Cut { count: 10 },
+            DeckEvent::Cut { count: 41 },
+            DeckEvent::Add { card: king, position: 51 },
+        ]);
+        assert_eq!(events(&zones, waste), vec![DeckEvent::Add { card: king, position: 0 }, DeckEvent::Draw { card: king, position: 0 }]);
+
+        assert!(zones.redo());
+        assert_eq!(events(&zones, waste).last(), Some(&DeckEvent::Add { card: king, position: 0 }));
+        assert!(zones.zone(stock).iter().eq(Deck::new().iter().take(51)));
+    }
+
+    #[test]
+    fn transactions_and_snapshots() {
+        let mut zones = Zones::new();
+        let stock = zones.add_zone(Deck::new());
+        let column = zones.add_zone(Deck::new_empty());
+        let other = zones.add_zone(Deck::new_empty());
+        let start = zones.snapshot();
+
+        zones.transaction(|z| {
+            z.move_top(stock, column);
+            z.move_top(stock, column);
+            z.move_top(stock, column);
+        });
+        let dealt = zones.snapshot();
+        assert!(zones.move_run(column, 2, other));
+        assert!(!zones.move_run(column, 2, other));
+        assert_eq!(zones.zone(other).peek_top(), Some(&Card::from_suit_and_rank(Suit::Spades, Rank::Jack)));
+
+        zones.shuffle_with(stock, &mut StdRng::seed_from_u64(3));
+        zones.cut(stock, 20);
+
+        assert!(zones.restore(dealt));
+        assert_eq!(zones.zone(column).len(), 3);
+        assert!(zones.zone(other).is_empty());
+
+        assert!(zones.undo());
+        assert!(!zones.restore(dealt));
+        assert!(zones.restore(start));
+        assert!(zones.zone(stock).iter().eq(Deck::new().iter()));
+
+        zones.move_top(stock, other);
+        assert!(!zones.restore(dealt));
+    }
+}